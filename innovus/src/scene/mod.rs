@@ -1,62 +1,306 @@
+use std::any::{Any, TypeId};
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+use crate::tools::arena::{ArenaHandle, UnboundedArena};
 
+mod query;
+mod schedule;
 
-type EntityID = u32;
+pub use query::*;
+pub use schedule::*;
 
-pub struct Entity {
-    id: EntityID,
-}
+/// A generational handle to an entity in a [`Scene`]. Handles to despawned entities are never
+/// reused, even if the underlying slot is.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Entity(ArenaHandle);
 
 impl Entity {
+    pub fn handle(&self) -> ArenaHandle {
+        self.0
+    }
 
-    pub fn id(&self) -> EntityID {
-        self.id
+    pub fn slot(&self) -> usize {
+        self.0.slot
     }
+}
 
+/// Marker trait for types which can be attached to entities.
+pub trait Component: Any {}
+
+impl<T: Any> Component for T {}
+
+/// Dense storage for a single component type, indexed by entity slot.
+pub struct ComponentStorage<T> {
+    values: Vec<Option<T>>,
+    count: usize,
 }
 
+impl<T> ComponentStorage<T> {
+    pub fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            count: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
 
+    pub fn get(&self, slot: usize) -> Option<&T> {
+        self.values.get(slot)?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, slot: usize) -> Option<&mut T> {
+        self.values.get_mut(slot)?.as_mut()
+    }
+
+    pub fn insert(&mut self, slot: usize, value: T) -> Option<T> {
+        if slot >= self.values.len() {
+            self.values.resize_with(slot + 1, || None);
+        }
+        let previous = self.values[slot].replace(value);
+        if previous.is_none() {
+            self.count += 1;
+        }
+        previous
+    }
+
+    pub fn remove(&mut self, slot: usize) -> Option<T> {
+        let previous = self.values.get_mut(slot)?.take();
+        if previous.is_some() {
+            self.count -= 1;
+        }
+        previous
+    }
+}
+
+impl<T> Default for ComponentStorage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Type-erased view of a component storage so the scene can clean up after despawned entities
+trait AnyStorage {
+    fn remove_slot(&mut self, slot: usize);
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Component> AnyStorage for RefCell<ComponentStorage<T>> {
+    fn remove_slot(&mut self, slot: usize) {
+        self.get_mut().remove(slot);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// A collection of entities and the components attached to them.
+///
+/// Component storages live behind `RefCell`s, so queries can borrow several component types at
+/// once through a shared reference. Spawning, despawning and attaching components require
+/// exclusive access.
 pub struct Scene {
-    entities: Vec<EntityID>,
+    entities: UnboundedArena<()>,
+    components: HashMap<TypeId, Box<dyn AnyStorage>>,
 }
 
 impl Scene {
+    pub fn new() -> Self {
+        Self {
+            entities: UnboundedArena::new(),
+            components: HashMap::new(),
+        }
+    }
 
-    pub fn create_entity(&mut self) -> Entity {
-        const FIRST_ENTITY_ID: EntityID = 1;
-        let mut new_id: EntityID = FIRST_ENTITY_ID;
-        let mut insert_index = self.entities.len();
-        match self.entities.last() {
-            None => {},
-            Some(last_id) => {
-                new_id = *last_id;
-                for (index, id) in self.entities.iter().enumerate() {
-                    if *id != FIRST_ENTITY_ID + index as EntityID {
-                        new_id = FIRST_ENTITY_ID + index as EntityID;
-                        insert_index = index;
-                        break;
-                    }
-                }
-            }
+    pub fn spawn(&mut self) -> Entity {
+        Entity(self.entities.insert(()))
+    }
+
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if self.entities.remove(entity.0).is_none() {
+            return false;
+        }
+        for storage in self.components.values_mut() {
+            storage.remove_slot(entity.slot());
+        }
+        true
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.get(entity.0).is_some()
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.values().map(|(handle, _)| Entity(handle))
+    }
+
+    pub fn entity_count(&self) -> usize {
+        self.entities.values().count()
+    }
+
+    /// Attach a component to an entity, returning the component it replaced (if any).
+    /// Returns `Err` with the component if the entity no longer exists.
+    pub fn insert<T: Component>(&mut self, entity: Entity, component: T) -> Result<Option<T>, T> {
+        if !self.contains(entity) {
+            return Err(component);
+        }
+        let storage = self.components
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(RefCell::new(ComponentStorage::<T>::new())));
+        let storage = storage.as_any()
+            .downcast_ref::<RefCell<ComponentStorage<T>>>()
+            .expect("component storage has mismatched type");
+        Ok(storage.borrow_mut().insert(entity.slot(), component))
+    }
+
+    pub fn remove<T: Component>(&mut self, entity: Entity) -> Option<T> {
+        if !self.contains(entity) {
+            return None;
         }
-        self.entities.insert(insert_index, new_id);
-        Entity{ id: new_id }
+        self.storage_mut::<T>()?.remove(entity.slot())
+    }
+
+    pub fn has<T: Component>(&self, entity: Entity) -> bool {
+        self.contains(entity) && self.storage::<T>()
+            .is_some_and(|storage| storage.get(entity.slot()).is_some())
+    }
+
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<Ref<'_, T>> {
+        if !self.contains(entity) {
+            return None;
+        }
+        Ref::filter_map(self.storage::<T>()?, |storage| storage.get(entity.slot())).ok()
+    }
+
+    pub fn get_mut<T: Component>(&self, entity: Entity) -> Option<RefMut<'_, T>> {
+        if !self.contains(entity) {
+            return None;
+        }
+        RefMut::filter_map(self.storage_mut::<T>()?, |storage| storage.get_mut(entity.slot())).ok()
+    }
+
+    fn storage_cell<T: Component>(&self) -> Option<&RefCell<ComponentStorage<T>>> {
+        self.components
+            .get(&TypeId::of::<T>())?
+            .as_any()
+            .downcast_ref()
     }
 
-    pub fn get_entity(id: EntityID) -> Entity {
-        Entity{ id }
+    /// Borrow the storage for a component type. Panics if the storage is already mutably borrowed.
+    pub fn storage<T: Component>(&self) -> Option<Ref<'_, ComponentStorage<T>>> {
+        Some(self.storage_cell::<T>()?.borrow())
     }
 
-    pub fn remove_entity(&mut self, entity: Entity) {
-        self.remove_entity_id(entity.id());
+    /// Mutably borrow the storage for a component type. Panics if the storage is already borrowed.
+    pub fn storage_mut<T: Component>(&self) -> Option<RefMut<'_, ComponentStorage<T>>> {
+        Some(self.storage_cell::<T>()?.borrow_mut())
     }
 
-    pub fn remove_entity_id(&mut self, entity_id: EntityID) {
-        for (index, id) in self.entities.iter().enumerate() {
-            if *id == entity_id {
-                self.entities.remove(index);
-                return;
+    /// Call `f` for every entity which has all of the components in `Q`.
+    ///
+    /// `Q` is a component reference or a tuple of them, e.g. `(&Position, &mut Velocity)`.
+    /// Requesting the same component type twice with `&mut` will panic.
+    pub fn for_each<Q: Query>(&self, mut f: impl FnMut(Entity, Q::Item<'_>)) {
+        let Some(mut fetch) = Q::fetch(self) else {
+            return;
+        };
+        for (handle, _) in self.entities.values() {
+            if let Some(item) = Q::get(&mut fetch, handle.slot) {
+                f(Entity(handle), item);
             }
         }
     }
 
-}
\ No newline at end of file
+    /// Collect every entity which has all of the components in `Q`.
+    pub fn query_entities<Q: Query>(&self) -> Vec<Entity> {
+        let mut entities = Vec::new();
+        self.for_each::<Q>(|entity, _| entities.push(entity));
+        entities
+    }
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Debug, PartialEq)]
+    struct Name(&'static str);
+
+    #[test]
+    fn components_can_be_inserted_replaced_and_removed() {
+        let mut scene = Scene::new();
+        let entity = scene.spawn();
+        assert!(!scene.has::<Position>(entity));
+        assert_eq!(scene.insert(entity, Position(1)), Ok(None));
+        assert_eq!(scene.insert(entity, Position(2)), Ok(Some(Position(1))));
+        scene.get_mut::<Position>(entity).unwrap().0 += 1;
+        assert_eq!(*scene.get::<Position>(entity).unwrap(), Position(3));
+        assert!(scene.get::<Name>(entity).is_none());
+
+        assert_eq!(scene.remove::<Position>(entity), Some(Position(3)));
+        assert_eq!(scene.remove::<Position>(entity), None);
+        assert!(scene.storage::<Position>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn stale_entities_are_rejected_after_their_slot_is_reused() {
+        let mut scene = Scene::new();
+        let stale = scene.spawn();
+        scene.insert(stale, Position(1)).unwrap();
+        assert!(scene.despawn(stale));
+        assert!(!scene.despawn(stale));
+
+        let entity = scene.spawn();
+        assert_eq!(entity.slot(), stale.slot());
+        assert_ne!(entity, stale);
+        assert!(scene.contains(entity));
+        assert!(!scene.contains(stale));
+        // Components of the despawned entity don't carry over to the new one
+        assert!(!scene.has::<Position>(entity));
+
+        assert_eq!(scene.insert(stale, Position(2)), Err(Position(2)));
+        scene.insert(entity, Position(3)).unwrap();
+        assert!(!scene.has::<Position>(stale));
+        assert!(scene.get::<Position>(stale).is_none());
+        assert!(scene.get_mut::<Position>(stale).is_none());
+        assert_eq!(scene.remove::<Position>(stale), None);
+        assert!(!scene.despawn(stale));
+        assert_eq!(*scene.get::<Position>(entity).unwrap(), Position(3));
+        assert_eq!(scene.entities().collect::<Vec<_>>(), [entity]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn aliased_mutable_query_panics() {
+        let mut scene = Scene::new();
+        let entity = scene.spawn();
+        scene.insert(entity, Position(0)).unwrap();
+        scene.for_each::<(&mut Position, &mut Position)>(|_, _| {});
+    }
+
+    #[test]
+    #[should_panic]
+    fn shared_and_mutable_query_of_the_same_component_panics() {
+        let mut scene = Scene::new();
+        let entity = scene.spawn();
+        scene.insert(entity, Position(0)).unwrap();
+        scene.for_each::<(&Position, &mut Position)>(|_, _| {});
+    }
+}
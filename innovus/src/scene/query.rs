@@ -0,0 +1,148 @@
+use std::cell::{Ref, RefMut};
+use super::*;
+
+/// A combination of components which can be fetched for each entity in a [`Scene`].
+///
+/// Implemented for `&T`, `&mut T`, `Option<&T>`, `Option<&mut T>` and tuples of up to
+/// eight queries.
+pub trait Query {
+    type Fetch<'s>;
+    type Item<'f>;
+
+    /// Borrow the storages needed by this query, or `None` if no entity can possibly match.
+    fn fetch(scene: &Scene) -> Option<Self::Fetch<'_>>;
+
+    fn get<'f>(fetch: &'f mut Self::Fetch<'_>, slot: usize) -> Option<Self::Item<'f>>;
+}
+
+impl<T: Component> Query for &T {
+    type Fetch<'s> = Ref<'s, ComponentStorage<T>>;
+    type Item<'f> = &'f T;
+
+    fn fetch(scene: &Scene) -> Option<Self::Fetch<'_>> {
+        scene.storage::<T>()
+    }
+
+    fn get<'f>(fetch: &'f mut Self::Fetch<'_>, slot: usize) -> Option<Self::Item<'f>> {
+        fetch.get(slot)
+    }
+}
+
+impl<T: Component> Query for &mut T {
+    type Fetch<'s> = RefMut<'s, ComponentStorage<T>>;
+    type Item<'f> = &'f mut T;
+
+    fn fetch(scene: &Scene) -> Option<Self::Fetch<'_>> {
+        scene.storage_mut::<T>()
+    }
+
+    fn get<'f>(fetch: &'f mut Self::Fetch<'_>, slot: usize) -> Option<Self::Item<'f>> {
+        fetch.get_mut(slot)
+    }
+}
+
+impl<T: Component> Query for Option<&T> {
+    type Fetch<'s> = Option<Ref<'s, ComponentStorage<T>>>;
+    type Item<'f> = Option<&'f T>;
+
+    fn fetch(scene: &Scene) -> Option<Self::Fetch<'_>> {
+        Some(scene.storage::<T>())
+    }
+
+    fn get<'f>(fetch: &'f mut Self::Fetch<'_>, slot: usize) -> Option<Self::Item<'f>> {
+        Some(fetch.as_ref().and_then(|storage| storage.get(slot)))
+    }
+}
+
+impl<T: Component> Query for Option<&mut T> {
+    type Fetch<'s> = Option<RefMut<'s, ComponentStorage<T>>>;
+    type Item<'f> = Option<&'f mut T>;
+
+    fn fetch(scene: &Scene) -> Option<Self::Fetch<'_>> {
+        Some(scene.storage_mut::<T>())
+    }
+
+    fn get<'f>(fetch: &'f mut Self::Fetch<'_>, slot: usize) -> Option<Self::Item<'f>> {
+        Some(fetch.as_mut().and_then(|storage| storage.get_mut(slot)))
+    }
+}
+
+macro_rules! impl_tuple_query {
+    ($($name:ident),+) => {
+        impl<$($name: Query),+> Query for ($($name,)+) {
+            type Fetch<'s> = ($($name::Fetch<'s>,)+);
+            type Item<'f> = ($($name::Item<'f>,)+);
+
+            fn fetch(scene: &Scene) -> Option<Self::Fetch<'_>> {
+                Some(($($name::fetch(scene)?,)+))
+            }
+
+            #[allow(non_snake_case)]
+            fn get<'f>(fetch: &'f mut Self::Fetch<'_>, slot: usize) -> Option<Self::Item<'f>> {
+                let ($($name,)+) = fetch;
+                Some(($($name::get($name, slot)?,)+))
+            }
+        }
+    };
+}
+
+impl_tuple_query!(A);
+impl_tuple_query!(A, B);
+impl_tuple_query!(A, B, C);
+impl_tuple_query!(A, B, C, D);
+impl_tuple_query!(A, B, C, D, E);
+impl_tuple_query!(A, B, C, D, E, F);
+impl_tuple_query!(A, B, C, D, E, F, G);
+impl_tuple_query!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Position(i32);
+
+    #[derive(Debug)]
+    struct Velocity(i32);
+
+    #[derive(Debug)]
+    struct Frozen;
+
+    #[test]
+    fn queries_only_match_entities_with_every_component() {
+        let mut scene = Scene::new();
+        let moving = scene.spawn();
+        scene.insert(moving, Position(0)).unwrap();
+        scene.insert(moving, Velocity(2)).unwrap();
+        let still = scene.spawn();
+        scene.insert(still, Position(5)).unwrap();
+        let bare = scene.spawn();
+
+        scene.for_each::<(&mut Position, &Velocity)>(|_, (position, velocity)| position.0 += velocity.0);
+        assert_eq!(scene.get::<Position>(moving).unwrap().0, 2);
+        assert_eq!(scene.get::<Position>(still).unwrap().0, 5);
+
+        assert_eq!(scene.query_entities::<&Position>(), [moving, still]);
+        assert_eq!(scene.query_entities::<(&Position, &Velocity)>(), [moving]);
+        assert_eq!(scene.query_entities::<Option<&Position>>(), [moving, still, bare]);
+        assert!(scene.query_entities::<&Frozen>().is_empty());
+        assert!(scene.query_entities::<(&Position, &Frozen)>().is_empty());
+    }
+
+    #[test]
+    fn optional_components_are_fetched_when_present() {
+        let mut scene = Scene::new();
+        let moving = scene.spawn();
+        scene.insert(moving, Position(0)).unwrap();
+        scene.insert(moving, Velocity(3)).unwrap();
+        let still = scene.spawn();
+        scene.insert(still, Position(1)).unwrap();
+
+        scene.for_each::<(&mut Position, Option<&Velocity>, Option<&mut Frozen>)>(|_, (position, velocity, frozen)| {
+            assert!(frozen.is_none());
+            position.0 += velocity.map_or(-1, |velocity| velocity.0);
+        });
+        assert_eq!(scene.get::<Position>(moving).unwrap().0, 3);
+        assert_eq!(scene.get::<Position>(still).unwrap().0, 0);
+    }
+}
@@ -0,0 +1,187 @@
+use super::*;
+
+type System<C> = Box<dyn FnMut(&mut Scene, &mut C, &mut Commands)>;
+type Command = Box<dyn FnOnce(&mut Scene)>;
+
+/// Structural changes to a scene which are deferred until the current system finishes,
+/// since entities can't be spawned or despawned while a query is borrowing the scene.
+pub struct Commands {
+    queue: Vec<Command>,
+}
+
+impl Commands {
+    pub fn new() -> Self {
+        Self {
+            queue: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, command: impl FnOnce(&mut Scene) + 'static) {
+        self.queue.push(Box::new(command));
+    }
+
+    pub fn despawn(&mut self, entity: Entity) {
+        self.push(move |scene| {
+            scene.despawn(entity);
+        });
+    }
+
+    pub fn insert<T: Component>(&mut self, entity: Entity, component: T) {
+        self.push(move |scene| {
+            let _ = scene.insert(entity, component);
+        });
+    }
+
+    pub fn remove<T: Component>(&mut self, entity: Entity) {
+        self.push(move |scene| {
+            scene.remove::<T>(entity);
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn apply(&mut self, scene: &mut Scene) {
+        for command in self.queue.drain(..) {
+            command(scene);
+        }
+    }
+}
+
+impl Default for Commands {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An ordered list of systems which are run over a scene, each receiving a shared context `C`
+/// (e.g. the frame's delta time or input state).
+pub struct Schedule<C = ()> {
+    systems: Vec<(String, System<C>)>,
+    commands: Commands,
+}
+
+impl<C> Schedule<C> {
+    pub fn new() -> Self {
+        Self {
+            systems: Vec::new(),
+            commands: Commands::new(),
+        }
+    }
+
+    pub fn with_system(mut self, name: impl Into<String>, system: impl FnMut(&mut Scene, &mut C, &mut Commands) + 'static) -> Self {
+        self.add_system(name, system);
+        self
+    }
+
+    pub fn add_system(&mut self, name: impl Into<String>, system: impl FnMut(&mut Scene, &mut C, &mut Commands) + 'static) {
+        self.systems.push((name.into(), Box::new(system)));
+    }
+
+    /// Insert a system so that it runs immediately before the system named `before`.
+    /// Returns `Err` with the name if no such system exists.
+    pub fn add_system_before(&mut self, before: &str, name: impl Into<String>, system: impl FnMut(&mut Scene, &mut C, &mut Commands) + 'static) -> Result<(), String> {
+        let index = self.system_index(before)
+            .ok_or_else(|| format!("no system named '{before}'"))?;
+        self.systems.insert(index, (name.into(), Box::new(system)));
+        Ok(())
+    }
+
+    pub fn remove_system(&mut self, name: &str) -> bool {
+        if let Some(index) = self.system_index(name) {
+            drop(self.systems.remove(index));
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    pub fn system_names(&self) -> impl Iterator<Item = &str> {
+        self.systems.iter().map(|(name, _)| name.as_str())
+    }
+
+    fn system_index(&self, name: &str) -> Option<usize> {
+        self.systems.iter().position(|(system_name, _)| system_name == name)
+    }
+
+    /// Run every system in order. Commands queued by a system are applied before the next one runs.
+    pub fn run(&mut self, scene: &mut Scene, context: &mut C) {
+        for (_, system) in &mut self.systems {
+            system(scene, context, &mut self.commands);
+            self.commands.apply(scene);
+        }
+    }
+}
+
+impl<C> Default for Schedule<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Health(i32);
+
+    #[test]
+    fn commands_are_deferred_until_applied() {
+        let mut scene = Scene::new();
+        let entity = scene.spawn();
+        let mut commands = Commands::new();
+        commands.insert(entity, Health(10));
+        commands.remove::<Health>(entity);
+        commands.insert(entity, Health(5));
+        assert!(!scene.has::<Health>(entity));
+
+        commands.apply(&mut scene);
+        assert!(commands.is_empty());
+        assert_eq!(*scene.get::<Health>(entity).unwrap(), Health(5));
+
+        commands.despawn(entity);
+        assert!(scene.contains(entity));
+        commands.apply(&mut scene);
+        assert!(!scene.contains(entity));
+    }
+
+    #[test]
+    fn systems_run_in_order_and_see_earlier_commands() {
+        let mut scene = Scene::new();
+        let mut schedule = Schedule::<Vec<String>>::new()
+            .with_system("spawn", |scene, log, commands| {
+                let entity = scene.spawn();
+                commands.insert(entity, Health(2));
+                log.push("spawn".to_string());
+            })
+            .with_system("damage", |scene, log, commands| {
+                scene.for_each::<(&mut Health,)>(|entity, (health,)| {
+                    health.0 -= 1;
+                    if health.0 <= 0 {
+                        commands.despawn(entity);
+                    }
+                });
+                log.push("damage".to_string());
+            });
+        schedule.add_system_before("damage", "count", |scene, log, _| {
+            log.push(format!("count {}", scene.query_entities::<&Health>().len()));
+        }).unwrap();
+        assert!(schedule.add_system_before("missing", "never", |_, _, _| {}).is_err());
+        assert_eq!(schedule.system_names().collect::<Vec<_>>(), ["spawn", "count", "damage"]);
+
+        let mut log = Vec::new();
+        schedule.run(&mut scene, &mut log);
+        assert_eq!(log, ["spawn", "count 1", "damage"]);
+
+        assert!(schedule.remove_system("spawn"));
+        assert!(!schedule.remove_system("spawn"));
+        log.clear();
+        schedule.run(&mut scene, &mut log);
+        schedule.run(&mut scene, &mut log);
+        assert_eq!(log, ["count 1", "damage", "count 0", "damage"]);
+        assert_eq!(scene.entity_count(), 0);
+    }
+}
@@ -1,12 +1,20 @@
 use std::cmp::Ordering;
 use super::*;
 
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, HashMap};
 use crate::tools::arena::{UnboundedArena, ArenaHandle};
 
 // TODO: seems hacky. how else to deal with FP precision?
 pub const COLLISION_TOLERANCE: f32 = 1.0e-5;
 
+/// The collision layer assigned to colliders by default.
+pub const DEFAULT_LAYERS: u32 = 1;
+/// A layer mask which accepts every collision layer.
+pub const ALL_LAYERS: u32 = u32::MAX;
+/// The minimum impact speed required for a collision to bounce. Slower impacts are treated as
+/// perfectly inelastic, which keeps resting contacts from jittering.
+pub const RESTITUTION_THRESHOLD: f32 = 1.0;
+
 /// The side/corner of collider 1 which will collide with collider 2.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum CollisionSide {
@@ -49,6 +57,47 @@ impl CollisionSide {
         }
     }
 
+    /// Determine the side of collider 1 which collides, given a collision normal pointing from
+    /// collider 1 toward collider 2. Only edges are returned, using the dominant axis of the normal.
+    pub fn from_normal(normal: Vector<f32, 2>) -> Self {
+        if normal.x().abs() > normal.y().abs() {
+            if normal.x() > 0.0 { Self::Right } else { Self::Left }
+        }
+        else {
+            if normal.y() > 0.0 { Self::Top } else { Self::Bottom }
+        }
+    }
+
+    pub fn is_corner(&self) -> bool {
+        matches!(self, Self::BottomLeft | Self::BottomRight | Self::TopLeft | Self::TopRight)
+    }
+
+    /// Whether this side is the given side, or a corner adjacent to it.
+    pub fn includes(&self, side: Self) -> bool {
+        *self == side || match self {
+            Self::BottomLeft => side == Self::Bottom || side == Self::Left,
+            Self::BottomRight => side == Self::Bottom || side == Self::Right,
+            Self::TopLeft => side == Self::Top || side == Self::Left,
+            Self::TopRight => side == Self::Top || side == Self::Right,
+            _ => false,
+        }
+    }
+
+    /// The direction of collider 2 relative to collider 1. Corners have a component of
+    /// magnitude 1 along both axes, so their normals are not unit vectors.
+    pub fn normal(&self) -> Vector<f32, 2> {
+        match self {
+            Self::Left => Vector([-1.0, 0.0]),
+            Self::Right => Vector([1.0, 0.0]),
+            Self::Bottom => Vector([0.0, -1.0]),
+            Self::Top => Vector([0.0, 1.0]),
+            Self::BottomLeft => Vector([-1.0, -1.0]),
+            Self::BottomRight => Vector([1.0, -1.0]),
+            Self::TopLeft => Vector([-1.0, 1.0]),
+            Self::TopRight => Vector([1.0, 1.0]),
+        }
+    }
+
     pub fn opposite(&self) -> Self {
         match self {
             Self::Left => Self::Right,
@@ -65,15 +114,17 @@ impl CollisionSide {
 
 #[derive(Debug)]
 pub struct Collision {
-    pub index_1: usize,
-    pub index_2: usize,
+    pub handle_1: ArenaHandle,
+    pub handle_2: ArenaHandle,
     pub time: f32,
     pub side: CollisionSide,
+    /// The direction of collider 2 relative to collider 1 at the point of contact.
+    pub normal: Vector<f32, 2>,
 }
 
 impl PartialEq for Collision {
     fn eq(&self, other: &Self) -> bool {
-        self.time == other.time && self.side == other.side
+        self.cmp(other) == Ordering::Equal
     }
 }
 
@@ -81,27 +132,58 @@ impl Eq for Collision {}
 
 impl PartialOrd for Collision {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collision {
+    fn cmp(&self, other: &Self) -> Ordering {
         // Since std::collections::BinaryHeap is a max-heap, and we want the earliest collisions
         // processed first, collisions with a greater time are treated as lesser priority
         // (hence the call to reverse()).
         // Comparing the collision side ensures that when there is a tie for time,
-        // corner hits are checked after edge hits.
-        Some(self.time.partial_cmp(&other.time)?
+        // corner hits are checked after edge hits. Any remaining ties are broken by the handles,
+        // so the order never depends on the order in which collisions were found.
+        // Unwrapping shouldn't be problematic because self.time should be in the interval [0, 1]
+        self.time.partial_cmp(&other.time).unwrap()
             .then_with(|| self.side.cmp(&other.side))
-            .reverse())
+            .then_with(|| self.handle_1.slot.cmp(&other.handle_1.slot))
+            .then_with(|| self.handle_2.slot.cmp(&other.handle_2.slot))
+            .reverse()
     }
 }
 
-impl Ord for Collision {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // This shouldn't be problematic because self.time should be in the interval [0, 1]
-        self.partial_cmp(other).unwrap()
-    }
+/// The solid region of a collider within its rectangle.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ColliderShape {
+    /// The whole rectangle is solid.
+    Rectangle,
+    /// Only the triangle below the diagonal of the rectangle is solid, forming a ramp which rises
+    /// toward the right if `rises_right` is true, or toward the left otherwise. The angle of the
+    /// slope is determined by the proportions of the rectangle.
+    Slope { rises_right: bool },
 }
 
 #[derive(Clone, Debug)]
 pub struct Collider {
     pub fixed: bool,
+    /// Sensors detect overlapping colliders, but are never involved in collision resolution.
+    pub sensor: bool,
+    /// The collision layers this collider belongs to.
+    pub layers: u32,
+    /// The collision layers this collider can interact with.
+    pub mask: u32,
+    /// Ignored for fixed colliders, which behave as if their mass were infinite.
+    pub mass: f32,
+    /// How much of the impact speed is retained after a collision, from 0 (none) to 1 (all).
+    /// The greater restitution of the two colliders is used.
+    pub restitution: f32,
+    /// The surface friction coefficient. The coefficients of both colliders are multiplied.
+    pub friction: f32,
+    pub shape: ColliderShape,
+    /// If set, this collider only blocks colliders hitting it on the given side (or the corners
+    /// of that side), allowing them to pass through from any other direction.
+    pub one_way: Option<CollisionSide>,
     pub rectangle: Rectangle<f32>,
     pub velocity: Vector<f32, 2>,
     pub hit_left: bool,
@@ -114,6 +196,14 @@ impl Collider {
     pub fn new(rectangle: Rectangle<f32>, velocity: Vector<f32, 2>) -> Self {
         Self {
             fixed: false,
+            sensor: false,
+            layers: DEFAULT_LAYERS,
+            mask: ALL_LAYERS,
+            mass: 1.0,
+            restitution: 0.0,
+            friction: 0.0,
+            shape: ColliderShape::Rectangle,
+            one_way: None,
             rectangle,
             velocity,
             hit_left: false,
@@ -126,6 +216,14 @@ impl Collider {
     pub fn new_fixed(rect: Rectangle<f32>) -> Self {
         Self {
             fixed: true,
+            sensor: false,
+            layers: DEFAULT_LAYERS,
+            mask: ALL_LAYERS,
+            mass: 1.0,
+            restitution: 0.0,
+            friction: 0.0,
+            shape: ColliderShape::Rectangle,
+            one_way: None,
             rectangle: rect,
             velocity: Vector::zero(),
             hit_left: false,
@@ -135,6 +233,109 @@ impl Collider {
         }
     }
 
+    pub fn new_sensor(rect: Rectangle<f32>) -> Self {
+        Self {
+            sensor: true,
+            ..Self::new_fixed(rect)
+        }
+    }
+
+    pub fn with_layers(mut self, layers: u32, mask: u32) -> Self {
+        self.layers = layers;
+        self.mask = mask;
+        self
+    }
+
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    pub fn with_material(mut self, restitution: f32, friction: f32) -> Self {
+        self.restitution = restitution;
+        self.friction = friction;
+        self
+    }
+
+    pub fn with_shape(mut self, shape: ColliderShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn with_one_way(mut self, side: CollisionSide) -> Self {
+        self.one_way = Some(side);
+        self
+    }
+
+    pub fn inverse_mass(&self) -> f32 {
+        if self.fixed || self.mass <= 0.0 {
+            0.0
+        }
+        else {
+            1.0 / self.mass
+        }
+    }
+
+    /// Whether this collider and `other` are allowed to collide, based on their layers and masks.
+    pub fn interacts_with(&self, other: &Self) -> bool {
+        self.layers & other.mask != 0 && other.layers & self.mask != 0
+    }
+
+    /// Whether this collider, if it is a sensor, would detect `other` when overlapping.
+    /// Unlike [`Collider::interacts_with`], this only depends on the sensor's mask.
+    pub fn detects(&self, other: &Self) -> bool {
+        self.sensor && !other.sensor && self.mask & other.layers != 0
+    }
+
+    /// Whether this collider blocks a collision on the given side/corner of itself.
+    pub fn blocks_on(&self, side: CollisionSide) -> bool {
+        self.one_way.is_none_or(|one_way| side.includes(one_way))
+    }
+
+    /// The outward normal of the sloped surface, if this collider is a slope.
+    pub fn slope_normal(&self) -> Option<Vector<f32, 2>> {
+        match self.shape {
+            ColliderShape::Rectangle => None,
+            ColliderShape::Slope { rises_right } => {
+                let span = self.rectangle.span();
+                let normal = if rises_right {
+                    Vector([-span.y(), span.x()])
+                } else {
+                    Vector([span.y(), span.x()])
+                };
+                Some(normal.normalized())
+            }
+        }
+    }
+
+    /// Project the solid region of this collider onto an axis, returning the interval it covers.
+    fn project(&self, axis: Vector<f32, 2>) -> (f32, f32) {
+        let rectangle = &self.rectangle;
+        let corners = [
+            rectangle.min,
+            rectangle.max_x_min_y(),
+            rectangle.max,
+            rectangle.min_x_max_y(),
+        ];
+        // A slope is missing the top corner on its lower side
+        let missing_index = match self.shape {
+            ColliderShape::Rectangle => None,
+            ColliderShape::Slope { rises_right: true } => Some(3),
+            ColliderShape::Slope { rises_right: false } => Some(2),
+        };
+        let mut min = f32::INFINITY;
+        let mut max = f32::NEG_INFINITY;
+        for (index, corner) in corners.into_iter().enumerate() {
+            if missing_index == Some(index) {
+                continue;
+            }
+            let projection = corner.dot(axis);
+            min = min.min(projection);
+            max = max.max(projection);
+        }
+        (min, max)
+    }
+
     pub fn stop(&mut self) {
         self.velocity = Vector::zero();
     }
@@ -191,7 +392,18 @@ impl Collider {
         phase
     }
 
-    pub fn sweep_collision(&self, other: &Self, dt: f32) -> Option<(f32, CollisionSide)> {
+    /// Find the time, side (of this collider) and normal of the first collision between this
+    /// collider and `other` within `dt`, if any.
+    pub fn sweep_collision(&self, other: &Self, dt: f32) -> Option<(f32, CollisionSide, Vector<f32, 2>)> {
+        if self.shape == ColliderShape::Rectangle && other.shape == ColliderShape::Rectangle {
+            self.sweep_rectangles(other, dt).map(|(time, side)| (time, side, side.normal()))
+        }
+        else {
+            self.sweep_shapes(other, dt)
+        }
+    }
+
+    fn sweep_rectangles(&self, other: &Self, dt: f32) -> Option<(f32, CollisionSide)> {
         // Determine the time of collision in the x-direction, or infinity if no collision
         let mut x_time = match self.velocity.x().partial_cmp(&other.velocity.x()) {
             Some(Ordering::Less) => (other.rectangle.max.x() - self.rectangle.min.x())
@@ -246,28 +458,258 @@ impl Collider {
         }
     }
 
-    pub fn collision_velocity(&self, other: &Self) -> Vector<f32, 2> {
-        if self.fixed || other.fixed {
-            Vector::zero()
+    fn sweep_shapes(&self, other: &Self, dt: f32) -> Option<(f32, CollisionSide, Vector<f32, 2>)> {
+        // Swept separating axis test: the shapes can only overlap while their projections overlap
+        // on every candidate axis, so the collision begins at the latest entry time among the axes
+        // (as long as that comes before the earliest exit time)
+        let relative_velocity = self.velocity - other.velocity;
+        let slope_axes: Vec<Vector<f32, 2>> = self.slope_normal().into_iter()
+            .chain(other.slope_normal())
+            .collect();
+        let mut entry_time = f32::NEG_INFINITY;
+        let mut exit_time = f32::INFINITY;
+        let mut normal = Vector::zero();
+        for (index, axis) in [Vector([1.0, 0.0]), Vector([0.0, 1.0])].into_iter().chain(slope_axes).enumerate() {
+            let (min_1, max_1) = self.project(axis);
+            let (min_2, max_2) = other.project(axis);
+            let speed = relative_velocity.dot(axis);
+            let (axis_entry, axis_exit, axis_normal) = if speed > 0.0 {
+                ((min_2 - max_1) / speed, (max_2 - min_1) / speed, axis)
+            }
+            else if speed < 0.0 {
+                ((max_2 - min_1) / speed, (min_2 - max_1) / speed, -axis)
+            }
+            else if max_1 <= min_2 || min_1 >= max_2 {
+                // Separated on this axis and not moving closer, so there can't be a collision
+                return None;
+            }
+            else {
+                continue;
+            };
+
+            // When a slope ties with (or nearly ties with) an axis of the rectangle, the slope
+            // takes precedence so that colliders run smoothly up onto ramps instead of catching
+            // on the lower end
+            let is_slope_axis = index >= 2;
+            if axis_entry > entry_time || (is_slope_axis && axis_entry >= entry_time - COLLISION_TOLERANCE) {
+                normal = axis_normal;
+            }
+            entry_time = entry_time.max(axis_entry);
+            exit_time = exit_time.min(axis_exit);
+        }
+
+        if entry_time < exit_time && -COLLISION_TOLERANCE <= entry_time && entry_time <= dt {
+            Some((entry_time, CollisionSide::from_normal(normal), normal))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Compute the velocities of this collider and `other` after colliding on the given side
+    /// (of this collider) with the given normal. Momentum is conserved along the collision axis,
+    /// and friction opposes sliding along the surface for edge hits. Corner hits are resolved on
+    /// both axes.
+    ///
+    /// The impulse is always applied along the axis of `side`, even when the normal is sloped.
+    /// This means colliders can rest on a slope without sliding down it, and keep their horizontal
+    /// speed while moving up it.
+    pub fn resolve_collision(&self, other: &Self, side: CollisionSide, normal: Vector<f32, 2>) -> (Vector<f32, 2>, Vector<f32, 2>) {
+        let inverse_masses = (self.inverse_mass(), other.inverse_mass());
+        if inverse_masses.0 + inverse_masses.1 <= 0.0 {
+            return (Vector::zero(), Vector::zero());
+        }
+        let restitution = self.restitution.max(other.restitution);
+        let friction = self.friction * other.friction;
+
+        let mut velocities = (self.velocity, other.velocity);
+        if side.is_corner() {
+            // Neither axis takes precedence for corner hits, so each is resolved separately
+            let corner = side.normal();
+            for axis_normal in [Vector([corner.x(), 0.0]), Vector([0.0, corner.y()])] {
+                apply_contact_impulse(&mut velocities, inverse_masses, axis_normal, axis_normal, restitution, 0.0);
+            }
         }
         else {
-            (self.velocity + other.velocity).mul(0.5)
+            apply_contact_impulse(&mut velocities, inverse_masses, normal, side.normal(), restitution, friction);
         }
+
+        velocities
     }
 }
 
-#[derive(PartialEq, Debug)]
+/// Apply the impulse of a contact to a pair of velocities. The impulse acts along the unit vector
+/// `direction`, and is just strong enough to stop (or reverse, with restitution) the approach
+/// along the unit vector `normal`.
+fn apply_contact_impulse(
+    velocities: &mut (Vector<f32, 2>, Vector<f32, 2>),
+    inverse_masses: (f32, f32),
+    normal: Vector<f32, 2>,
+    direction: Vector<f32, 2>,
+    restitution: f32,
+    friction: f32,
+) {
+    let inverse_mass_sum = inverse_masses.0 + inverse_masses.1;
+    let relative_velocity = velocities.0 - velocities.1;
+    let normal_speed = relative_velocity.dot(normal);
+    // Only resolve if the colliders are still approaching each other
+    if normal_speed <= 0.0 {
+        return;
+    }
+
+    let bounce = if normal_speed > RESTITUTION_THRESHOLD { restitution } else { 0.0 };
+    let impulse = -(1.0 + bounce) * normal_speed / (inverse_mass_sum * direction.dot(normal));
+    velocities.0 += direction.mul(impulse * inverse_masses.0);
+    velocities.1 -= direction.mul(impulse * inverse_masses.1);
+
+    // Coulomb friction: the impulse along the surface can't exceed the friction coefficient
+    // times the impulse which would be needed to stop the colliders pressing together. On slopes,
+    // this ignores the part of the impulse which lifts a collider moving up the slope.
+    let pressing_speed = relative_velocity.dot(direction).max(0.0);
+    let tangent = Vector([-direction.y(), direction.x()]);
+    let tangent_speed = (velocities.0 - velocities.1).dot(tangent);
+    let max_friction_impulse = friction * (1.0 + bounce) * pressing_speed / inverse_mass_sum;
+    let friction_impulse = (-tangent_speed / inverse_mass_sum)
+        .clamp(-max_friction_impulse, max_friction_impulse);
+    velocities.0 += tangent.mul(friction_impulse * inverse_masses.0);
+    velocities.1 -= tangent.mul(friction_impulse * inverse_masses.1);
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct ColliderHandle(ArenaHandle);
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SensorEventKind {
+    /// The collider began overlapping the sensor.
+    Enter,
+    /// The collider stopped overlapping the sensor (or was removed).
+    Exit,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SensorEvent {
+    pub sensor: ColliderHandle,
+    pub other: ColliderHandle,
+    pub kind: SensorEventKind,
+}
+
+/// Criteria for which colliders a physics query should consider.
+#[derive(Clone, Debug)]
+pub struct QueryFilter {
+    /// Only colliders in at least one of these layers are considered.
+    pub mask: u32,
+    pub include_fixed: bool,
+    pub include_dynamic: bool,
+    pub include_sensors: bool,
+    pub excluded: Vec<ColliderHandle>,
+}
+
+impl QueryFilter {
+    pub fn new() -> Self {
+        Self {
+            mask: ALL_LAYERS,
+            include_fixed: true,
+            include_dynamic: true,
+            include_sensors: false,
+            excluded: Vec::new(),
+        }
+    }
+
+    pub fn with_mask(mut self, mask: u32) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    pub fn exclude_fixed(mut self) -> Self {
+        self.include_fixed = false;
+        self
+    }
+
+    pub fn exclude_dynamic(mut self) -> Self {
+        self.include_dynamic = false;
+        self
+    }
+
+    pub fn include_sensors(mut self) -> Self {
+        self.include_sensors = true;
+        self
+    }
+
+    pub fn excluding(mut self, handle: ColliderHandle) -> Self {
+        self.excluded.push(handle);
+        self
+    }
+
+    pub fn accepts(&self, handle: &ColliderHandle, collider: &Collider) -> bool {
+        let kind_included = if collider.sensor {
+            self.include_sensors
+        } else if collider.fixed {
+            self.include_fixed
+        } else {
+            self.include_dynamic
+        };
+        kind_included && collider.layers & self.mask != 0 && !self.excluded.contains(handle)
+    }
+}
+
+impl Default for QueryFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RaycastHit {
+    pub collider: ColliderHandle,
+    /// The point where the ray enters the collider.
+    pub point: Vector<f32, 2>,
+    /// The surface normal at the point of entry, or zero if the ray started inside the collider.
+    pub normal: Vector<f32, 2>,
+    /// The distance traveled along the ray before hitting the collider.
+    pub distance: f32,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SweepHit {
+    pub collider: ColliderHandle,
+    /// The fraction of the displacement which can be traveled before hitting the collider.
+    pub time: f32,
+    /// The side/corner of the swept rectangle which hits the collider.
+    pub side: CollisionSide,
+    /// The position of the swept rectangle at the time of the hit.
+    pub rectangle: Rectangle<f32>,
+}
+
+/// A record of two colliders coming into contact during a simulation step.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ContactEvent {
+    pub collider_1: ColliderHandle,
+    pub collider_2: ColliderHandle,
+    /// The side/corner of collider 1 which collided with collider 2.
+    pub side: CollisionSide,
+    /// The direction of collider 2 relative to collider 1 at the point of contact.
+    pub normal: Vector<f32, 2>,
+    /// The time into the simulation step at which the contact occurred.
+    pub time: f32,
+    /// The velocity of collider 1 relative to collider 2 immediately before impact.
+    pub relative_velocity: Vector<f32, 2>,
+}
+
 #[derive(Debug)]
 pub struct Physics {
     colliders: UnboundedArena<Collider>,
+    sensor_overlaps: HashMap<ColliderHandle, Vec<ColliderHandle>>,
+    sensor_events: Vec<SensorEvent>,
+    contact_events: Vec<ContactEvent>,
 }
 
 impl Physics {
     pub fn new() -> Self {
         Self {
             colliders: UnboundedArena::new(),
+            sensor_overlaps: HashMap::new(),
+            sensor_events: Vec::new(),
+            contact_events: Vec::new(),
         }
     }
 
@@ -284,9 +726,173 @@ impl Physics {
     }
 
     pub fn remove_collider(&mut self, handle: ColliderHandle) -> Option<Collider> {
+        self.sensor_overlaps.remove(&handle);
         self.colliders.remove(handle.0)
     }
 
+    /// The sensor events accumulated by simulation steps since they were last drained.
+    pub fn sensor_events(&self) -> &[SensorEvent] {
+        &self.sensor_events
+    }
+
+    pub fn drain_sensor_events(&mut self) -> std::vec::Drain<'_, SensorEvent> {
+        self.sensor_events.drain(..)
+    }
+
+    pub fn sensor_events_for<'a>(&'a self, sensor: &'a ColliderHandle) -> impl Iterator<Item = &'a SensorEvent> + 'a {
+        self.sensor_events.iter().filter(move |event| &event.sensor == sensor)
+    }
+
+    /// The colliders overlapping the given sensor as of the most recent simulation step.
+    pub fn sensor_overlaps(&self, sensor: &ColliderHandle) -> &[ColliderHandle] {
+        self.sensor_overlaps.get(sensor).map_or(&[], Vec::as_slice)
+    }
+
+    /// The contact events accumulated by simulation steps since they were last drained.
+    pub fn contact_events(&self) -> &[ContactEvent] {
+        &self.contact_events
+    }
+
+    pub fn drain_contact_events(&mut self) -> std::vec::Drain<'_, ContactEvent> {
+        self.contact_events.drain(..)
+    }
+
+    fn query_colliders<'a>(&'a self, filter: &'a QueryFilter) -> impl Iterator<Item = (ColliderHandle, &'a Collider)> + 'a {
+        self.colliders
+            .values()
+            .map(|(handle, collider)| (ColliderHandle(handle), collider))
+            .filter(|(handle, collider)| filter.accepts(handle, collider))
+    }
+
+    /// Find the first collider hit by the line segment from `start` to `end`. One-way colliders
+    /// are only hit from their blocking side, and slopes only along their solid triangle.
+    pub fn raycast(&self, start: Vector<f32, 2>, end: Vector<f32, 2>, filter: &QueryFilter) -> Option<RaycastHit> {
+        let delta = end - start;
+        let length = delta.magnitude();
+        let mut segment_bounds = Rectangle::new(start, start);
+        segment_bounds.expand_toward(delta);
+
+        let mut closest_hit: Option<(f32, ColliderHandle, Vector<f32, 2>)> = None;
+        for (handle, collider) in self.query_colliders(filter) {
+            let rectangle = &collider.rectangle;
+            if !segment_bounds.intersects_inclusive(rectangle) && !rectangle.contains_inclusive(start) {
+                continue;
+            }
+
+            // Slab test: find the interval of the segment (as a fraction in [0, 1]) lying
+            // within the rectangle on each axis, then intersect the intervals
+            let mut entry_time = f32::NEG_INFINITY;
+            let mut exit_time = f32::INFINITY;
+            let mut normal = Vector::zero();
+            let mut missed = false;
+            for axis in 0..2 {
+                if delta[axis] == 0.0 {
+                    if start[axis] < rectangle.min[axis] || start[axis] > rectangle.max[axis] {
+                        missed = true;
+                        break;
+                    }
+                    continue;
+                }
+                let time_1 = (rectangle.min[axis] - start[axis]) / delta[axis];
+                let time_2 = (rectangle.max[axis] - start[axis]) / delta[axis];
+                let (axis_entry, axis_exit) = if time_1 < time_2 { (time_1, time_2) } else { (time_2, time_1) };
+                if axis_entry > entry_time {
+                    entry_time = axis_entry;
+                    normal = Vector::zero();
+                    normal[axis] = -delta[axis].signum();
+                }
+                exit_time = exit_time.min(axis_exit);
+            }
+            // Slopes are clipped once more by the half-plane below their sloped surface
+            if let Some(slope_normal) = collider.slope_normal() {
+                let surface_point = if slope_normal.x() < 0.0 { rectangle.min } else { rectangle.min_x_max_y() };
+                let start_distance = (start - surface_point).dot(slope_normal);
+                let approach_speed = delta.dot(slope_normal);
+                if approach_speed == 0.0 {
+                    missed |= start_distance > 0.0;
+                }
+                else {
+                    let time = -start_distance / approach_speed;
+                    if approach_speed < 0.0 && time > entry_time {
+                        entry_time = time;
+                        normal = slope_normal;
+                    }
+                    else if approach_speed > 0.0 {
+                        exit_time = exit_time.min(time);
+                    }
+                }
+            }
+            if missed || entry_time > exit_time || exit_time < 0.0 || entry_time > 1.0 {
+                continue;
+            }
+            if entry_time < 0.0 {
+                // The segment starts inside the collider, which one-way colliders let it leave
+                if collider.one_way.is_some() {
+                    continue;
+                }
+                entry_time = 0.0;
+                normal = Vector::zero();
+            }
+            else if !collider.blocks_on(CollisionSide::from_normal(normal)) {
+                continue;
+            }
+            if closest_hit.as_ref().is_none_or(|&(time, ..)| entry_time < time) {
+                closest_hit = Some((entry_time, handle, normal));
+            }
+        }
+
+        closest_hit.map(|(time, collider, normal)| RaycastHit {
+            collider,
+            point: start + delta.mul(time),
+            normal,
+            distance: length * time,
+        })
+    }
+
+    /// Find all colliders overlapping the given rectangle. Colliders which are only touching
+    /// the rectangle are not included. Slopes are treated as if they filled their whole rectangle.
+    pub fn overlap(&self, rectangle: &Rectangle<f32>, filter: &QueryFilter) -> Vec<ColliderHandle> {
+        self.query_colliders(filter)
+            .filter(|(_, collider)| collider.rectangle.intersects_inclusive(rectangle))
+            .map(|(handle, _)| handle)
+            .collect()
+    }
+
+    /// Find the first collider hit by moving the given rectangle by `displacement`,
+    /// treating all colliders as stationary. Colliders which the rectangle already
+    /// overlaps are ignored.
+    pub fn sweep_box(&self, rectangle: Rectangle<f32>, displacement: Vector<f32, 2>, filter: &QueryFilter) -> Option<SweepHit> {
+        let swept = Collider::new(rectangle, displacement);
+        let broad_phase = swept.broad_phase(1.0);
+
+        let mut closest_hit: Option<(f32, CollisionSide, ColliderHandle)> = None;
+        for (handle, collider) in self.query_colliders(filter) {
+            if !broad_phase.intersects_inclusive(&collider.rectangle) {
+                continue;
+            }
+            let obstacle = Collider::new_fixed(collider.rectangle).with_shape(collider.shape);
+            if let Some((time, side, _)) = swept.sweep_collision(&obstacle, 1.0) {
+                if !collider.blocks_on(side.opposite()) {
+                    continue;
+                }
+                if closest_hit.as_ref().is_none_or(|&(closest_time, ..)| time < closest_time) {
+                    closest_hit = Some((time, side, handle));
+                }
+            }
+        }
+
+        closest_hit.map(|(time, side, collider)| {
+            let mut rectangle = rectangle;
+            rectangle.shift_by(displacement.mul(time.max(0.0)));
+            SweepHit {
+                collider,
+                time: time.max(0.0),
+                side,
+                rectangle,
+            }
+        })
+    }
+
     pub fn step_simulation(&mut self, dt: f32) {
         // Clear all collider hit flags
         for (_, collider) in self.colliders.values_mut() {
@@ -300,8 +906,8 @@ impl Physics {
         // Incrementally handle all swept collisions, earliest first
         let mut collisions = self.get_collisions(dt);
         while let Some(collision) = collisions.pop() {
-            let collider_1 = self.colliders.get_current(collision.index_1).unwrap();
-            let collider_2 = self.colliders.get_current(collision.index_2).unwrap();
+            let collider_1 = self.colliders.get(collision.handle_1).unwrap();
+            let collider_2 = self.colliders.get(collision.handle_2).unwrap();
 
             // Double-check that there is still a broad phase intersection between the colliders.
             // This is necessary because the colliders' velocities may have changed since the
@@ -309,36 +915,49 @@ impl Physics {
             if !collider_1.broad_phase(dt).intersects_inclusive(&collider_2.broad_phase(dt)) {
                 continue;
             }
+            // For the same reason, sweep the pair again from its current state. If the collision
+            // now happens differently (which is likely with slopes, since a change in velocity
+            // can change which part of the slope is hit first), it's queued again or dropped.
+            let start_time = time_used[collision.handle_1.slot].max(time_used[collision.handle_2.slot]);
+            let mut current_1 = collider_1.clone();
+            current_1.rectangle.shift_by(current_1.velocity.mul(start_time - time_used[collision.handle_1.slot]));
+            let mut current_2 = collider_2.clone();
+            current_2.rectangle.shift_by(current_2.velocity.mul(start_time - time_used[collision.handle_2.slot]));
+            let Some((time, side, normal)) = Self::sweep_pair(&current_1, &current_2, dt - start_time) else {
+                continue;
+            };
+            let time = start_time + time;
+            if side != collision.side || (time - collision.time).abs() > COLLISION_TOLERANCE {
+                collisions.push(Collision {
+                    time,
+                    side,
+                    normal,
+                    ..collision
+                });
+                continue;
+            }
 
-            let collision_velocity = collider_1.collision_velocity(collider_2);
+            let (velocity_1, velocity_2) = collider_1.resolve_collision(collider_2, collision.side, collision.normal);
+            self.contact_events.push(ContactEvent {
+                collider_1: ColliderHandle(collision.handle_1),
+                collider_2: ColliderHandle(collision.handle_2),
+                side: collision.side,
+                normal: collision.normal,
+                time: collision.time,
+                relative_velocity: collider_1.velocity - collider_2.velocity,
+            });
 
-            for index in [collision.index_1, collision.index_2] {
-                let collider = self.colliders.get_current_mut(index).unwrap();
+            for (handle, velocity) in [(collision.handle_1, velocity_1), (collision.handle_2, velocity_2)] {
+                let collider = self.colliders.get_mut(handle).unwrap();
 
                 // Advance to the collision site
-                collider.rectangle.shift_by(collider.velocity.mul(collision.time - time_used[index]));
+                collider.rectangle.shift_by(collider.velocity.mul(collision.time - time_used[handle.slot]));
                 // Record the amount of time passed once collision site is reached
-                time_used[index] = collision.time;
+                time_used[handle.slot] = collision.time;
                 // Set the post-collision velocity of the collider
-                collider.velocity = match collision.side {
-                    CollisionSide::Left |
-                    CollisionSide::Right => {
-                        Vector([collision_velocity.x(), collider.velocity.y()])
-                    }
-                    CollisionSide::Bottom |
-                    CollisionSide::Top => {
-                        Vector([collider.velocity.x(), collision_velocity.y()])
-                    }
-                    CollisionSide::BottomLeft |
-                    CollisionSide::BottomRight |
-                    CollisionSide::TopLeft |
-                    CollisionSide::TopRight => {
-                        // Subject to change
-                        collision_velocity
-                    }
-                };
+                collider.velocity = velocity;
                 // Set the corresponding hit flags in the collider
-                collider.set_hit_flags(if index == collision.index_1 {
+                collider.set_hit_flags(if handle == collision.handle_1 {
                     collision.side
                 } else {
                     collision.side.opposite()
@@ -350,43 +969,387 @@ impl Physics {
         for (handle, collider) in self.colliders.values_mut() {
             collider.rectangle.shift_by(collider.velocity.mul(dt - time_used[handle.slot]));
         }
+
+        self.update_sensors();
+    }
+
+    fn update_sensors(&mut self) {
+        let mut sensor_overlaps = HashMap::new();
+
+        for (sensor_handle, sensor) in self.colliders.values() {
+            if !sensor.sensor {
+                continue;
+            }
+            let sensor_handle = ColliderHandle(sensor_handle);
+
+            // TODO: checks every collider for every sensor; fine while sensors are few
+            let overlaps: Vec<ColliderHandle> = self.colliders
+                .values()
+                .filter(|&(other_handle, other)| {
+                    other_handle != sensor_handle.0
+                        && sensor.detects(other)
+                        && sensor.rectangle.intersects_inclusive(&other.rectangle)
+                })
+                .map(|(other_handle, _)| ColliderHandle(other_handle))
+                .collect();
+
+            let previous_overlaps = self.sensor_overlaps.remove(&sensor_handle).unwrap_or_default();
+            for other in &overlaps {
+                if !previous_overlaps.contains(other) {
+                    self.sensor_events.push(SensorEvent {
+                        sensor: sensor_handle.clone(),
+                        other: other.clone(),
+                        kind: SensorEventKind::Enter,
+                    });
+                }
+            }
+            for other in previous_overlaps {
+                if !overlaps.contains(&other) {
+                    self.sensor_events.push(SensorEvent {
+                        sensor: sensor_handle.clone(),
+                        other,
+                        kind: SensorEventKind::Exit,
+                    });
+                }
+            }
+
+            sensor_overlaps.insert(sensor_handle, overlaps);
+        }
+
+        self.sensor_overlaps = sensor_overlaps;
     }
 
     fn get_collisions(&self, dt: f32) -> BinaryHeap<Collision> {
-        // TODO: this is a fairly naive approach which is O(n^2) and very slow
-        let colliders: Vec<_> = self.colliders.values().collect();
+        // Sweep and prune: sort the x-axis endpoints of every broad phase, then sweep from left
+        // to right while keeping track of which broad phases are currently "open". Only pairs
+        // whose broad phases overlap on the x-axis are ever considered, so the cost is roughly
+        // proportional to the number of such pairs rather than O(n^2).
+        let phases: Vec<(ArenaHandle, &Collider, Rectangle<f32>)> = self.colliders
+            .values()
+            .filter(|(_, collider)| !collider.sensor)
+            .map(|(handle, collider)| (handle, collider, collider.broad_phase(dt)))
+            .collect();
+
+        let mut endpoints = Vec::with_capacity(phases.len() * 2);
+        for (index, &(_, _, phase)) in phases.iter().enumerate() {
+            if phase.min.x() < phase.max.x() {
+                endpoints.push(SweepEndpoint { value: phase.min.x(), kind: SweepEndpointKind::Begin, index });
+                endpoints.push(SweepEndpoint { value: phase.max.x(), kind: SweepEndpointKind::End, index });
+            }
+            else if phase.min.x() == phase.max.x() {
+                endpoints.push(SweepEndpoint { value: phase.min.x(), kind: SweepEndpointKind::Point, index });
+            }
+        }
+        endpoints.sort_unstable_by(|a, b| {
+            a.value.total_cmp(&b.value).then_with(|| a.kind.cmp(&b.kind))
+        });
+
+        // Fixed colliders are kept apart from the others so that fixed-fixed pairs, which can't
+        // possibly collide, are never even visited
+        let mut active_fixed: Vec<usize> = Vec::new();
+        let mut active_dynamic: Vec<usize> = Vec::new();
         let mut collisions = BinaryHeap::new();
 
-        for (checked_count, &(handle_1, collider_1)) in colliders.iter().enumerate() {
-            // Skipping checked_count + 1 elements for this inner loop ensures that:
-            // a) no collider is checked against itself (this is what the +1 is for)
-            // b) there is only one check performed on each pair
-            // c) for each pair (handle_1, handle_2) it holds that handle_1.slot < handle_2.slot
-            // This is still O(n^2), but it cuts the number of checks done in half.
-            for &(handle_2, collider_2) in colliders[checked_count + 1 ..].iter() {
-                // If both colliders are fixed, they can't possibly collide.
-                if collider_1.fixed && collider_2.fixed {
+        for endpoint in endpoints {
+            let (_, collider, _) = phases[endpoint.index];
+            let active = if collider.fixed { &mut active_fixed } else { &mut active_dynamic };
+
+            if endpoint.kind == SweepEndpointKind::End {
+                let position = active.iter().position(|&index| index == endpoint.index).unwrap();
+                active.swap_remove(position);
+                continue;
+            }
+
+            // Every collider is checked against the open dynamic broad phases,
+            // but only non-fixed colliders need to be checked against the open fixed ones
+            for &other_index in &active_dynamic {
+                self.check_collision_pair(&phases, endpoint.index, other_index, dt, &mut collisions);
+            }
+            if !collider.fixed {
+                for &other_index in &active_fixed {
+                    self.check_collision_pair(&phases, endpoint.index, other_index, dt, &mut collisions);
+                }
+            }
+
+            // Zero-width broad phases can't overlap anything that begins after them,
+            // so they never need to be opened
+            if endpoint.kind == SweepEndpointKind::Begin {
+                let active = if collider.fixed { &mut active_fixed } else { &mut active_dynamic };
+                active.push(endpoint.index);
+            }
+        }
+
+        collisions
+    }
+
+    fn check_collision_pair(
+        &self,
+        phases: &[(ArenaHandle, &Collider, Rectangle<f32>)],
+        index_a: usize,
+        index_b: usize,
+        dt: f32,
+        collisions: &mut BinaryHeap<Collision>,
+    ) {
+        #[cfg(test)]
+        tests::PAIR_CHECKS.with(|checks| checks.set(checks.get() + 1));
+
+        // Order each pair such that handle_1.slot < handle_2.slot, which keeps the results
+        // consistent regardless of the order in which the sweep finds them
+        let (index_1, index_2) = if phases[index_a].0.slot < phases[index_b].0.slot {
+            (index_a, index_b)
+        } else {
+            (index_b, index_a)
+        };
+        let (handle_1, collider_1, phase_1) = phases[index_1];
+        let (handle_2, collider_2, phase_2) = phases[index_2];
+
+        if !collider_1.interacts_with(collider_2) {
+            return;
+        }
+        // The sweep only guarantees overlap on the x-axis, so check the full broad phases
+        if !phase_1.intersects_inclusive(&phase_2) {
+            return;
+        }
+        // Now, check for an actual collision between the two colliders.
+        if let Some((time, side, normal)) = Self::sweep_pair(collider_1, collider_2, dt) {
+            collisions.push(Collision {
+                handle_1,
+                handle_2,
+                time,
+                side,
+                normal,
+            });
+        }
+    }
+
+    fn sweep_pair(collider_1: &Collider, collider_2: &Collider, dt: f32) -> Option<(f32, CollisionSide, Vector<f32, 2>)> {
+        let (time, side, normal) = collider_1.sweep_collision(collider_2, dt)?;
+        // One-way colliders let the other collider pass through unless hit on the right side
+        if collider_1.blocks_on(side) && collider_2.blocks_on(side.opposite()) {
+            Some((time, side, normal))
+        }
+        else {
+            None
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum SweepEndpointKind {
+    // At equal values, intervals ending are handled before zero-width intervals, which are
+    // handled before intervals beginning. This matches the strict comparisons used by
+    // Rectangle::intersects_inclusive, where touching broad phases don't intersect.
+    End,
+    Point,
+    Begin,
+}
+
+#[derive(Debug)]
+struct SweepEndpoint {
+    value: f32,
+    kind: SweepEndpointKind,
+    index: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    thread_local! {
+        /// How many pairs [`Physics::get_collisions`] has checked on this thread.
+        pub(super) static PAIR_CHECKS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// A small xorshift generator, so that the random collider sets are reproducible.
+    struct TestRandom(u64);
+
+    impl TestRandom {
+        fn next_f32(&mut self) -> f32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 >> 40) as f32 / (1u64 << 24) as f32
+        }
+
+        fn range(&mut self, min: f32, max: f32) -> f32 {
+            min + self.next_f32() * (max - min)
+        }
+    }
+
+    /// Fill a square region with `count` random colliders, at roughly the same density
+    /// regardless of the count.
+    fn random_physics(count: usize, seed: u64) -> Physics {
+        let mut random = TestRandom(seed);
+        let extent = (count as f32).sqrt() * 2.0;
+        let mut physics = Physics::new();
+        for _ in 0..count {
+            let rectangle = Rectangle::from_span(
+                Vector([random.range(0.0, extent), random.range(0.0, extent)]),
+                Vector([random.range(0.25, 2.0), random.range(0.25, 2.0)]),
+            );
+            let kind = random.next_f32();
+            let mut collider = if kind < 0.3 {
+                Collider::new_fixed(rectangle)
+            }
+            else if kind < 0.4 {
+                Collider::new_sensor(rectangle)
+            }
+            else {
+                Collider::new(rectangle, Vector([random.range(-60.0, 60.0), random.range(-60.0, 60.0)]))
+            };
+            if random.next_f32() < 0.1 {
+                collider = collider.with_one_way(CollisionSide::Top);
+            }
+            if random.next_f32() < 0.2 {
+                collider = collider.with_layers(2, ALL_LAYERS & !DEFAULT_LAYERS);
+            }
+            physics.add_collider(collider);
+        }
+        physics
+    }
+
+    /// The straightforward O(n^2) version of [`Physics::get_collisions`], checking every pair.
+    fn pairwise_collisions(physics: &Physics, dt: f32) -> BinaryHeap<Collision> {
+        let colliders: Vec<(ArenaHandle, &Collider)> = physics.colliders
+            .values()
+            .filter(|(_, collider)| !collider.sensor)
+            .collect();
+        let mut collisions = BinaryHeap::new();
+        for (i, &(handle_a, collider_a)) in colliders.iter().enumerate() {
+            for &(handle_b, collider_b) in &colliders[i + 1..] {
+                let ((handle_1, collider_1), (handle_2, collider_2)) = if handle_a.slot < handle_b.slot {
+                    ((handle_a, collider_a), (handle_b, collider_b))
+                } else {
+                    ((handle_b, collider_b), (handle_a, collider_a))
+                };
+                if collider_1.fixed && collider_2.fixed || !collider_1.interacts_with(collider_2) {
                     continue;
                 }
-                // Before checking for actual collision between a pair, we will first
-                // check if there is broad phase intersection. The broad phase is a
-                // rectangle encompassing the projected motion of a collider, so if their
-                // broad phases don't intersect, we can discard the pair.
                 if !collider_1.broad_phase(dt).intersects_inclusive(&collider_2.broad_phase(dt)) {
                     continue;
                 }
-                // Now, check for an actual collision between the two colliders.
-                if let Some((time, side)) = collider_1.sweep_collision(collider_2, dt) {
-                    collisions.push(Collision {
-                        index_1: handle_1.slot,
-                        index_2: handle_2.slot,
-                        time,
-                        side,
-                    });
+                if let Some((time, side, normal)) = Physics::sweep_pair(collider_1, collider_2, dt) {
+                    collisions.push(Collision { handle_1, handle_2, time, side, normal });
                 }
             }
         }
+        collisions
+    }
 
+    fn summarize(collisions: BinaryHeap<Collision>) -> Vec<(ArenaHandle, ArenaHandle, f32, CollisionSide, Vector<f32, 2>)> {
         collisions
+            .into_sorted_vec()
+            .into_iter()
+            .map(|collision| (collision.handle_1, collision.handle_2, collision.time, collision.side, collision.normal))
+            .collect()
+    }
+
+    fn count_pair_checks(physics: &Physics) -> usize {
+        PAIR_CHECKS.with(|checks| checks.set(0));
+        physics.get_collisions(DT);
+        PAIR_CHECKS.with(Cell::get)
+    }
+
+    #[test]
+    fn collision_order_breaks_ties_by_handle() {
+        let collision = |slot_1, slot_2| Collision {
+            handle_1: ArenaHandle { slot: slot_1, version: 0 },
+            handle_2: ArenaHandle { slot: slot_2, version: 0 },
+            time: 0.5,
+            side: CollisionSide::Bottom,
+            normal: Vector([0.0, 1.0]),
+        };
+        assert_ne!(collision(0, 1), collision(0, 2));
+        // Lower handles come out of the max-heap first
+        assert!(collision(0, 1) > collision(0, 2));
+        assert!(collision(0, 2) > collision(1, 2));
+    }
+
+    #[test]
+    fn raycast_passes_through_one_way_colliders_from_below() {
+        let mut physics = Physics::new();
+        let platform = physics.add_collider(
+            Collider::new_fixed(Rectangle::new(Vector([0.0, 0.75]), Vector([1.0, 1.0])))
+                .with_one_way(CollisionSide::Top),
+        );
+        let filter = QueryFilter::new();
+
+        assert!(physics.raycast(Vector([0.5, 0.0]), Vector([0.5, 2.0]), &filter).is_none());
+        assert!(physics.raycast(Vector([0.5, 0.9]), Vector([0.5, 2.0]), &filter).is_none());
+        assert!(physics.raycast(Vector([0.0, 0.5]), Vector([2.0, 0.5]), &filter).is_none());
+
+        let hit = physics.raycast(Vector([0.5, 2.0]), Vector([0.5, 0.0]), &filter).unwrap();
+        assert_eq!(hit.collider, platform);
+        assert_eq!(hit.normal, Vector([0.0, 1.0]));
+        assert_eq!(hit.point, Vector([0.5, 1.0]));
+    }
+
+    #[test]
+    fn raycast_only_hits_the_solid_part_of_slopes() {
+        let mut physics = Physics::new();
+        let slope = physics.add_collider(
+            Collider::new_fixed(Rectangle::new(Vector([0.0, 0.0]), Vector([1.0, 1.0])))
+                .with_shape(ColliderShape::Slope { rises_right: true }),
+        );
+        let filter = QueryFilter::new();
+
+        // Through the empty triangle above the sloped surface
+        assert!(physics.raycast(Vector([-1.0, 0.75]), Vector([0.5, 0.75]), &filter).is_none());
+        assert!(physics.raycast(Vector([0.25, 2.0]), Vector([0.25, 0.5]), &filter).is_none());
+
+        let hit = physics.raycast(Vector([0.25, 2.0]), Vector([0.25, 0.0]), &filter).unwrap();
+        assert_eq!(hit.collider, slope);
+        assert!((hit.point.y() - 0.25).abs() < 1.0e-6);
+        assert_eq!(hit.normal, Vector([-1.0, 1.0]).normalized());
+
+        let hit = physics.raycast(Vector([2.0, 0.5]), Vector([0.0, 0.5]), &filter).unwrap();
+        assert_eq!(hit.point, Vector([1.0, 0.5]));
+        assert_eq!(hit.normal, Vector([1.0, 0.0]));
+    }
+
+    #[test]
+    fn sensor_events_accumulate_until_drained() {
+        let mut physics = Physics::new();
+        let sensor = physics.add_collider(Collider::new_sensor(Rectangle::new(Vector([0.0, 0.0]), Vector([1.0, 1.0]))));
+        let other = physics.add_collider(Collider::new_fixed(Rectangle::new(Vector([0.5, 0.5]), Vector([1.5, 1.5]))));
+
+        physics.step_simulation(DT);
+        physics.get_collider_mut(&other).unwrap().rectangle = Rectangle::new(Vector([5.0, 5.0]), Vector([6.0, 6.0]));
+        physics.step_simulation(DT);
+
+        let kinds: Vec<SensorEventKind> = physics.sensor_events_for(&sensor).map(|event| event.kind).collect();
+        assert_eq!(kinds, [SensorEventKind::Enter, SensorEventKind::Exit]);
+        assert_eq!(physics.drain_sensor_events().count(), 2);
+        assert!(physics.sensor_events().is_empty());
+    }
+
+    #[test]
+    fn sweep_matches_pairwise_collisions() {
+        for seed in 1..=20 {
+            let physics = random_physics(300, seed * 0x9E37_79B9);
+            let expected = summarize(pairwise_collisions(&physics, DT));
+            assert!(!expected.is_empty());
+            assert_eq!(summarize(physics.get_collisions(DT)), expected, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn sweep_scales_with_collider_count() {
+        let mut checks = Vec::new();
+        for count in [100, 1_000, 10_000] {
+            let physics = random_physics(count, count as u64);
+            if count <= 1_000 {
+                assert_eq!(summarize(physics.get_collisions(DT)), summarize(pairwise_collisions(&physics, DT)));
+            }
+            checks.push(count_pair_checks(&physics));
+        }
+        // At constant density, each collider only overlaps about sqrt(n) others on the x-axis, so
+        // ten times as many colliders should mean roughly thirty times as many pairs to check,
+        // whereas checking every pair would mean a hundred times as many
+        let ratio = checks[2] as f64 / checks[1] as f64;
+        assert!(ratio < 50.0, "10 000 colliders checked {ratio:.1} times as many pairs as 1 000: {checks:?}");
     }
 }
@@ -3,16 +3,20 @@
 uniform mat4 camera_view;
 uniform mat4 camera_proj;
 uniform sampler2D tex_atlas;
+uniform float sky_light_multiplier;
 
 layout(location = 0) in vec3 vertex_position;
-layout(location = 1) in vec4 vertex_color;
+layout(location = 1) in vec4 vertex_light;
 layout(location = 2) in vec2 vertex_uv;
+layout(location = 3) in float vertex_opacity;
 
 out vec4 frag_color;
 out vec2 frag_uv;
 
 void main() {
-    frag_color = vertex_color;
+    // Sky light dims with the time of day, but block light is unaffected
+    vec3 light = max(vertex_light.rgb, vec3(vertex_light.a * sky_light_multiplier));
+    frag_color = vec4(light, vertex_opacity);
     frag_uv = vertex_uv / textureSize(tex_atlas, 0);
 
     gl_Position = camera_proj * camera_view * vec4(vertex_position, 1.0);
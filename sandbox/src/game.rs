@@ -10,11 +10,11 @@ use crate::tools::asset::AssetPool;
 use crate::tools::input::InputState;
 use crate::world::camera::Camera;
 use crate::world::block::{BlockSide, CHUNK_SIZE};
-use crate::world::block::types::AIR;
 use crate::world::entity::Entity;
-use crate::world::entity::types::player::PlayerMode;
+use crate::world::entity::types::player::{PlayerMode, HOTBAR_SIZE};
 use crate::world::gen::WorldGenerator;
 use crate::world::item::{Item, ITEM_TYPES};
+use crate::world::save::WorldSave;
 use crate::world::World;
 
 pub struct Game<'world> {
@@ -81,14 +81,34 @@ impl<'world> Game<'world> {
         self.current_world.as_ref()
     }
 
-    pub fn enter_world(&mut self, generator: Option<Box<dyn WorldGenerator>>) {
+    pub fn enter_world(&mut self, directory: Option<&Path>, generator: Option<Box<dyn WorldGenerator>>) -> Result<(), String> {
+        self.exit_world();
+
+        let save = directory.map(WorldSave::open).transpose()?;
+        // An existing world directory determines its own generator; the one passed in is only used for new worlds
+        let level = save.as_ref().map(WorldSave::read_level).transpose()?.flatten();
+        let generator = match &level {
+            Some(level) => World::read_level_generator(level)?,
+            None => generator,
+        };
         let camera = Camera::new(
             Vector::zero(),
             self.viewport_size,
             self.content_scale.mul(48.0),
             5.0,
         );
-        self.current_world = Some(World::new(generator, camera, &mut self.assets));
+        let mut world = World::new(generator, save, camera, &mut self.assets);
+        if let Some(level) = &level {
+            world.restore_level(level, &mut self.assets)?;
+        }
+        self.current_world = Some(world);
+        Ok(())
+    }
+
+    pub fn exit_world(&mut self) {
+        if let Some(mut world) = self.current_world.take() {
+            world.save();
+        }
     }
 
     pub fn run_frame(&mut self, inputs: &InputState, window: &mut Window) {
@@ -164,6 +184,13 @@ impl<'world> Game<'world> {
                 let next_item_index = (item_index as isize + offset).rem_euclid(ITEM_TYPES.len() as isize) as usize;
                 world.player_mut().set_held_item(Item::with_max_count(ITEM_TYPES[next_item_index]));
             }
+            const HOTBAR_KEYS: [Key; HOTBAR_SIZE] = [
+                Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5,
+                Key::Num6, Key::Num7, Key::Num8, Key::Num9, Key::Num0,
+            ];
+            if let Some(slot) = HOTBAR_KEYS.iter().position(|&key| inputs.key_was_pressed(key)) {
+                world.player_mut().set_selected_slot(slot);
+            }
             if inputs.key_was_pressed(Key::F4) {
                 let current_mode = world.player().mode();
                 world.player_mut().set_mode(match current_mode {
@@ -172,44 +199,70 @@ impl<'world> Game<'world> {
                 });
             }
             if inputs.key_was_pressed(Key::Escape) {
+                if self.gui.inventory_shown() {
+                    world.player_mut().return_crafting_items();
+                    world.player_mut().return_cursor_item();
+                }
                 self.gui.set_inventory_shown(!self.gui.inventory_shown());
                 self.gui.clear_text();
             }
-            self.gui.handle_input(inputs);
+            let gui_captured_input = self.gui.handle_input(inputs, world.player_mut(), self.assets.recipes());
 
-            if left_held || right_held || middle_held {
+            if gui_captured_input {
+                self.last_block_pos = None;
+                world.stop_mining();
+            }
+            else if left_held || right_held || middle_held {
                 let chunk_location = Vector([
                     cursor_world_pos.x().div_euclid(CHUNK_SIZE as f32) as i64,
                     cursor_world_pos.y().div_euclid(CHUNK_SIZE as f32) as i64,
                 ]);
                 let block_x = cursor_world_pos.x().rem_euclid(CHUNK_SIZE as f32) as usize;
                 let block_y = cursor_world_pos.y().rem_euclid(CHUNK_SIZE as f32) as usize;
+                let block_position = cursor_world_pos.map(|x| x.floor() as i64);
+
+                // Holding Alt targets the wall behind the block instead
+                let targets_wall = inputs.key_is_held(Key::LeftAlt) || inputs.key_is_held(Key::RightAlt);
+
+                let can_reach = world.player_can_reach(block_position);
 
-                if self.last_block_pos.is_none_or(|pos| pos != (block_x, block_y)) {
+                // Mining takes time, so it continues every frame the button is held
+                if left_held && can_reach {
+                    if targets_wall {
+                        world.player_mine_wall(block_position, dt, &mut self.assets, &self.audio);
+                    }
+                    else {
+                        world.player_mine_block(block_position, dt, &mut self.assets, &self.audio);
+                    }
+                }
+                else {
+                    world.stop_mining();
+                }
+
+                if can_reach && self.last_block_pos.is_none_or(|pos| pos != (block_x, block_y)) {
                     self.last_block_pos = Some((block_x, block_y));
                     if middle_held {
-                        let block_type = world
+                        let item_type = world
                             .get_chunk(chunk_location)
-                            .map_or(&AIR, |chunk| {
-                                chunk.block_at(block_x, block_y).block_type()
+                            .and_then(|chunk| {
+                                if targets_wall {
+                                    chunk.wall_at(block_x, block_y).item_type()
+                                }
+                                else {
+                                    chunk.block_at(block_x, block_y).block_type().item_type()
+                                }
                             });
-                        if let Some(item_type) = block_type.item_type() {
+                        if let Some(item_type) = item_type {
                             world.player_mut().set_held_item(Item::new(
                                 item_type,
                                 item_type.max_count(),
                             ));
                         }
                     }
-                    if left_held {
-                        world.user_destroy_block(
-                            chunk_location,
-                            block_x,
-                            block_y,
-                            &mut self.assets,
-                            &self.audio,
-                        );
+                    if right_held && targets_wall {
+                        world.player_place_wall(block_position, &self.assets, &self.audio);
                     }
-                    if right_held {
+                    else if right_held {
                         world.player_use_item(
                             chunk_location,
                             block_x,
@@ -223,12 +276,13 @@ impl<'world> Game<'world> {
             }
             else {
                 self.last_block_pos = None;
+                world.stop_mining();
             }
 
             world.set_block_preview_position(cursor_world_pos);
-            world.update(inputs, dt);
+            world.update(inputs, dt, &mut self.assets, &self.audio);
 
-            self.gui.update_item_display(world.player().held_item(), &self.assets);
+            self.gui.update_item_display(world.player(), &self.assets);
             self.gui.update_player_info_display(
                 world.player().position(),
                 world.player().velocity(),
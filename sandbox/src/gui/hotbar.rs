@@ -1,16 +1,18 @@
 use innovus::gfx::MeshRenderer;
 use innovus::tools::{Rectangle, Vector};
 use crate::gui::render::{GuiImage, GuiVertex};
-use crate::gui::render::item::ItemGrid;
+use crate::gui::render::item::{ItemGrid, ItemSlot};
 use crate::gui::render::text::{TextBackground, TextLine};
 use crate::tools::asset::AssetPool;
 use crate::tools::input::InputState;
-use crate::world::item::{types, Item};
+use crate::world::item::Item;
 
 pub struct Hotbar {
     anchor: Vector<f32, 2>,
     offset: Vector<f32, 2>,
     background_image: GuiImage,
+    selection_image: GuiImage,
+    selected_slot: usize,
     held_item_text: TextLine,
     item_grid: ItemGrid,
     background_layer: MeshRenderer<GuiVertex>,
@@ -21,6 +23,8 @@ pub struct Hotbar {
 impl Hotbar {
     const HELD_ITEM_TEXT_OFFSET: Vector<f32, 2> = Vector([106.0, 0.0]);
     const ITEM_GRID_OFFSET: Vector<f32, 2> = Vector([8.0, 8.0]);
+    const SLOT_SPACING: f32 = 20.0;
+    const SLOT_COUNT: usize = 10;
 
     pub fn new(assets: &mut AssetPool) -> Result<Self, String> {
         Ok(Self {
@@ -31,6 +35,12 @@ impl Hotbar {
                 Vector::one(),
                 assets.get_gui_image("gui/hotbar")?,
             ),
+            selection_image: GuiImage::new(
+                Rectangle::from_span(Vector::filled(-4.0), Vector::filled(24.0)),
+                Vector::one(),
+                assets.get_gui_image("gui/hotbar_selection")?,
+            ),
+            selected_slot: 0,
             held_item_text: TextLine::new(
                 Vector([0.5, 1.0]),
                 Vector([1.0, 1.0, 1.0, 1.0]),
@@ -40,19 +50,11 @@ impl Hotbar {
                 },
                 String::new(),
             ),
-            item_grid: {
-                let mut item_grid = ItemGrid::new(
-                    10,
-                    10,
-                    Vector::filled(4.0)
-                );
-                item_grid.slot_mut(0).set_item(Item::new(&types::DIAMOND, 20));
-                item_grid.slot_mut(1).set_item(Item::new(&types::DIAMOND_BLOCK, 7));
-                item_grid.slot_mut(2).set_item(Item::new(&types::EMERALD, 5));
-                item_grid.slot_mut(3).set_item(Item::new(&types::EMERALD_BLOCK, 2));
-                item_grid.slot_mut(4).set_item(Item::new(&types::MAGMIUM_PICKAXE, 1));
-                item_grid
-            },
+            item_grid: ItemGrid::new(
+                Self::SLOT_COUNT,
+                Self::SLOT_COUNT,
+                Vector::filled(Self::SLOT_SPACING - ItemSlot::BOUNDS.x_span()),
+            ),
             background_layer: MeshRenderer::create(),
             item_layer: MeshRenderer::create(),
             foreground_layer: MeshRenderer::create(),
@@ -81,6 +83,16 @@ impl Hotbar {
         self.invalidate();
     }
 
+    /// Show the given items (the first row of the player's inventory) in the hotbar slots, with
+    /// the selected slot highlighted. Only slots whose items changed are redrawn.
+    pub fn update_items(&mut self, items: &[Item], selected_slot: usize) {
+        let selection_changed = selected_slot != self.selected_slot;
+        self.selected_slot = selected_slot;
+        if self.item_grid.update_items(items) || selection_changed {
+            self.invalidate();
+        }
+    }
+
     pub fn invalidate(&mut self) {
         self.background_layer.clear();
         self.item_layer.clear();
@@ -89,6 +101,7 @@ impl Hotbar {
 
     pub fn reload_assets(&mut self, assets: &mut AssetPool) -> Result<(), String> {
         self.background_image.set_atlas_region(assets.get_gui_image("gui/hotbar")?);
+        self.selection_image.set_atlas_region(assets.get_gui_image("gui/hotbar_selection")?);
         self.held_item_text.invalidate();
         self.item_grid.invalidate();
         self.invalidate();
@@ -115,6 +128,10 @@ impl Hotbar {
                 self.background_layer.data_mut(),
                 self.offset,
             );
+            self.selection_image.append_to_mesh(
+                self.background_layer.data_mut(),
+                self.offset + Self::ITEM_GRID_OFFSET + Vector([self.selected_slot as f32 * Self::SLOT_SPACING, 0.0]),
+            );
             self.item_grid.append_to_mesh(
                 self.item_layer.data_mut(),
                 self.foreground_layer.data_mut(),
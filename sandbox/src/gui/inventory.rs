@@ -0,0 +1,410 @@
+use glfw::{MouseButtonLeft, MouseButtonRight};
+use innovus::tools::{Rectangle, Vector};
+use crate::gui::render::{GuiImage, GuiLayers};
+use crate::gui::render::item::{ItemGrid, ItemSlot};
+use crate::tools::asset::AssetPool;
+use crate::tools::input::{InputState, Key};
+use crate::world::entity::types::player::{Player, CRAFTING_GRID_WIDTH, HOTBAR_SIZE, INVENTORY_SIZE};
+use crate::world::item::{Item, RecipeBook, SlotClick};
+
+/// A slot which can be clicked in the inventory screen.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ScreenSlot {
+    Inventory(usize),
+    Crafting(usize),
+    CraftingResult,
+}
+
+/// The full inventory window, with the crafting grid above the player's inventory and the hotbar
+/// row at the bottom. Nothing here touches the graphics context until [`Self::render`], so input
+/// handling can be driven by an [`InputState`] on its own.
+pub struct InventoryScreen {
+    anchor: Vector<f32, 2>,
+    offset: Vector<f32, 2>,
+    crafting_image: Option<GuiImage>,
+    inventory_image: Option<GuiImage>,
+    crafting_grid: ItemGrid,
+    result_slot: ItemSlot,
+    storage_grid: ItemGrid,
+    hotbar_grid: ItemGrid,
+    drag_start: Option<ScreenSlot>,
+    changed: bool,
+}
+
+impl InventoryScreen {
+    pub const SIZE: Vector<f32, 2> = Vector([212.0, 192.0]);
+    const CRAFTING_PANEL_OFFSET: Vector<f32, 2> = Vector([50.0, 0.0]);
+    const CRAFTING_PANEL_SIZE: Vector<f32, 2> = Vector([112.0, 84.0]);
+    const CRAFTING_GRID_OFFSET: Vector<f32, 2> = Vector([58.0, 20.0]);
+    const RESULT_SLOT_OFFSET: Vector<f32, 2> = Vector([138.0, 40.0]);
+    const INVENTORY_PANEL_OFFSET: Vector<f32, 2> = Vector([0.0, 88.0]);
+    const INVENTORY_PANEL_SIZE: Vector<f32, 2> = Vector([212.0, 104.0]);
+    const STORAGE_GRID_OFFSET: Vector<f32, 2> = Vector([8.0, 108.0]);
+    const HOTBAR_GRID_OFFSET: Vector<f32, 2> = Vector([8.0, 168.0]);
+    const SLOT_GAP: Vector<f32, 2> = Vector([4.0, 4.0]);
+
+    pub fn new() -> Self {
+        Self {
+            anchor: Vector([0.5, 0.5]),
+            offset: Self::SIZE.mul(-0.5),
+            crafting_image: None,
+            inventory_image: None,
+            crafting_grid: ItemGrid::new(CRAFTING_GRID_WIDTH * CRAFTING_GRID_WIDTH, CRAFTING_GRID_WIDTH, Self::SLOT_GAP),
+            result_slot: ItemSlot::new(),
+            storage_grid: ItemGrid::new(INVENTORY_SIZE - HOTBAR_SIZE, HOTBAR_SIZE, Self::SLOT_GAP),
+            hotbar_grid: ItemGrid::new(HOTBAR_SIZE, HOTBAR_SIZE, Self::SLOT_GAP),
+            drag_start: None,
+            changed: true,
+        }
+    }
+
+    pub fn anchor(&self) -> Vector<f32, 2> {
+        self.anchor
+    }
+
+    pub fn offset(&self) -> Vector<f32, 2> {
+        self.offset
+    }
+
+    pub fn set_offset(&mut self, offset: Vector<f32, 2>) {
+        self.offset = offset;
+        self.changed = true;
+    }
+
+    pub fn invalidate(&mut self) {
+        self.crafting_image = None;
+        self.inventory_image = None;
+        self.crafting_grid.invalidate();
+        self.result_slot.invalidate();
+        self.storage_grid.invalidate();
+        self.hotbar_grid.invalidate();
+        self.changed = true;
+    }
+
+    /// Find the slot under the cursor, if any.
+    pub fn slot_at(&self, cursor_offset: Vector<f32, 2>) -> Option<ScreenSlot> {
+        let cursor_offset = cursor_offset - self.offset;
+        if let Some(index) = self.storage_grid.slot_index_at(cursor_offset - Self::STORAGE_GRID_OFFSET) {
+            Some(ScreenSlot::Inventory(HOTBAR_SIZE + index))
+        }
+        else if let Some(index) = self.hotbar_grid.slot_index_at(cursor_offset - Self::HOTBAR_GRID_OFFSET) {
+            Some(ScreenSlot::Inventory(index))
+        }
+        else if let Some(index) = self.crafting_grid.slot_index_at(cursor_offset - Self::CRAFTING_GRID_OFFSET) {
+            Some(ScreenSlot::Crafting(index))
+        }
+        else if ItemSlot::BOUNDS.contains_inclusive(cursor_offset - Self::RESULT_SLOT_OFFSET) {
+            Some(ScreenSlot::CraftingResult)
+        }
+        else {
+            None
+        }
+    }
+
+    pub fn update_items(&mut self, player: &Player, recipes: &RecipeBook) {
+        let slots = player.inventory().slots();
+        let crafting_slots = player.crafting_grid().slots();
+        let result = recipes
+            .find_match(crafting_slots, CRAFTING_GRID_WIDTH)
+            .map_or_else(Item::default, |recipe| recipe.result().clone());
+        let result_changed = self.result_slot.item().item_type() != result.item_type()
+            || self.result_slot.item().count() != result.count();
+        if result_changed {
+            self.result_slot.set_item(result);
+        }
+        let hotbar_changed = self.hotbar_grid.update_items(&slots[..HOTBAR_SIZE]);
+        let storage_changed = self.storage_grid.update_items(&slots[HOTBAR_SIZE..]);
+        let crafting_changed = self.crafting_grid.update_items(crafting_slots);
+        self.changed |= result_changed || hotbar_changed || storage_changed || crafting_changed;
+    }
+
+    fn click_slot(slot: ScreenSlot, click: SlotClick, player: &mut Player, recipes: &RecipeBook) {
+        match slot {
+            ScreenSlot::Inventory(index) => player.click_inventory_slot(index, click),
+            ScreenSlot::Crafting(index) => player.click_crafting_slot(index, click),
+            ScreenSlot::CraftingResult => {
+                if let Some(recipe) = recipes.find_match(player.crafting_grid().slots(), CRAFTING_GRID_WIDTH) {
+                    player.craft_onto_cursor(recipe);
+                }
+            }
+        }
+    }
+
+    fn transfer_slot(slot: ScreenSlot, player: &mut Player, recipes: &RecipeBook) {
+        match slot {
+            ScreenSlot::Inventory(index) => player.transfer_inventory_slot(index),
+            ScreenSlot::Crafting(index) => player.transfer_crafting_slot(index),
+            ScreenSlot::CraftingResult => {
+                if let Some(recipe) = recipes.find_match(player.crafting_grid().slots(), CRAFTING_GRID_WIDTH) {
+                    player.craft_into_inventory(recipe);
+                }
+            }
+        }
+    }
+
+    /// Move items around the player's inventory and crafting grid in response to mouse input.
+    /// Left click picks up or puts down a whole stack (also by dragging it to another slot), right
+    /// click splits a stack or puts down one item, and shift-click moves a stack between the
+    /// hotbar and the rest of the inventory, or out of the crafting grid. Clicking the crafting
+    /// result crafts it once, or as many times as possible with shift held. Returns whether the
+    /// cursor is over the screen.
+    pub fn handle_input(
+        &mut self,
+        cursor_offset: Vector<f32, 2>,
+        inputs: &InputState,
+        player: &mut Player,
+        recipes: &RecipeBook,
+    ) -> bool {
+        if let Some(slot) = self.slot_at(cursor_offset) {
+            let shift_held = inputs.key_is_held(Key::LeftShift) || inputs.key_is_held(Key::RightShift);
+            if inputs.button_was_pressed(MouseButtonLeft) {
+                if shift_held {
+                    Self::transfer_slot(slot, player, recipes);
+                }
+                else {
+                    let picked_up = player.cursor_item().is_empty();
+                    Self::click_slot(slot, SlotClick::Primary, player, recipes);
+                    if picked_up && !player.cursor_item().is_empty() {
+                        self.drag_start = Some(slot);
+                    }
+                }
+            }
+            else if inputs.button_was_released(MouseButtonLeft) {
+                // Releasing a dragged stack over a different slot puts it down there
+                let dragged = self.drag_start.is_some_and(|start| start != slot);
+                if dragged && slot != ScreenSlot::CraftingResult && !player.cursor_item().is_empty() {
+                    Self::click_slot(slot, SlotClick::Primary, player, recipes);
+                }
+            }
+            if inputs.button_was_pressed(MouseButtonRight) {
+                Self::click_slot(slot, SlotClick::Secondary, player, recipes);
+            }
+        }
+        if !inputs.button_is_held(MouseButtonLeft) {
+            self.drag_start = None;
+        }
+        self.update_items(player, recipes);
+
+        let cursor_offset = cursor_offset - self.offset;
+        Rectangle::from_span(Self::CRAFTING_PANEL_OFFSET, Self::CRAFTING_PANEL_SIZE).contains_inclusive(cursor_offset)
+            || Rectangle::from_span(Self::INVENTORY_PANEL_OFFSET, Self::INVENTORY_PANEL_SIZE).contains_inclusive(cursor_offset)
+    }
+
+    pub fn render(&mut self, layers: &mut GuiLayers, assets: &mut AssetPool) {
+        if self.changed || layers.is_empty() {
+            layers.clear();
+
+            if self.crafting_image.is_none() {
+                self.crafting_image = assets.get_gui_image("gui/crafting").ok().map(|atlas_region| {
+                    GuiImage::new(
+                        Rectangle::from_span(Self::CRAFTING_PANEL_OFFSET, Self::CRAFTING_PANEL_SIZE),
+                        Vector::one(),
+                        atlas_region,
+                    )
+                });
+            }
+            if self.inventory_image.is_none() {
+                self.inventory_image = assets.get_gui_image("gui/inventory").ok().map(|atlas_region| {
+                    GuiImage::new(
+                        Rectangle::from_span(Self::INVENTORY_PANEL_OFFSET, Self::INVENTORY_PANEL_SIZE),
+                        Vector::one(),
+                        atlas_region,
+                    )
+                });
+            }
+            for image in [&self.crafting_image, &self.inventory_image].into_iter().flatten() {
+                image.append_to_mesh(layers.background.data_mut(), self.offset);
+            }
+            for (grid, grid_offset) in [
+                (&mut self.crafting_grid, Self::CRAFTING_GRID_OFFSET),
+                (&mut self.storage_grid, Self::STORAGE_GRID_OFFSET),
+                (&mut self.hotbar_grid, Self::HOTBAR_GRID_OFFSET),
+            ] {
+                grid.append_to_mesh(
+                    layers.item.data_mut(),
+                    layers.foreground.data_mut(),
+                    self.offset + grid_offset,
+                    assets,
+                );
+            }
+            self.result_slot.append_to_mesh(
+                layers.item.data_mut(),
+                layers.foreground.data_mut(),
+                self.offset + Self::RESULT_SLOT_OFFSET,
+                assets,
+            );
+
+            layers.upload_buffers();
+            self.changed = false;
+        }
+
+        assets.gui_shaders().set_uniform("anchor", &self.anchor);
+        layers.render(assets);
+    }
+}
+
+impl Default for InventoryScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glfw::{Action, Modifiers, MouseButton};
+    use crate::tools::generate_uuid;
+    use crate::world::entity::types::player::PlayerMode;
+    use crate::world::item::types;
+    use super::*;
+
+    struct Harness {
+        screen: InventoryScreen,
+        inputs: InputState,
+        player: Player,
+        recipes: RecipeBook,
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            Self {
+                screen: InventoryScreen::new(),
+                inputs: InputState::new(),
+                player: Player::new(generate_uuid(), Vector::zero(), None, PlayerMode::Normal),
+                recipes: RecipeBook::new(),
+            }
+        }
+
+        fn slot_center(&self, slot: ScreenSlot) -> Vector<f32, 2> {
+            let slot_offset = match slot {
+                ScreenSlot::Inventory(index) if index < HOTBAR_SIZE => {
+                    InventoryScreen::HOTBAR_GRID_OFFSET + self.screen.hotbar_grid.slot_offset(index)
+                }
+                ScreenSlot::Inventory(index) => {
+                    InventoryScreen::STORAGE_GRID_OFFSET + self.screen.storage_grid.slot_offset(index - HOTBAR_SIZE)
+                }
+                ScreenSlot::Crafting(index) => {
+                    InventoryScreen::CRAFTING_GRID_OFFSET + self.screen.crafting_grid.slot_offset(index)
+                }
+                ScreenSlot::CraftingResult => InventoryScreen::RESULT_SLOT_OFFSET,
+            };
+            self.screen.offset() + slot_offset + ItemSlot::BOUNDS.center()
+        }
+
+        /// Run one frame of input with the cursor over a slot.
+        fn frame(&mut self, slot: ScreenSlot, button: MouseButton, action: Action, shift_held: bool) {
+            self.inputs.reset();
+            let shift_action = if shift_held { Action::Press } else { Action::Release };
+            self.inputs.handle_key(Key::LeftShift, shift_action, Modifiers::empty());
+            self.inputs.handle_mouse_button(button, action, Modifiers::empty());
+            let cursor_offset = self.slot_center(slot);
+            assert!(self.screen.handle_input(cursor_offset, &self.inputs, &mut self.player, &self.recipes));
+        }
+
+        fn click(&mut self, slot: ScreenSlot, button: MouseButton, shift_held: bool) {
+            self.frame(slot, button, Action::Press, shift_held);
+            self.frame(slot, button, Action::Release, shift_held);
+        }
+
+        fn set_slot(&mut self, index: usize, item: Item) {
+            self.player.inventory_mut().set_item(index, item);
+        }
+
+        fn slot(&self, index: usize) -> (&'static str, u32) {
+            stack(self.player.inventory().item(index))
+        }
+
+        fn cursor(&self) -> (&'static str, u32) {
+            stack(self.player.cursor_item())
+        }
+    }
+
+    fn stack(item: &Item) -> (&'static str, u32) {
+        if item.is_empty() {
+            ("", 0)
+        }
+        else {
+            (item.item_type().name(), item.count())
+        }
+    }
+
+    const STORAGE_SLOT: usize = HOTBAR_SIZE + 3;
+    const OTHER_STORAGE_SLOT: usize = HOTBAR_SIZE + 4;
+
+    #[test]
+    fn left_click_picks_up_places_and_swaps() {
+        let mut harness = Harness::new();
+        harness.set_slot(STORAGE_SLOT, Item::new(&types::STONE, 10));
+        harness.set_slot(OTHER_STORAGE_SLOT, Item::new(&types::DIRT, 5));
+
+        // Pick up
+        harness.click(ScreenSlot::Inventory(STORAGE_SLOT), MouseButtonLeft, false);
+        assert_eq!(harness.cursor(), ("stone", 10));
+        assert_eq!(harness.slot(STORAGE_SLOT), ("", 0));
+
+        // Swap with a different item
+        harness.click(ScreenSlot::Inventory(OTHER_STORAGE_SLOT), MouseButtonLeft, false);
+        assert_eq!(harness.cursor(), ("dirt", 5));
+        assert_eq!(harness.slot(OTHER_STORAGE_SLOT), ("stone", 10));
+
+        // Place into an empty slot
+        harness.click(ScreenSlot::Inventory(STORAGE_SLOT), MouseButtonLeft, false);
+        assert_eq!(harness.cursor(), ("", 0));
+        assert_eq!(harness.slot(STORAGE_SLOT), ("dirt", 5));
+    }
+
+    #[test]
+    fn right_click_splits_and_places_one() {
+        let mut harness = Harness::new();
+        harness.set_slot(STORAGE_SLOT, Item::new(&types::STONE, 9));
+
+        // Split, taking the larger half
+        harness.click(ScreenSlot::Inventory(STORAGE_SLOT), MouseButtonRight, false);
+        assert_eq!(harness.cursor(), ("stone", 5));
+        assert_eq!(harness.slot(STORAGE_SLOT), ("stone", 4));
+
+        // Place one into an empty slot, then one onto the matching stack
+        harness.click(ScreenSlot::Inventory(OTHER_STORAGE_SLOT), MouseButtonRight, false);
+        assert_eq!(harness.cursor(), ("stone", 4));
+        assert_eq!(harness.slot(OTHER_STORAGE_SLOT), ("stone", 1));
+        harness.click(ScreenSlot::Inventory(STORAGE_SLOT), MouseButtonRight, false);
+        assert_eq!(harness.cursor(), ("stone", 3));
+        assert_eq!(harness.slot(STORAGE_SLOT), ("stone", 5));
+    }
+
+    #[test]
+    fn releasing_a_drag_over_another_slot_puts_the_stack_down() {
+        let mut harness = Harness::new();
+        harness.set_slot(STORAGE_SLOT, Item::new(&types::STONE, 10));
+
+        harness.frame(ScreenSlot::Inventory(STORAGE_SLOT), MouseButtonLeft, Action::Press, false);
+        assert_eq!(harness.cursor(), ("stone", 10));
+        harness.frame(ScreenSlot::Inventory(OTHER_STORAGE_SLOT), MouseButtonLeft, Action::Release, false);
+        assert_eq!(harness.cursor(), ("", 0));
+        assert_eq!(harness.slot(STORAGE_SLOT), ("", 0));
+        assert_eq!(harness.slot(OTHER_STORAGE_SLOT), ("stone", 10));
+
+        // Releasing over the slot the stack came from keeps it on the cursor
+        harness.frame(ScreenSlot::Inventory(OTHER_STORAGE_SLOT), MouseButtonLeft, Action::Press, false);
+        harness.frame(ScreenSlot::Inventory(OTHER_STORAGE_SLOT), MouseButtonLeft, Action::Release, false);
+        assert_eq!(harness.cursor(), ("stone", 10));
+        assert_eq!(harness.slot(OTHER_STORAGE_SLOT), ("", 0));
+    }
+
+    #[test]
+    fn shift_click_transfers_between_hotbar_and_storage() {
+        let mut harness = Harness::new();
+        harness.set_slot(2, Item::new(&types::STONE, 10));
+        harness.set_slot(HOTBAR_SIZE, Item::new(&types::STONE, 95));
+
+        // Tops up the matching stack in storage first, then fills the next empty slot
+        harness.click(ScreenSlot::Inventory(2), MouseButtonLeft, true);
+        assert_eq!(harness.cursor(), ("", 0));
+        assert_eq!(harness.slot(2), ("", 0));
+        assert_eq!(harness.slot(HOTBAR_SIZE), ("stone", 100));
+        assert_eq!(harness.slot(HOTBAR_SIZE + 1), ("stone", 5));
+
+        harness.click(ScreenSlot::Inventory(HOTBAR_SIZE + 1), MouseButtonLeft, true);
+        assert_eq!(harness.slot(HOTBAR_SIZE + 1), ("", 0));
+        assert_eq!(harness.slot(0), ("stone", 5));
+    }
+}
@@ -1,8 +1,9 @@
-use innovus::gfx::MeshRenderer;
 use innovus::tools::Vector;
 use crate::tools::asset::AssetPool;
-use crate::world::item::Item;
-use render::GuiVertex;
+use crate::world::entity::types::player::{Player, HOTBAR_SIZE};
+use crate::world::item::RecipeBook;
+use inventory::InventoryScreen;
+use render::GuiLayers;
 use render::cursor::GuiCursor;
 use render::text::{TextLine, TextLineRenderer};
 use crate::gui::render::text::TextBackground;
@@ -10,6 +11,7 @@ use crate::tools::input::InputState;
 
 pub mod render;
 pub mod hotbar;
+pub mod inventory;
 
 pub struct GuiManager {
     viewport_size: Vector<f32, 2>,
@@ -19,7 +21,8 @@ pub struct GuiManager {
     cursor_position: Vector<f32, 2>,
     cursor: GuiCursor,
     hotbar: hotbar::Hotbar,
-    inventory: MeshRenderer<GuiVertex>,
+    inventory_screen: InventoryScreen,
+    inventory_layers: GuiLayers,
     inventory_shown: bool,
     fps_display: TextLineRenderer,
     player_info_display: TextLineRenderer,
@@ -36,7 +39,8 @@ impl GuiManager {
             cursor_position: Vector::zero(),
             cursor: GuiCursor::new(Vector::zero(), Vector::zero(), &crate::world::item::types::AIR),
             hotbar: hotbar::Hotbar::new(assets)?,
-            inventory: MeshRenderer::create(),
+            inventory_screen: InventoryScreen::new(),
+            inventory_layers: GuiLayers::create(),
             inventory_shown: false,
             fps_display: TextLineRenderer::create(
                 TextLine::new(
@@ -140,6 +144,14 @@ impl GuiManager {
         &mut self.hotbar
     }
 
+    pub fn inventory_screen(&self) -> &InventoryScreen {
+        &self.inventory_screen
+    }
+
+    pub fn inventory_screen_mut(&mut self) -> &mut InventoryScreen {
+        &mut self.inventory_screen
+    }
+
     pub fn inventory_shown(&self) -> bool {
         self.inventory_shown
     }
@@ -150,7 +162,7 @@ impl GuiManager {
 
     pub fn reload_assets(&mut self, assets: &mut AssetPool) -> Result<(), String> {
         self.hotbar.reload_assets(assets)?;
-        self.inventory.clear();
+        self.inventory_screen.invalidate();
         Ok(())
     }
 
@@ -168,8 +180,16 @@ impl GuiManager {
         ));
     }
 
-    pub fn update_item_display(&mut self, item: &Item, assets: &AssetPool) {
-        self.cursor.set_item_type(item.item_type());
+    pub fn update_item_display(&mut self, player: &Player, assets: &AssetPool) {
+        self.hotbar.update_items(&player.inventory().slots()[..HOTBAR_SIZE], player.selected_slot());
+        if self.inventory_shown {
+            self.inventory_screen.update_items(player, assets.recipes());
+            self.cursor.set_item(player.cursor_item());
+        }
+        else {
+            self.cursor.set_item_type(player.held_item().item_type());
+        }
+        let item = player.held_item();
         if item.item_type().is_air() {
             self.hotbar.set_held_item_text(String::new());
         }
@@ -203,7 +223,15 @@ impl GuiManager {
         self.input_test.data_mut().set_text(String::new());
     }
 
-    pub fn handle_input(&mut self, inputs: &InputState) -> bool {
+    /// Handle input for whichever GUI elements are under the cursor, returning whether the input
+    /// was captured by the GUI. While the inventory screen is open, it captures all input.
+    pub fn handle_input(&mut self, inputs: &InputState, player: &mut Player, recipes: &RecipeBook) -> bool {
+        if self.inventory_shown {
+            let cursor_offset = self.anchor_adjustment(self.cursor.anchor(), self.inventory_screen.anchor())
+                + self.cursor.offset();
+            self.inventory_screen.handle_input(cursor_offset, inputs, player, recipes);
+            return true;
+        }
         let cursor_offset = self.anchor_adjustment(self.cursor.anchor(), self.hotbar.anchor())
             + self.cursor.offset();
         if self.hotbar.handle_input(cursor_offset, inputs) {
@@ -216,26 +244,8 @@ impl GuiManager {
         assets.gui_shaders().set_uniform("offset_scale", &self.offset_scale);
         assets.gui_shaders().set_uniform("tex_atlas", assets.gui_texture());
 
-        if self.inventory.is_empty() {
-            let atlas_region = assets.get_gui_image("gui/inventory").unwrap();
-            let to_f32 = |x: u32| x as f32;
-            self.inventory.add(
-                &[
-                    GuiVertex::new(Vector([-106.0, -62.0]), None, Some(atlas_region.min.map(to_f32))),
-                    GuiVertex::new(Vector([-106.0, 62.0]), None, Some(atlas_region.min_x_max_y().map(to_f32))),
-                    GuiVertex::new(Vector([106.0, 62.0]), None, Some(atlas_region.max.map(to_f32))),
-                    GuiVertex::new(Vector([106.0, -62.0]), None, Some(atlas_region.max_x_min_y().map(to_f32))),
-                ],
-                &[
-                    [0, 1, 2],
-                    [2, 3, 0],
-                ],
-            );
-        }
         if self.inventory_shown {
-            assets.gui_texture().bind();
-            assets.gui_shaders().set_uniform("anchor", &Vector([0.5f32, 0.5f32]));
-            self.inventory.render();
+            self.inventory_screen.render(&mut self.inventory_layers, assets);
             self.input_test.render(assets);
         }
 
@@ -1,13 +1,16 @@
 use innovus::gfx::MeshRenderer;
 use innovus::tools::Vector;
 use crate::gui::render::GuiVertex;
+use crate::gui::render::item::format_item_count;
+use crate::gui::render::text::{TextBackground, TextLine};
 use crate::tools::asset::AssetPool;
-use crate::world::item::ItemType;
+use crate::world::item::{Item, ItemType};
 
 pub struct GuiCursor {
     anchor: Vector<f32, 2>,
     offset: Vector<f32, 2>,
     item_type: &'static ItemType,
+    count_text: TextLine,
     mesh: MeshRenderer<GuiVertex>,
 }
 
@@ -17,6 +20,15 @@ impl GuiCursor {
             anchor,
             offset,
             item_type,
+            count_text: TextLine::new(
+                Vector([1.0, 1.0]),
+                Vector::one(),
+                TextBackground::DropShadow {
+                    color: Vector([0.0, 0.0, 0.0, 0.8]),
+                    offset: Vector([0.0, 1.0]),
+                },
+                String::new(),
+            ),
             mesh: MeshRenderer::create(),
         }
     }
@@ -43,6 +55,13 @@ impl GuiCursor {
 
     pub fn set_item_type(&mut self, item_type: &'static ItemType) {
         self.item_type = item_type;
+        self.count_text.clear_text();
+    }
+
+    /// Show an item stack next to the cursor, including its count.
+    pub fn set_item(&mut self, item: &Item) {
+        self.item_type = item.item_type();
+        self.count_text.set_text(format_item_count(item.count()));
     }
 
     pub fn render(&mut self, assets: &mut AssetPool) {
@@ -114,6 +133,8 @@ impl GuiCursor {
                 [2, 3, 0],
             ],
         );
+        self.count_text.append_to_mesh(self.mesh.data_mut(), self.offset + Vector([21.0, 23.0]), assets);
+        self.mesh.upload_buffers();
 
         assets.gui_texture().bind();
         self.mesh.render();
@@ -155,29 +155,61 @@ impl ItemGrid {
         }
     }
 
-    pub fn handle_input(&mut self, cursor_offset: Vector<f32, 2>, inputs: &InputState) -> bool {
+    /// Show the given items in the slots of this grid, returning whether any slot changed.
+    pub fn update_items(&mut self, items: &[Item]) -> bool {
+        let mut changed = false;
+        for (slot, item) in std::iter::zip(&mut self.slots, items) {
+            if slot.item().item_type() != item.item_type() || slot.item().count() != item.count() {
+                slot.set_item(item.clone());
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Find the index of the slot under the cursor, if any. The offset is relative to the top
+    /// left corner of the first slot.
+    pub fn slot_index_at(&self, cursor_offset: Vector<f32, 2>) -> Option<usize> {
         let mut cursor_y = cursor_offset.y();
-        for row_slots in self.slots.chunks_mut(self.column_count) {
+        for (row, row_slots) in self.slots.chunks(self.column_count).enumerate() {
             if cursor_y < 0.0 {
-                return false;
+                return None;
             }
             else if cursor_y <= ItemSlot::BOUNDS.y_span() {
                 let mut cursor_x = cursor_offset.x();
-                for slot in row_slots {
+                for column in 0..row_slots.len() {
                     if cursor_x < 0.0 {
-                        return false;
+                        return None;
                     }
                     else if cursor_x <= ItemSlot::BOUNDS.x_span() {
-                        slot.handle_input(Vector([cursor_x, cursor_y]), inputs);
-                        return true;
+                        return Some(row * self.column_count + column);
                     }
                     cursor_x -= ItemSlot::BOUNDS.x_span() + self.gap.x();
                 }
-                return false;
+                return None;
             }
             cursor_y -= ItemSlot::BOUNDS.y_span() + self.gap.y();
         }
-        false
+        None
+    }
+
+    /// Get the offset of a slot relative to the top left corner of the first slot.
+    pub fn slot_offset(&self, index: usize) -> Vector<f32, 2> {
+        let row = (index / self.column_count) as f32;
+        let column = (index % self.column_count) as f32;
+        Vector([
+            column * (ItemSlot::BOUNDS.x_span() + self.gap.x()),
+            row * (ItemSlot::BOUNDS.y_span() + self.gap.y()),
+        ])
+    }
+
+    pub fn handle_input(&mut self, cursor_offset: Vector<f32, 2>, inputs: &InputState) -> bool {
+        let Some(index) = self.slot_index_at(cursor_offset) else {
+            return false;
+        };
+        let slot_offset = self.slot_offset(index);
+        self.slots[index].handle_input(cursor_offset - slot_offset, inputs);
+        true
     }
 
     pub fn append_to_mesh(
@@ -1,6 +1,7 @@
 use std::mem::offset_of;
-use innovus::gfx::{Mesh, Vertex, VertexAttribute, VertexAttributeType};
+use innovus::gfx::{Mesh, MeshRenderer, Vertex, VertexAttribute, VertexAttributeType};
 use innovus::tools::{Rectangle, Vector};
+use crate::tools::asset::AssetPool;
 
 pub mod cursor;
 pub mod item;
@@ -108,3 +109,46 @@ impl GuiImage {
         );
     }
 }
+
+/// Meshes for a GUI element drawn in three layers: a background from the GUI atlas, items from the
+/// item atlas, and a foreground from the GUI atlas drawn over the items.
+pub struct GuiLayers {
+    pub background: MeshRenderer<GuiVertex>,
+    pub item: MeshRenderer<GuiVertex>,
+    pub foreground: MeshRenderer<GuiVertex>,
+}
+
+impl GuiLayers {
+    pub fn create() -> Self {
+        Self {
+            background: MeshRenderer::create(),
+            item: MeshRenderer::create(),
+            foreground: MeshRenderer::create(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.background.is_empty() && self.item.is_empty() && self.foreground.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.background.clear();
+        self.item.clear();
+        self.foreground.clear();
+    }
+
+    pub fn upload_buffers(&self) {
+        self.background.upload_buffers();
+        self.item.upload_buffers();
+        self.foreground.upload_buffers();
+    }
+
+    pub fn render(&self, assets: &mut AssetPool) {
+        assets.gui_texture().bind();
+        self.background.render();
+        assets.item_texture().bind();
+        self.item.render();
+        assets.gui_texture().bind();
+        self.foreground.render();
+    }
+}
@@ -1,3 +1,4 @@
+use std::path::Path;
 use glfw::{Context, WindowEvent};
 use innovus::gfx::screen;
 use innovus::tools::Vector;
@@ -50,7 +51,10 @@ fn main() {
     };
 
     let mut game = Game::start("sandbox/assets", viewport_size, Vector::one()).unwrap();
-    game.enter_world(Some(Box::new(OverworldGenerator::new(0))));
+    game.enter_world(
+        Some(Path::new("sandbox/saves/world")),
+        Some(Box::new(OverworldGenerator::new(0))),
+    ).unwrap();
 
     while !window.should_close() {
         input_state.reset();
@@ -84,4 +88,6 @@ fn main() {
 
         window.swap_buffers();
     }
+
+    game.exit_world();
 }
@@ -2,10 +2,12 @@ use crate::script::{Command, CommandResult, utils};
 use crate::tools::asset::AssetPool;
 use crate::world::item::Item;
 use crate::world::World;
+use crate::world::time::NAMED_TIMES;
 
 pub const BUILTIN_COMMANDS: &[Command] = &[
     Command::new("hello", 0, 0, hello),
     Command::new("give", 1, 2, give),
+    Command::new("time", 0, 2, time),
 ];
 
 pub fn hello(args: &[&str], world: &mut World, assets: &AssetPool) -> CommandResult<String> {
@@ -19,11 +21,53 @@ pub fn give(args: &[&str], world: &mut World, assets: &AssetPool) -> CommandResu
         Some(count_text) => utils::parse_u32(count_text, assets)?,
         None => 1,
     };
-    let item = if item_count > 0 && !item_type.is_air() {
-        Item::new(item_type, item_count)
-    } else {
-        Item::default()
-    };
-    world.player_mut().set_held_item(item);
-    Ok(assets.get_text("command.success").into())
+    if item_count == 0 || item_type.is_air() {
+        return Ok(assets.get_text("command.success").into());
+    }
+    let leftover = world.player_mut().inventory_mut().insert(Item::new(item_type, item_count));
+    let item_key = format!("item.{item_type}");
+    let item_name = assets.get_text(&item_key);
+    let given_count = (item_count - leftover.count()).to_string();
+    if leftover.is_empty() {
+        Ok(assets.get_template_text("command.give.success", &[&given_count, item_name]))
+    }
+    else {
+        Ok(assets.get_template_text("command.give.inventory_full", &[&given_count, item_name, &leftover.count().to_string()]))
+    }
+}
+
+pub fn time(args: &[&str], world: &mut World, assets: &AssetPool) -> CommandResult<String> {
+    match args {
+        [] => {}
+        ["set", value] => {
+            match NAMED_TIMES.iter().find(|&&(name, _)| name == *value) {
+                Some(&(_, time_of_day)) => world.time_mut().skip_to(time_of_day),
+                None => {
+                    let ticks = utils::parse_u64(value, assets)?;
+                    world.time_mut().set_ticks(ticks);
+                }
+            }
+        }
+        ["add", value] => {
+            let ticks = utils::parse_u64(value, assets)?;
+            let time = world.time_mut();
+            time.set_ticks(time.ticks().saturating_add(ticks));
+        }
+        ["length", value] => {
+            let day_length = utils::parse_u64(value, assets)?;
+            if day_length == 0 {
+                return Err(assets.get_template_text("command.error.invalid_day_length", &[value]));
+            }
+            if !world.time_mut().set_day_length(day_length) {
+                return Err(assets.get_template_text("command.error.day_length_too_long", &[value]));
+            }
+        }
+        _ => return Err(assets.get_text("command.error.syntax").into()),
+    }
+    let time = world.time();
+    Ok(assets.get_template_text("command.time.query", &[
+        &time.day().to_string(),
+        &(time.ticks() % time.day_length()).to_string(),
+        &time.day_length().to_string(),
+    ]))
 }
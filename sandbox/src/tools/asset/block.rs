@@ -2,7 +2,7 @@ use std::hash::{Hash, Hasher};
 use json::JsonValue;
 use innovus::tools::{Rectangle, Vector};
 use crate::tools::noise::{scramble, SimpleHasher};
-use crate::world::block::{AttributeType, AttributeValue, Block, BlockType, Chunk, ChunkLocation, ChunkMap};
+use crate::world::block::{AttributeType, AttributeValue, Block, BlockType, Chunk, ChunkLocation, ChunkMap, WallType};
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 #[repr(usize)]
@@ -152,6 +152,27 @@ impl BlockImage {
     }
 
     pub fn get_quadrant_atlas_offsets(&self, chunk_map: &ChunkMap, chunk: &Chunk, block: &Block, x: usize, y: usize) -> [Vector<u32, 2>; 4] {
+        self.get_connected_atlas_offsets(x, y, |that_x, that_y| {
+            chunk
+                .with_block_slot(that_x, that_y, chunk_map, |that_slot| {
+                    block.connects_to(that_slot.block())
+                })
+                .unwrap_or(true)
+        })
+    }
+
+    /// Get the atlas offsets for each quadrant of a wall, which connects to walls of the same type.
+    pub fn get_wall_quadrant_atlas_offsets(&self, chunk_map: &ChunkMap, chunk: &Chunk, wall_type: &WallType, x: usize, y: usize) -> [Vector<u32, 2>; 4] {
+        self.get_connected_atlas_offsets(x, y, |that_x, that_y| {
+            chunk
+                .with_block_slot(that_x, that_y, chunk_map, |that_slot| {
+                    that_slot.wall() == wall_type
+                })
+                .unwrap_or(true)
+        })
+    }
+
+    fn get_connected_atlas_offsets(&self, x: usize, y: usize, connects: impl Fn(isize, isize) -> bool) -> [Vector<u32, 2>; 4] {
         if let BlockImageFormat::Single = self.format {
             return [self.atlas_offset; 4];
         }
@@ -161,16 +182,16 @@ impl BlockImage {
         let down_y = y as isize - 1;
         let up_y = y as isize + 1;
 
-        let left_connect = self.get_connect(chunk_map, chunk, block, left_x, y as isize);
-        let right_connect = self.get_connect(chunk_map, chunk, block, right_x, y as isize);
-        let down_connect = self.get_connect(chunk_map, chunk, block, x as isize, down_y);
-        let up_connect = self.get_connect(chunk_map, chunk, block, x as isize, up_y);
+        let left_connect = connects(left_x, y as isize);
+        let right_connect = connects(right_x, y as isize);
+        let down_connect = connects(x as isize, down_y);
+        let up_connect = connects(x as isize, up_y);
 
         let shape_offsets: [u32; 4] = [
-            self.get_shape_offset(chunk_map, chunk, block, left_x, up_y, left_connect, up_connect),
-            self.get_shape_offset(chunk_map, chunk, block, right_x, up_y, right_connect, up_connect),
-            self.get_shape_offset(chunk_map, chunk, block, left_x, down_y, left_connect, down_connect),
-            self.get_shape_offset(chunk_map, chunk, block, right_x, down_y, right_connect, down_connect),
+            self.get_shape_offset(&connects, left_x, up_y, left_connect, up_connect),
+            self.get_shape_offset(&connects, right_x, up_y, right_connect, up_connect),
+            self.get_shape_offset(&connects, left_x, down_y, left_connect, down_connect),
+            self.get_shape_offset(&connects, right_x, down_y, right_connect, down_connect),
         ];
 
         shape_offsets.map(|shape_offset| Vector([
@@ -179,7 +200,16 @@ impl BlockImage {
         ]))
     }
 
-    fn get_shape_offset(&self, chunk_map: &ChunkMap, chunk: &Chunk, block: &Block, that_x: isize, that_y: isize, x_connect: bool, y_connect: bool) -> u32 {
+    /// Get the atlas offsets for each quadrant of the block as if it had no neighbors.
+    pub fn get_isolated_atlas_offsets(&self) -> [Vector<u32, 2>; 4] {
+        let shape_offset = match &self.format {
+            BlockImageFormat::Single => 0,
+            BlockImageFormat::ConnectedSimple { shape_offsets } => shape_offsets[SimpleShape::OutwardCorners as usize],
+        };
+        [Vector([self.atlas_offset.x() + shape_offset * self.size, self.atlas_offset.y()]); 4]
+    }
+
+    fn get_shape_offset(&self, connects: &impl Fn(isize, isize) -> bool, that_x: isize, that_y: isize, x_connect: bool, y_connect: bool) -> u32 {
         match &self.format {
             BlockImageFormat::Single => {
                 0
@@ -190,7 +220,7 @@ impl BlockImage {
                     (true, false) => SimpleShape::Horizontal,
                     (false, true) => SimpleShape::Vertical,
                     (true, true) => {
-                        if self.get_connect(chunk_map, chunk, block, that_x, that_y) {
+                        if connects(that_x, that_y) {
                             SimpleShape::Fill
                         }
                         else {
@@ -203,14 +233,6 @@ impl BlockImage {
             }
         }
     }
-
-    fn get_connect(&self, chunk_map: &ChunkMap, chunk: &Chunk, this_block: &Block, that_x: isize, that_y: isize) -> bool {
-        chunk
-            .with_block_slot(that_x, that_y, chunk_map, |that_slot| {
-                this_block.connects_to(that_slot.block())
-            })
-            .unwrap_or(true)
-    }
 }
 
 fn parse_attribute_value(attribute_type: &AttributeType, value: &JsonValue) -> Option<AttributeValue> {
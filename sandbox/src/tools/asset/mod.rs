@@ -4,11 +4,11 @@ use std::path::{Path, PathBuf};
 use json::JsonValue;
 use innovus::gfx::{Image, ImageAtlas, Program, ProgramPreset, Shader, ShaderType, Texture2D, TextureSampling, TextureWrap};
 use innovus::gfx::color::ColorPalette;
-use innovus::tools::Rectangle;
+use innovus::tools::{Rectangle, Vector};
 use crate::tools::asset::block::{BlockAppearance, BlockImage};
 use crate::tools::asset::entity::EntityImage;
-use crate::world::block::{Block, BlockType, ChunkLocation, BLOCK_TYPES};
-use crate::world::item::ItemType;
+use crate::world::block::{Block, BlockType, ChunkLocation, WallType, BLOCK_TYPES, WALL_TYPES};
+use crate::world::item::{ItemType, Recipe, RecipeBook};
 use crate::world::item::types::ITEM_TYPES;
 
 pub mod anim;
@@ -25,6 +25,8 @@ pub struct AssetPool {
     block_texture: Texture2D,
     block_atlas: ImageAtlas,
     block_appearances: HashMap<*const BlockType, BlockAppearance>,
+    wall_images: HashMap<*const WallType, BlockImage>,
+    crack_image: Option<Rectangle<u32>>,
     block_shaders: Program,
     item_texture: Texture2D,
     item_atlas: ImageAtlas,
@@ -34,6 +36,7 @@ pub struct AssetPool {
     entity_images: HashMap<String, EntityImage>,
     color_palettes: HashMap<String, ColorPalette>,
     text_strings: HashMap<String, String>,
+    recipes: RecipeBook,
 }
 
 impl AssetPool {
@@ -57,6 +60,8 @@ impl AssetPool {
             block_texture: create_texture(0),
             block_atlas: ImageAtlas::new(Default::default()),
             block_appearances: HashMap::new(),
+            wall_images: HashMap::new(),
+            crack_image: None,
             block_shaders: Program::create()?,
             item_texture: create_texture(0),
             item_atlas: ImageAtlas::new(Default::default()),
@@ -66,6 +71,7 @@ impl AssetPool {
             entity_images: HashMap::new(),
             color_palettes: HashMap::new(),
             text_strings: HashMap::new(),
+            recipes: RecipeBook::new(),
         };
 
         // Despite the name of the method, this loads everything for the first time
@@ -108,6 +114,8 @@ impl AssetPool {
         self.clear_color_palettes();
         self.reload_shaders()?;
         self.reload_text_strings()?;
+        // Recipes report errors using text strings, so those need to be loaded first
+        self.reload_recipes()?;
 
         Ok(())
     }
@@ -158,6 +166,7 @@ impl AssetPool {
 
     pub fn reload_block_appearances(&mut self) -> Result<(), String> {
         self.block_appearances.clear();
+        self.wall_images.clear();
         self.block_atlas.clear();
 
         let default_block_image = {
@@ -200,6 +209,35 @@ impl AssetPool {
             self.block_appearances.insert(block_type, block_appearance);
         }
 
+        // Walls share the block atlas so that chunks can draw both in a single mesh
+        for &wall_type in WALL_TYPES {
+            let path = format!("images/wall/{wall_type}");
+
+            let Ok(loaded_image) = self.load_image(&path) else {
+                continue;
+            };
+            let atlas_offset = self.block_atlas.add_image(&loaded_image);
+            let atlas_region = Rectangle::from_span(atlas_offset, loaded_image.size());
+
+            let mut wall_image;
+            if let Ok(metadata) = self.load_json(&path) {
+                wall_image = BlockImage::parse(format!("wall/{wall_type}"), &metadata, Some(&default_block_image))?;
+            }
+            else {
+                wall_image = default_block_image.clone();
+                wall_image.set_key(format!("wall/{wall_type}"));
+            }
+            wall_image.set_atlas_region(atlas_region)?;
+
+            self.wall_images.insert(wall_type, wall_image);
+        }
+
+        // The crack stages shown over a block being mined, laid out left to right in one image
+        self.crack_image = self.load_image("images/block/crack").ok().map(|loaded_image| {
+            let atlas_offset = self.block_atlas.add_image(&loaded_image);
+            Rectangle::from_span(atlas_offset, loaded_image.size())
+        });
+
         self.block_texture.upload_image(self.block_atlas.image());
 
         Ok(())
@@ -213,6 +251,16 @@ impl AssetPool {
         self.get_block_appearance(block.block_type()).get_image(block, chunk_location, x, y)
     }
 
+    /// Get the image for a type of wall, or `None` if it has no appearance (e.g. air).
+    pub fn get_wall_image(&self, wall_type: &'static WallType) -> Option<&BlockImage> {
+        self.wall_images.get(&(wall_type as *const _))
+    }
+
+    /// Get the atlas region containing every crack stage, or `None` if the image is missing.
+    pub fn crack_image(&self) -> Option<Rectangle<u32>> {
+        self.crack_image
+    }
+
     pub fn block_shaders(&self) -> &Program {
         &self.block_shaders
     }
@@ -278,6 +326,29 @@ impl AssetPool {
         }
     }
 
+    /// Get the image of an item type for showing it in the world as an entity. Since entities are
+    /// drawn from the entity atlas, the item image is added there the first time it is needed.
+    pub fn get_item_entity_image(&mut self, item_type: &'static ItemType) -> Result<EntityImage, String> {
+        let key = format!("item/{item_type}");
+        if let Some(entity_image) = self.entity_images.get(&key) {
+            Ok(entity_image.clone())
+        }
+        else {
+            let loaded_image = self.load_image(format!("images/{key}"))?;
+            let atlas_offset = self.entity_atlas.add_image(&loaded_image);
+            let atlas_region = Rectangle::from_span(atlas_offset, loaded_image.size());
+            self.entity_texture.upload_image(self.entity_atlas.image());
+
+            let entity_image = EntityImage {
+                world_offset: Rectangle::from_span(Vector::zero(), loaded_image.size().map(|x| x as f32 / 16.0)),
+                atlas_base_region: atlas_region,
+                animation: None,
+            };
+            self.entity_images.insert(key, entity_image.clone());
+            Ok(entity_image)
+        }
+    }
+
     pub fn clear_entity_images(&mut self) {
         self.entity_images.clear();
         self.entity_atlas.clear();
@@ -336,6 +407,40 @@ impl AssetPool {
         Ok(())
     }
 
+    pub fn recipes(&self) -> &RecipeBook {
+        &self.recipes
+    }
+
+    pub fn reload_recipes(&mut self) -> Result<(), String> {
+        self.recipes.clear();
+
+        let recipes_path = self.resolve_path("recipes");
+        let entries = std::fs::read_dir(&recipes_path)
+            .map_err(|err| format!("failed to read recipes at '{}': {err}", recipes_path.display()))?;
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? == "json" {
+                    Some(path.file_stem()?.to_str()?.to_string())
+                }
+                else {
+                    None
+                }
+            })
+            .collect();
+        // Load in a consistent order so that overlapping recipes always resolve the same way
+        names.sort();
+
+        for name in names {
+            let data = self.load_json(format!("recipes/{name}"))?;
+            let recipe = Recipe::from_json(name.clone(), &data, self)
+                .map_err(|err| format!("invalid recipe '{name}': {err}"))?;
+            self.recipes.add(recipe);
+        }
+
+        Ok(())
+    }
+
     pub fn reload_shaders(&mut self) -> Result<(), String> {
         self.gui_shaders.attach_shader(&Shader::create(
             &self.load_text("shaders/gui_v.glsl")?,
@@ -20,6 +20,36 @@ pub fn scramble(mut n: u64) -> u64 {
     n
 }
 
+/// A small deterministic random number generator, for when results must be reproducible
+/// from a seed (e.g. anything that affects the world).
+#[derive(Clone, Debug)]
+pub struct SimpleRandom {
+    state: u64,
+}
+
+impl SimpleRandom {
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            state: scramble(seed),
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        scramble(self.state)
+    }
+
+    /// A random integer in `0..bound`. `bound` must not be 0.
+    pub fn next_below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// Returns `true` once every `n` calls on average.
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.next_below(n) == 0
+    }
+}
+
 #[derive(Default)]
 pub struct SimpleHasher {
     state: FnvHasher,
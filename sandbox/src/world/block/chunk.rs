@@ -1,13 +1,25 @@
 use std::cell::{Ref, RefCell, RefMut};
-use std::collections::BTreeMap;
-use innovus::gfx::{MeshRenderer, Vertex2D};
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{Hash, Hasher};
+use innovus::gfx::MeshRenderer;
 use innovus::tools::{Rectangle, Vector};
-use innovus::tools::phys::{Collider, ColliderHandle, Physics};
+use innovus::tools::phys::{Collider, ColliderHandle, Physics, ALL_LAYERS};
 use crate::tools::asset::AssetPool;
+use crate::tools::noise::{SimpleHasher, SimpleRandom};
 use crate::world::gen::WorldGenerator;
+use crate::world::save::{DataReader, DataWriter, WorldSave};
+use crate::world::TERRAIN_LAYER;
 use super::*;
 
 pub const CHUNK_SIZE: usize = 16;
+/// The index of the first vertex of the wall layer in a chunk mesh.
+const WALL_LAYER_VERTEX_START: usize = 0;
+/// The index of the first vertex of the block layer in a chunk mesh, which follows the wall layer.
+const BLOCK_LAYER_VERTEX_START: usize = CHUNK_SIZE * CHUNK_SIZE * VERTICES_PER_BLOCK;
+/// How much darker walls are than blocks in the same light.
+const WALL_BRIGHTNESS: f32 = 0.5;
+/// The number of blocks in each simulated chunk which receive a random tick every tick.
+pub const RANDOM_TICKS_PER_CHUNK: usize = 3;
 
 pub fn resolve_relative_coordinate(value: isize) -> (i64, usize) {
     (
@@ -23,6 +35,14 @@ pub fn light_value(effective_light: u8) -> f32 {
         .clamp(0.0, 1.0)
 }
 
+/// Block light, with a separate 0-15 level for each of the red, green and blue channels.
+pub type BlockLight = Vector<u8, 3>;
+
+/// Dim a light color so that its brightest channel is scaled down to `level` out of 15.
+pub fn scale_light(color: BlockLight, level: u8) -> BlockLight {
+    color.map(|channel| (channel as u16 * level.min(15) as u16 / 15) as u8)
+}
+
 pub type ChunkLocation = Vector<i64, 2>;
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -58,7 +78,8 @@ impl From<BlockCoord> for i64 {
 #[derive(Clone, Debug)]
 pub struct BlockSlot {
     block: Block,
-    block_light: u8,
+    wall: &'static WallType,
+    block_light: BlockLight,
     sky_light: u8,
     needs_render: bool,
 }
@@ -68,7 +89,11 @@ impl BlockSlot {
         &self.block
     }
 
-    pub fn block_light(&self) -> u8 {
+    pub fn wall(&self) -> &'static WallType {
+        self.wall
+    }
+
+    pub fn block_light(&self) -> BlockLight {
         self.block_light
     }
 
@@ -80,8 +105,52 @@ impl BlockSlot {
         self.needs_render
     }
 
-    pub fn light_value(&self) -> f32 {
-        light_value(self.block_light.max(self.sky_light))
+    /// Get one of the light channels, where 0 to 2 are red, green and blue block light and
+    /// [`SKY_LIGHT_CHANNEL`] is sky light.
+    pub fn light_channel(&self, channel: usize) -> u8 {
+        if channel == SKY_LIGHT_CHANNEL {
+            self.sky_light
+        }
+        else {
+            self.block_light[channel]
+        }
+    }
+
+    /// How much light in a channel is lost when passing into this slot. Walls only hold back sky
+    /// light, so block light passes through them freely.
+    pub fn light_opacity(&self, channel: usize) -> u8 {
+        if channel == SKY_LIGHT_CHANNEL {
+            self.block.light_opacity().max(self.wall.light_opacity())
+        }
+        else {
+            self.block.light_opacity()
+        }
+    }
+
+    /// Whether this slot casts a shadow from the sky, either from its block or its wall.
+    pub fn blocks_sky_light(&self) -> bool {
+        self.block.blocks_sky_light() || self.wall.blocks_sky_light()
+    }
+
+    /// Everything about this slot which affects how light spreads: the light it emits, followed by
+    /// how much block light and sky light are lost passing into it.
+    fn light_properties(&self) -> (BlockLight, u8, u8) {
+        (
+            self.block.light_emission(),
+            self.block.light_opacity(),
+            self.light_opacity(SKY_LIGHT_CHANNEL),
+        )
+    }
+
+    /// The light this slot is rendered with: red, green and blue block light, followed by sky
+    /// light, which is dimmed by the block shader according to the time of day.
+    pub fn vertex_light(&self) -> Vector<f32, 4> {
+        Vector([
+            light_value(self.block_light[0]),
+            light_value(self.block_light[1]),
+            light_value(self.block_light[2]),
+            light_value(self.sky_light),
+        ])
     }
 }
 
@@ -89,13 +158,88 @@ impl Default for BlockSlot {
     fn default() -> Self {
         Self {
             block: Default::default(),
-            block_light: 0,
+            wall: &wall::AIR,
+            block_light: Vector::zero(),
             sky_light: 0,
             needs_render: true,
         }
     }
 }
 
+const CHUNK_DATA_VERSION: u32 = 4;
+// Version 1 chunks have no scheduled ticks, versions before 3 store a single block light level,
+// and versions before 4 have no walls
+const MIN_CHUNK_DATA_VERSION: u32 = 1;
+
+const ATTRIBUTE_TAG_BOOL: u8 = 0;
+const ATTRIBUTE_TAG_U8: u8 = 1;
+const ATTRIBUTE_TAG_I8: u8 = 2;
+const ATTRIBUTE_TAG_U32: u8 = 3;
+const ATTRIBUTE_TAG_I32: u8 = 4;
+const ATTRIBUTE_TAG_STRING: u8 = 5;
+
+fn write_attribute_value(writer: &mut DataWriter, attribute_type: &AttributeType, value: &AttributeValue) {
+    match (attribute_type, value) {
+        // Enum values are stored by name so that reordering the value names doesn't break old saves
+        (AttributeType::Enum { value_names, .. }, &AttributeValue::U8(index)) => {
+            writer.write_u8(ATTRIBUTE_TAG_STRING);
+            writer.write_str(value_names.get(index as usize).copied().unwrap_or_default());
+        }
+        (_, &AttributeValue::Bool(value)) => {
+            writer.write_u8(ATTRIBUTE_TAG_BOOL);
+            writer.write_u8(value as u8);
+        }
+        (_, &AttributeValue::U8(value)) => {
+            writer.write_u8(ATTRIBUTE_TAG_U8);
+            writer.write_u8(value);
+        }
+        (_, &AttributeValue::I8(value)) => {
+            writer.write_u8(ATTRIBUTE_TAG_I8);
+            writer.write_i8(value);
+        }
+        (_, &AttributeValue::U32(value)) => {
+            writer.write_u8(ATTRIBUTE_TAG_U32);
+            writer.write_u32(value);
+        }
+        (_, &AttributeValue::I32(value)) => {
+            writer.write_u8(ATTRIBUTE_TAG_I32);
+            writer.write_i32(value);
+        }
+        (_, AttributeValue::String(value)) => {
+            writer.write_u8(ATTRIBUTE_TAG_STRING);
+            writer.write_str(value);
+        }
+    }
+}
+
+fn read_attribute_value(reader: &mut DataReader) -> Result<AttributeValue, String> {
+    match reader.read_u8()? {
+        ATTRIBUTE_TAG_BOOL => Ok(AttributeValue::Bool(reader.read_u8()? != 0)),
+        ATTRIBUTE_TAG_U8 => Ok(AttributeValue::U8(reader.read_u8()?)),
+        ATTRIBUTE_TAG_I8 => Ok(AttributeValue::I8(reader.read_i8()?)),
+        ATTRIBUTE_TAG_U32 => Ok(AttributeValue::U32(reader.read_u32()?)),
+        ATTRIBUTE_TAG_I32 => Ok(AttributeValue::I32(reader.read_i32()?)),
+        ATTRIBUTE_TAG_STRING => Ok(AttributeValue::String(reader.read_str()?.into())),
+        tag => Err(format!("invalid attribute tag {tag}")),
+    }
+}
+
+fn convert_attribute_value(attribute_type: &AttributeType, value: AttributeValue) -> Option<AttributeValue> {
+    match (attribute_type, value) {
+        (AttributeType::Enum { value_names, .. }, AttributeValue::String(name)) => {
+            let index = value_names.iter().position(|&value_name| value_name == name)?;
+            Some(AttributeValue::U8(index as u8))
+        }
+        (AttributeType::Bool(..), value @ AttributeValue::Bool(..)) => Some(value),
+        (AttributeType::U8(..), value @ AttributeValue::U8(..)) => Some(value),
+        (AttributeType::I8(..), value @ AttributeValue::I8(..)) => Some(value),
+        (AttributeType::U32(..), value @ AttributeValue::U32(..)) => Some(value),
+        (AttributeType::I32(..), value @ AttributeValue::I32(..)) => Some(value),
+        (AttributeType::String(..), value @ AttributeValue::String(..)) => Some(value),
+        _ => None,
+    }
+}
+
 const ADJACENT_OFFSETS: [(isize, isize); 4] = [(0, 1), (0, -1), (-1, 0), (1, 0)];
 
 #[derive(Debug)]
@@ -104,8 +248,11 @@ pub struct Chunk {
     block_slots: [[BlockSlot; CHUNK_SIZE]; CHUNK_SIZE],
     collision_map: Option<[[Box<[ColliderHandle]>; CHUNK_SIZE]; CHUNK_SIZE]>,
     render_all: bool,
-    mesh: MeshRenderer<Vertex2D>,
+    /// Created on the first render, so that chunks can exist without a graphics context.
+    mesh: Option<MeshRenderer<BlockVertex>>,
     height_map: [i64; CHUNK_SIZE],
+    light_initialized: bool,
+    scheduled_ticks: Vec<ScheduledTick>,
 }
 
 impl Chunk {
@@ -115,8 +262,10 @@ impl Chunk {
             block_slots: Default::default(),
             collision_map: None,
             render_all: true,
-            mesh: MeshRenderer::create(),
+            mesh: None,
             height_map: Default::default(),
+            light_initialized: false,
+            scheduled_ticks: Vec::new(),
         }
     }
 
@@ -128,18 +277,189 @@ impl Chunk {
         &self.height_map
     }
 
+    /// Set the height of the highest block casting a shadow in each column, at or above which
+    /// blocks receive direct sunlight. This is kept up to date as blocks change once lighting
+    /// has been initialized.
     pub fn set_height_map(&mut self, height_map: [i64; CHUNK_SIZE]) {
         self.height_map = height_map;
+    }
+
+    /// Light the entire chunk from scratch, including light entering from neighboring chunks.
+    /// Until this is called, placing blocks doesn't update lighting, which keeps world
+    /// generation fast.
+    pub fn initialize_lighting(&mut self, chunk_map: &ChunkMap) {
+        let positions: Vec<Vector<i64, 2>> = (0..CHUNK_SIZE)
+            .flat_map(|y| (0..CHUNK_SIZE).map(move |x| (x, y)))
+            .map(|(x, y)| self.block_position(x, y))
+            .collect();
+        LightEngine::new(self, chunk_map).relight(&positions);
+        self.light_initialized = true;
+    }
+
+    pub fn write_data(&self, writer: &mut DataWriter) {
+        writer.write_u32(CHUNK_DATA_VERSION);
+        for height in self.height_map {
+            writer.write_i64(height);
+        }
 
-        // Update sky light
-        for (x, terrain_height) in (0..CHUNK_SIZE).zip(height_map) {
-            let base_y = self.location.y() * CHUNK_SIZE as i64;
-            for y in 0..CHUNK_SIZE {
-                let blocks_to_surface = terrain_height - (base_y + y as i64);
-                let sky_light = 15 - blocks_to_surface.clamp(0, 15) as u8;
-                self.block_slots[y][x].sky_light = sky_light;
+        // Block types are written once in a palette, then referenced by index in each slot
+        let mut palette: Vec<&'static BlockType> = Vec::new();
+        let palette_indices: Vec<u16> = self.block_slots
+            .iter()
+            .flatten()
+            .map(|slot| {
+                let block_type = slot.block().block_type();
+                let index = palette
+                    .iter()
+                    .position(|&palette_type| palette_type == block_type)
+                    .unwrap_or_else(|| {
+                        palette.push(block_type);
+                        palette.len() - 1
+                    });
+                index as u16
+            })
+            .collect();
+
+        writer.write_u16(palette.len() as u16);
+        for block_type in &palette {
+            writer.write_str(block_type.name());
+            writer.write_u8(block_type.attributes().len() as u8);
+            for (attribute_name, _) in block_type.attributes() {
+                writer.write_str(attribute_name);
             }
         }
+
+        for (slot, palette_index) in self.block_slots.iter().flatten().zip(palette_indices) {
+            writer.write_u16(palette_index);
+            let attribute_types = slot.block().block_type().attributes();
+            for ((_, attribute_type), value) in attribute_types.iter().zip(slot.block().attributes()) {
+                write_attribute_value(writer, attribute_type, value);
+            }
+            for channel in slot.block_light.0 {
+                writer.write_u8(channel);
+            }
+            writer.write_u8(slot.sky_light);
+        }
+
+        writer.write_u32(self.scheduled_ticks.len() as u32);
+        for scheduled_tick in &self.scheduled_ticks {
+            writer.write_u8(scheduled_tick.x as u8);
+            writer.write_u8(scheduled_tick.y as u8);
+            writer.write_u64(scheduled_tick.due_tick);
+        }
+
+        // Walls get a palette of their own in the same way as blocks
+        let mut wall_palette: Vec<&'static WallType> = Vec::new();
+        let wall_palette_indices: Vec<u16> = self.block_slots
+            .iter()
+            .flatten()
+            .map(|slot| {
+                let index = wall_palette
+                    .iter()
+                    .position(|&palette_type| palette_type == slot.wall())
+                    .unwrap_or_else(|| {
+                        wall_palette.push(slot.wall());
+                        wall_palette.len() - 1
+                    });
+                index as u16
+            })
+            .collect();
+
+        writer.write_u16(wall_palette.len() as u16);
+        for wall_type in &wall_palette {
+            writer.write_str(wall_type.name());
+        }
+        for palette_index in wall_palette_indices {
+            writer.write_u16(palette_index);
+        }
+    }
+
+    pub fn read_data(location: ChunkLocation, reader: &mut DataReader) -> Result<Self, String> {
+        let version = reader.read_u32()?;
+        if !(MIN_CHUNK_DATA_VERSION..=CHUNK_DATA_VERSION).contains(&version) {
+            return Err(format!("unsupported chunk data version {version}"));
+        }
+
+        let mut chunk = Self::new(location);
+        chunk.light_initialized = true;
+        for height in &mut chunk.height_map {
+            *height = reader.read_i64()?;
+        }
+
+        // For each palette entry, map the saved attribute order onto the current one
+        // so that attributes added or removed since the chunk was saved are handled gracefully
+        let palette_length = reader.read_u16()?;
+        let mut palette = Vec::with_capacity(palette_length as usize);
+        for _ in 0..palette_length {
+            let name = reader.read_str()?;
+            let block_type = get_block_type(name)
+                .ok_or_else(|| format!("unknown block type '{name}'"))?;
+            let attribute_count = reader.read_u8()?;
+            let mut attribute_indices = Vec::with_capacity(attribute_count as usize);
+            for _ in 0..attribute_count {
+                let attribute_name = reader.read_str()?;
+                attribute_indices.push(block_type.get_attribute_info(attribute_name).map(|(index, _)| index));
+            }
+            palette.push((block_type, attribute_indices));
+        }
+
+        for slot in chunk.block_slots.iter_mut().flatten() {
+            let palette_index = reader.read_u16()?;
+            let (block_type, attribute_indices) = palette
+                .get(palette_index as usize)
+                .ok_or_else(|| format!("palette index {palette_index} is out of range"))?;
+            let mut block = Block::new(block_type, BlockSide::None);
+            for &attribute_index in attribute_indices {
+                let value = read_attribute_value(reader)?;
+                if let Some(index) = attribute_index {
+                    let (_, attribute_type) = &block_type.attributes()[index];
+                    if let Some(value) = convert_attribute_value(attribute_type, value) {
+                        block.set_attribute_value(index, value);
+                    }
+                }
+            }
+            slot.block = block;
+            slot.block_light = if version >= 3 {
+                Vector([reader.read_u8()?, reader.read_u8()?, reader.read_u8()?])
+            }
+            else {
+                Vector::filled(reader.read_u8()?)
+            };
+            slot.sky_light = reader.read_u8()?;
+        }
+
+        if version >= 2 {
+            let scheduled_tick_count = reader.read_u32()?;
+            for _ in 0..scheduled_tick_count {
+                let x = reader.read_u8()? as usize;
+                let y = reader.read_u8()? as usize;
+                let due_tick = reader.read_u64()?;
+                if x >= CHUNK_SIZE || y >= CHUNK_SIZE {
+                    return Err(format!("scheduled tick position ({x}, {y}) is out of range"));
+                }
+                chunk.schedule_tick(x, y, due_tick);
+            }
+        }
+
+        if version >= 4 {
+            let wall_palette_length = reader.read_u16()?;
+            let mut wall_palette = Vec::with_capacity(wall_palette_length as usize);
+            for _ in 0..wall_palette_length {
+                let name = reader.read_str()?;
+                let wall_type = get_wall_type(name)
+                    .ok_or_else(|| format!("unknown wall type '{name}'"))?;
+                wall_palette.push(wall_type);
+            }
+            for slot in chunk.block_slots.iter_mut().flatten() {
+                let palette_index = reader.read_u16()?;
+                slot.wall = wall_palette
+                    .get(palette_index as usize)
+                    .copied()
+                    .ok_or_else(|| format!("wall palette index {palette_index} is out of range"))?;
+            }
+        }
+
+        Ok(chunk)
     }
 
     pub fn block_slot_at(&self, x: usize, y: usize) -> &BlockSlot {
@@ -150,19 +470,47 @@ impl Chunk {
         self.block_slots[y][x].block()
     }
 
+    pub fn wall_at(&self, x: usize, y: usize) -> &'static WallType {
+        self.block_slots[y][x].wall()
+    }
+
     pub fn sky_light_at(&self, x: usize, y: usize) -> u8 {
         self.block_slots[y][x].sky_light()
     }
 
-    pub fn block_light_at(&self, x: usize, y: usize) -> u8 {
+    pub fn block_light_at(&self, x: usize, y: usize) -> BlockLight {
         self.block_slots[y][x].block_light()
     }
 
+    pub fn block_colliders_at(&self, x: usize, y: usize) -> Option<&[ColliderHandle]> {
+        self.collision_map.as_ref().map(|collision_map| &collision_map[y][x][..])
+    }
+
     pub fn is_dirty_at(&self, x: usize, y: usize) -> bool {
         self.block_slots[y][x].needs_render()
     }
 
     pub fn set_block_at(&mut self, x: usize, y: usize, block: Block, chunk_map: &ChunkMap, physics: &mut Physics) {
+        // Any power networks touching this block will need to be recalculated
+        let position = self.block_position(x, y);
+        let old_block = self.block_at(x, y);
+        if old_block.is_power_component() || block.is_power_component() {
+            chunk_map.power_grid().mark_dirty(position);
+        }
+        if is_pipe(old_block) || is_pipe(&block) {
+            chunk_map.pipe_grid().mark_dirty(position);
+        }
+        self.replace_block_at(x, y, block, chunk_map, physics);
+
+        // Let the block and its neighbors react to the change
+        chunk_map.queue_block_update(position);
+        for (dx, dy) in ADJACENT_OFFSETS {
+            chunk_map.queue_block_update(position + Vector([dx as i64, dy as i64]));
+        }
+    }
+
+    /// Set a block without notifying anything but the renderer and lighting.
+    pub(super) fn replace_block_at(&mut self, x: usize, y: usize, block: Block, chunk_map: &ChunkMap, physics: &mut Physics) {
         if let Some(collision_map) = &mut self.collision_map {
             // Add new physics colliders and remove the old ones
             let new_colliders = Self::create_block_colliders(self.location, x, y, &block, physics);
@@ -172,12 +520,114 @@ impl Chunk {
             }
         }
 
+        let old_light_properties = self.block_slots[y][x].light_properties();
         self.block_slots[y][x].block = block;
         self.block_slots[y][x].needs_render = true;
         // Propagate the render flag to the surrounding blocks in order to update their appearances
         self.propagate_render_flag(x, y, chunk_map);
-        // Update lighting around the block
-        self.update_lighting(x as isize, y as isize, chunk_map);
+
+        self.update_lighting_at(x, y, old_light_properties, chunk_map);
+    }
+
+    /// Set the wall behind a block. Walls don't collide with anything, so only the renderer and
+    /// lighting need to know about the change.
+    pub fn set_wall_at(&mut self, x: usize, y: usize, wall_type: &'static WallType, chunk_map: &ChunkMap) {
+        let old_light_properties = self.block_slots[y][x].light_properties();
+        self.block_slots[y][x].wall = wall_type;
+        self.block_slots[y][x].needs_render = true;
+        // Neighboring walls may connect to this one
+        self.propagate_render_flag(x, y, chunk_map);
+
+        self.update_lighting_at(x, y, old_light_properties, chunk_map);
+    }
+
+    /// Update lighting around a slot if the change to it could have affected anything.
+    fn update_lighting_at(&mut self, x: usize, y: usize, old_light_properties: (BlockLight, u8, u8), chunk_map: &ChunkMap) {
+        let new_light_properties = self.block_slots[y][x].light_properties();
+        if !self.light_initialized || old_light_properties == new_light_properties {
+            return;
+        }
+        let (_, _, old_sky_opacity) = old_light_properties;
+        let (_, _, new_sky_opacity) = new_light_properties;
+        let mut changed_positions = vec![self.block_position(x, y)];
+        if (old_sky_opacity > 1) != (new_sky_opacity > 1) {
+            changed_positions.extend(self.update_height_map(x, y, chunk_map));
+        }
+        LightEngine::new(self, chunk_map).relight(&changed_positions);
+    }
+
+    pub(super) fn set_light_channel(&mut self, x: usize, y: usize, channel: usize, light: u8) {
+        let slot = &mut self.block_slots[y][x];
+        if channel == SKY_LIGHT_CHANNEL {
+            slot.sky_light = light;
+        }
+        else {
+            slot.block_light[channel] = light;
+        }
+    }
+
+    /// Move the sunlight cutoff for a column after the slot at (`x`, `y`) started or stopped
+    /// casting a shadow, returning every loaded position whose direct sunlight changed.
+    fn update_height_map(&mut self, x: usize, y: usize, chunk_map: &ChunkMap) -> Vec<Vector<i64, 2>> {
+        let position = self.block_position(x, y);
+        let old_height = self.height_map[x];
+        let mut column_locations: Vec<ChunkLocation> = chunk_map
+            .locations()
+            .filter(|location| location.x() == self.location.x())
+            .collect();
+        // Highest first
+        column_locations.sort_by_key(|location| std::cmp::Reverse(location.y()));
+
+        let new_height = if self.block_slot_at(x, y).blocks_sky_light() {
+            old_height.max(position.y())
+        }
+        else if position.y() == old_height {
+            // Find the next block down which casts a shadow, as far as the column is loaded
+            column_locations
+                .iter()
+                .filter(|location| location.y() <= self.location.y())
+                .find_map(|&location| {
+                    let find_highest = |chunk: &Chunk| {
+                        (0..CHUNK_SIZE)
+                            .rev()
+                            .map(|block_y| chunk.block_position(x, block_y).y())
+                            .filter(|&block_y| block_y < position.y())
+                            .find(|&block_y| {
+                                chunk.block_slot_at(x, BlockCoord::from(block_y).offset).blocks_sky_light()
+                            })
+                    };
+                    if location == self.location {
+                        find_highest(self)
+                    }
+                    else {
+                        find_highest(&*chunk_map.get(location)?)
+                    }
+                })
+                .unwrap_or(i32::MIN as i64)
+        }
+        else {
+            old_height
+        };
+        if new_height == old_height {
+            return Vec::new();
+        }
+
+        let low = old_height.min(new_height);
+        let high = old_height.max(new_height);
+        let mut changed_positions = Vec::new();
+        for location in column_locations {
+            let base_y = location.y() * CHUNK_SIZE as i64;
+            for block_y in low.max(base_y) .. high.min(base_y + CHUNK_SIZE as i64) {
+                changed_positions.push(Vector([position.x(), block_y]));
+            }
+            if location == self.location {
+                self.height_map[x] = new_height;
+            }
+            else if let Some(mut chunk) = chunk_map.get_mut(location) {
+                chunk.height_map[x] = new_height;
+            }
+        }
+        changed_positions
     }
 
     pub fn with_block_slot<F, T>(&self, x: isize, y: isize, chunk_map: &ChunkMap, f: F) -> Option<T>
@@ -196,6 +646,41 @@ impl Chunk {
         }
     }
 
+    pub fn block_position(&self, x: usize, y: usize) -> Vector<i64, 2> {
+        Vector([
+            i64::from(BlockCoord::new(self.location.x(), x)),
+            i64::from(BlockCoord::new(self.location.y(), y)),
+        ])
+    }
+
+    pub fn scheduled_ticks(&self) -> &[ScheduledTick] {
+        &self.scheduled_ticks
+    }
+
+    /// Schedule a tick for the block at (`x`, `y`). If one is already scheduled there, the
+    /// earlier of the two is kept.
+    pub fn schedule_tick(&mut self, x: usize, y: usize, due_tick: u64) {
+        let existing = self.scheduled_ticks
+            .iter_mut()
+            .find(|scheduled_tick| scheduled_tick.x == x && scheduled_tick.y == y);
+        if let Some(scheduled_tick) = existing {
+            scheduled_tick.due_tick = scheduled_tick.due_tick.min(due_tick);
+        }
+        else {
+            self.scheduled_ticks.push(ScheduledTick { x, y, due_tick });
+        }
+    }
+
+    /// Remove and return every scheduled tick which is due by `current_tick`, earliest first.
+    pub fn take_due_ticks(&mut self, current_tick: u64) -> Vec<ScheduledTick> {
+        let (mut due_ticks, pending_ticks) = std::mem::take(&mut self.scheduled_ticks)
+            .into_iter()
+            .partition(|scheduled_tick: &ScheduledTick| scheduled_tick.due_tick <= current_tick);
+        self.scheduled_ticks = pending_ticks;
+        due_ticks.sort_by_key(|scheduled_tick| (scheduled_tick.due_tick, scheduled_tick.y, scheduled_tick.x));
+        due_ticks
+    }
+
     pub fn set_all_need_render(&mut self) {
         self.render_all = true;
     }
@@ -227,165 +712,134 @@ impl Chunk {
         }
     }
 
-    pub fn update_lighting(&mut self, start_x: isize, start_y: isize, chunk_map: &ChunkMap) {
-        let mut stack = Vec::new();
-        stack.push((start_x, start_y));
-
-        while let Some((x, y)) = stack.pop() {
-            let (chunk_offset_x, block_x) = resolve_relative_coordinate(x);
-            let (chunk_offset_y, block_y) = resolve_relative_coordinate(y);
-
-            let current_light;
-            let light_emission;
-            if chunk_offset_x == 0 && chunk_offset_y == 0 {
-                let slot = self.block_slot_at(block_x, block_y);
-                current_light = slot.block_light();
-                light_emission = slot.block().light_emission();
-            }
-            else {
-                let other_chunk_location = self.relative_chunk_location(chunk_offset_x, chunk_offset_y);
-                let Some(chunk) = chunk_map.get(other_chunk_location) else {
-                    continue;
-                };
-                let slot = chunk.block_slot_at(block_x, block_y);
-                current_light = slot.block_light();
-                light_emission = slot.block().light_emission();
-            }
-
-            let max_surrounding_light = ADJACENT_OFFSETS
-                .iter()
-                .filter_map(|&(dx, dy)| {
-                    self.with_block_slot(x + dx, y + dy, chunk_map, BlockSlot::block_light)
-                })
-                .max()
-                .unwrap();
-            let expected_light = light_emission.max(max_surrounding_light.saturating_sub(1));
-
-            if chunk_offset_x == 0 && chunk_offset_y == 0 {
-                self.block_slots[block_y][block_x].block_light = expected_light;
-                self.set_all_need_render();
-            }
-            else {
-                let other_chunk_location = self.relative_chunk_location(chunk_offset_x, chunk_offset_y);
-                let mut chunk = chunk_map.get_mut(other_chunk_location).unwrap();
-                chunk.block_slots[block_y][block_x].block_light = expected_light;
-                chunk.set_all_need_render();
-            }
-
-            if current_light != expected_light {
-                // Add adjacent blocks to update stack
-                stack.extend(ADJACENT_OFFSETS.map(|(dx, dy)| (x + dx, y + dy)));
-            }
-        }
-    }
-
     pub fn render(&mut self, assets: &AssetPool, chunk_map: &ChunkMap) {
-        if self.mesh.is_empty() {
+        let mut mesh = self.mesh.take().unwrap_or_else(MeshRenderer::create);
+        if mesh.is_empty() {
             let mut vertices = Vec::new();
             let mut faces = Vec::new();
-            for block_y in 0..CHUNK_SIZE {
-                for block_x in 0..CHUNK_SIZE {
-                    for offset in QUADRANT_OFFSETS {
-                        let index = vertices.len() as u32;
-                        faces.push([index + 0, index + 1, index + 2]);
-                        faces.push([index + 2, index + 3, index + 0]);
-                        // TODO: obviously lossy
-                        let vertex_position = Vector([
-                            self.location.x() as f32 * CHUNK_SIZE as f32 + block_x as f32 + offset.x(),
-                            self.location.y() as f32 * CHUNK_SIZE as f32 + block_y as f32 + offset.y(),
-                        ]);
-                        for vertex_offset in QUADRANT_VERTEX_OFFSETS {
-                            vertices.push(Vertex2D::new(
-                                (vertex_position + vertex_offset).with_z(0.0),
-                                None,
-                                None,
-                            ));
+            // The wall layer comes first so that blocks are drawn on top of it
+            for _layer in 0..2 {
+                for block_y in 0..CHUNK_SIZE {
+                    for block_x in 0..CHUNK_SIZE {
+                        for offset in QUADRANT_OFFSETS {
+                            let index = vertices.len() as u32;
+                            faces.push([index + 0, index + 1, index + 2]);
+                            faces.push([index + 2, index + 3, index + 0]);
+                            // TODO: obviously lossy
+                            let vertex_position = Vector([
+                                self.location.x() as f32 * CHUNK_SIZE as f32 + block_x as f32 + offset.x(),
+                                self.location.y() as f32 * CHUNK_SIZE as f32 + block_y as f32 + offset.y(),
+                            ]);
+                            for vertex_offset in QUADRANT_VERTEX_OFFSETS {
+                                vertices.push(BlockVertex::new(
+                                    (vertex_position + vertex_offset).with_z(0.0),
+                                    None,
+                                    None,
+                                ));
+                            }
                         }
                     }
                 }
             }
-            self.mesh.add(&vertices, &faces);
+            mesh.add(&vertices, &faces);
         }
 
         for y in 0..CHUNK_SIZE {
             for x in 0..CHUNK_SIZE {
                 if self.render_all || self.block_slot_at(x, y).needs_render() {
-                    self.update_block_vertices(x, y, assets, chunk_map);
+                    self.update_slot_vertices(&mut mesh, x, y, assets, chunk_map);
                     self.block_slots[y][x].needs_render = false;
                 }
             }
         }
-        self.mesh.upload_vertex_buffer();
+        mesh.upload_vertex_buffer();
         self.render_all = false;
 
-        self.mesh.render();
+        mesh.render();
+        self.mesh = Some(mesh);
     }
 
-    fn update_block_vertices(&mut self, x: usize, y: usize, assets: &AssetPool, chunk_map: &ChunkMap) {
+    fn update_slot_vertices(&self, mesh: &mut MeshRenderer<BlockVertex>, x: usize, y: usize, assets: &AssetPool, chunk_map: &ChunkMap) {
         let slot = &self.block_slots[y][x];
         // (y * CHUNK_SIZE + x) blocks in, 4 quads per block, 4 vertices per quad
-        let first_index = (y * CHUNK_SIZE + x) * VERTICES_PER_BLOCK;
-
-        if let Some(image) = assets.get_block_image(slot.block(), self.location(), x, y) {
-            let quadrant_vertex_lights = {
-                let x = x as isize;
-                let y = y as isize;
-                // u = up, d = down, l = left, r = right, c = center (all relative to current block)
-                let get_light = BlockSlot::light_value;
-                let block_light_cc = slot.light_value(); // Might as well use what we have
-                let block_light_ul = self.with_block_slot(x - 1, y + 1, chunk_map, get_light).unwrap_or(block_light_cc);
-                let block_light_uc = self.with_block_slot(x + 0, y + 1, chunk_map, get_light).unwrap_or(block_light_cc);
-                let block_light_ur = self.with_block_slot(x + 1, y + 1, chunk_map, get_light).unwrap_or(block_light_cc);
-                let block_light_cl = self.with_block_slot(x - 1, y + 0, chunk_map, get_light).unwrap_or(block_light_cc);
-                let block_light_cr = self.with_block_slot(x + 1, y + 0, chunk_map, get_light).unwrap_or(block_light_cc);
-                let block_light_dl = self.with_block_slot(x - 1, y - 1, chunk_map, get_light).unwrap_or(block_light_cc);
-                let block_light_dc = self.with_block_slot(x + 0, y - 1, chunk_map, get_light).unwrap_or(block_light_cc);
-                let block_light_dr = self.with_block_slot(x + 1, y - 1, chunk_map, get_light).unwrap_or(block_light_cc);
-
-                let corner_light_ul = (block_light_ul + block_light_uc + block_light_cl + block_light_cc) / 4.0;
-                let corner_light_ur = (block_light_ur + block_light_uc + block_light_cr + block_light_cc) / 4.0;
-                let corner_light_dl = (block_light_dl + block_light_dc + block_light_cl + block_light_cc) / 4.0;
-                let corner_light_dr = (block_light_dr + block_light_dc + block_light_cr + block_light_cc) / 4.0;
-
-                let edge_light_u = (corner_light_ul + corner_light_ur) / 2.0;
-                let edge_light_d = (corner_light_dl + corner_light_dr) / 2.0;
-                let edge_light_l = (corner_light_ul + corner_light_dl) / 2.0;
-                let edge_light_r = (corner_light_ur + corner_light_dr) / 2.0;
-
-                // Outer array (block quadrant): up left, up right, down left, down right
-                // Inner array (quadrant vertices): down left, up left, up right, down right
-                [
-                    [edge_light_l, corner_light_ul, edge_light_u, block_light_cc],
-                    [block_light_cc, edge_light_u, corner_light_ur, edge_light_r],
-                    [corner_light_dl, edge_light_l, block_light_cc, edge_light_d],
-                    [edge_light_d, block_light_cc, edge_light_r, corner_light_dr],
-                ]
-            };
+        let slot_vertex_offset = (y * CHUNK_SIZE + x) * VERTICES_PER_BLOCK;
+
+        let quadrant_vertex_lights = {
+            let x = x as isize;
+            let y = y as isize;
+            // u = up, d = down, l = left, r = right, c = center (all relative to current block)
+            let get_light = BlockSlot::vertex_light;
+            let block_light_cc = slot.vertex_light(); // Might as well use what we have
+            let block_light_ul = self.with_block_slot(x - 1, y + 1, chunk_map, get_light).unwrap_or(block_light_cc);
+            let block_light_uc = self.with_block_slot(x + 0, y + 1, chunk_map, get_light).unwrap_or(block_light_cc);
+            let block_light_ur = self.with_block_slot(x + 1, y + 1, chunk_map, get_light).unwrap_or(block_light_cc);
+            let block_light_cl = self.with_block_slot(x - 1, y + 0, chunk_map, get_light).unwrap_or(block_light_cc);
+            let block_light_cr = self.with_block_slot(x + 1, y + 0, chunk_map, get_light).unwrap_or(block_light_cc);
+            let block_light_dl = self.with_block_slot(x - 1, y - 1, chunk_map, get_light).unwrap_or(block_light_cc);
+            let block_light_dc = self.with_block_slot(x + 0, y - 1, chunk_map, get_light).unwrap_or(block_light_cc);
+            let block_light_dr = self.with_block_slot(x + 1, y - 1, chunk_map, get_light).unwrap_or(block_light_cc);
+
+            let corner_light_ul = (block_light_ul + block_light_uc + block_light_cl + block_light_cc).div(4.0);
+            let corner_light_ur = (block_light_ur + block_light_uc + block_light_cr + block_light_cc).div(4.0);
+            let corner_light_dl = (block_light_dl + block_light_dc + block_light_cl + block_light_cc).div(4.0);
+            let corner_light_dr = (block_light_dr + block_light_dc + block_light_cr + block_light_cc).div(4.0);
+
+            let edge_light_u = (corner_light_ul + corner_light_ur).div(2.0);
+            let edge_light_d = (corner_light_dl + corner_light_dr).div(2.0);
+            let edge_light_l = (corner_light_ul + corner_light_dl).div(2.0);
+            let edge_light_r = (corner_light_ur + corner_light_dr).div(2.0);
+
+            // Outer array (block quadrant): up left, up right, down left, down right
+            // Inner array (quadrant vertices): down left, up left, up right, down right
+            [
+                [edge_light_l, corner_light_ul, edge_light_u, block_light_cc],
+                [block_light_cc, edge_light_u, corner_light_ur, edge_light_r],
+                [corner_light_dl, edge_light_l, block_light_cc, edge_light_d],
+                [edge_light_d, block_light_cc, edge_light_r, corner_light_dr],
+            ]
+        };
+
+        let wall_image = assets.get_wall_image(slot.wall()).map(|image| {
+            (image.size(), image.get_wall_quadrant_atlas_offsets(chunk_map, self, slot.wall(), x, y))
+        });
+        let block_image = assets.get_block_image(slot.block(), self.location(), x, y).map(|image| {
+            (image.size(), image.get_quadrant_atlas_offsets(chunk_map, self, slot.block(), x, y))
+        });
+
+        // Walls are shaded darker to set them apart from the blocks in front of them
+        let wall_vertex_lights = quadrant_vertex_lights.map(|vertex_lights| {
+            vertex_lights.map(|vertex_light| vertex_light.mul(WALL_BRIGHTNESS))
+        });
+        Self::set_quadrant_vertices(mesh, WALL_LAYER_VERTEX_START + slot_vertex_offset, wall_image, wall_vertex_lights);
+        Self::set_quadrant_vertices(mesh, BLOCK_LAYER_VERTEX_START + slot_vertex_offset, block_image, quadrant_vertex_lights);
+    }
 
-            let mut index = first_index;
-            let atlas_offsets = image.get_quadrant_atlas_offsets(chunk_map, self, slot.block(), x, y);
+    fn set_quadrant_vertices(mesh: &mut MeshRenderer<BlockVertex>, first_index: usize, image: Option<(u32, [Vector<u32, 2>; 4])>, quadrant_vertex_lights: [[Vector<f32, 4>; 4]; 4]) {
+        let mut index = first_index;
+        if let Some((image_size, atlas_offsets)) = image {
             let quadrant_info = std::iter::zip(QUADRANT_OFFSETS, atlas_offsets).zip(quadrant_vertex_lights);
             for ((quadrant_offset, atlas_offset), vertex_lights) in quadrant_info {
                 let vertex_info = std::iter::zip(QUADRANT_VERTEX_OFFSETS, vertex_lights);
                 for (vertex_offset, vertex_light) in vertex_info {
-                    let vertex = self.mesh.vertex_at_mut(index);
-                    vertex.color = Vector([vertex_light, vertex_light, vertex_light, 1.0]);
+                    let vertex = mesh.vertex_at_mut(index);
+                    vertex.light = vertex_light;
+                    vertex.opacity = 1.0;
                     let total_offset = quadrant_offset + vertex_offset;
                     vertex.uv = Vector([
-                        atlas_offset.x() as f32 + total_offset.x() * image.size() as f32,
-                        atlas_offset.y() as f32 + (1.0 - total_offset.y()) * image.size() as f32,
+                        atlas_offset.x() as f32 + total_offset.x() * image_size as f32,
+                        atlas_offset.y() as f32 + (1.0 - total_offset.y()) * image_size as f32,
                     ]);
                     index += 1;
                 }
             }
         }
         else {
-            // Make block invisible since it has no appearance (e.g. air)
-            let mut index = first_index;
+            // Make the quadrants invisible since there is no appearance (e.g. air)
             for _ in 0..VERTICES_PER_BLOCK {
-                let vertex = self.mesh.vertex_at_mut(index);
-                vertex.color = Vector::zero();
+                let vertex = mesh.vertex_at_mut(index);
+                vertex.light = Vector::zero();
                 vertex.uv = Vector::filled(f32::NAN);
+                vertex.opacity = 0.0;
                 index += 1;
             }
         }
@@ -425,9 +879,10 @@ impl Chunk {
             chunk_location.x() as f32 * CHUNK_SIZE as f32 + x as f32,
             chunk_location.y() as f32 * CHUNK_SIZE as f32 + y as f32,
         ]);
-        block.block_type().colliders
+        block.colliders()
             .iter()
-            .map(|&bounds| {
+            .map(|block_collider| {
+                let bounds = block_collider.bounds;
                 let mut collider_bounds = Rectangle::new(
                     Vector([
                         bounds.min.x() as f32 / 32.0,
@@ -439,7 +894,15 @@ impl Chunk {
                     ]),
                 );
                 collider_bounds.shift_by(block_origin);
-                physics.add_collider(Collider::new_fixed(collider_bounds))
+                let block_type = block.block_type();
+                let mut collider = Collider::new_fixed(collider_bounds)
+                    .with_shape(block_collider.shape)
+                    .with_layers(TERRAIN_LAYER, ALL_LAYERS)
+                    .with_material(block_type.restitution(), block_type.friction());
+                if let Some(side) = block_collider.one_way {
+                    collider = collider.with_one_way(side);
+                }
+                physics.add_collider(collider)
             })
             .collect()
     }
@@ -447,18 +910,28 @@ impl Chunk {
 
 pub struct ChunkMap {
     generator: Option<Box<dyn WorldGenerator>>,
+    save: Option<WorldSave>,
     chunks: BTreeMap<ChunkLocation, RefCell<Chunk>>,
     chunk_load_range: Rectangle<i64>,
     chunk_simulate_range: Rectangle<i64>,
+    power_grid: PowerGrid,
+    pipe_grid: PipeGrid,
+    block_updates: RefCell<BTreeSet<Vector<i64, 2>>>,
+    ticks: u64,
 }
 
 impl ChunkMap {
-    pub fn new(generator: Option<Box<dyn WorldGenerator>>) -> Self {
+    pub fn new(generator: Option<Box<dyn WorldGenerator>>, save: Option<WorldSave>) -> Self {
         Self {
             generator,
+            save,
             chunks: BTreeMap::new(),
             chunk_load_range: Rectangle::new(Vector([-2, -2]), Vector([2, 2])),
             chunk_simulate_range: Rectangle::new(Vector([-1, -1]), Vector([1, 1])),
+            power_grid: PowerGrid::new(),
+            pipe_grid: PipeGrid::new(),
+            block_updates: RefCell::new(BTreeSet::new()),
+            ticks: 0,
         }
     }
 
@@ -470,6 +943,39 @@ impl ChunkMap {
         self.chunks.get(&location).map(|chunk| chunk.borrow_mut())
     }
 
+    pub fn with_block_at<T>(&self, position: Vector<i64, 2>, f: impl FnOnce(&Block) -> T) -> Option<T> {
+        self.with_block_slot_at(position, |slot| f(slot.block()))
+    }
+
+    pub fn with_block_slot_at<T>(&self, position: Vector<i64, 2>, f: impl FnOnce(&BlockSlot) -> T) -> Option<T> {
+        let coord_x = BlockCoord::from(position.x());
+        let coord_y = BlockCoord::from(position.y());
+        let chunk = self.get(Vector([coord_x.chunk, coord_y.chunk]))?;
+        Some(f(chunk.block_slot_at(coord_x.offset, coord_y.offset)))
+    }
+
+    /// Set the block at a world position, returning `false` if its chunk isn't loaded.
+    pub fn set_block_at(&self, position: Vector<i64, 2>, block: Block, physics: &mut Physics) -> bool {
+        let coord_x = BlockCoord::from(position.x());
+        let coord_y = BlockCoord::from(position.y());
+        let Some(mut chunk) = self.get_mut(Vector([coord_x.chunk, coord_y.chunk])) else {
+            return false;
+        };
+        chunk.set_block_at(coord_x.offset, coord_y.offset, block, self, physics);
+        true
+    }
+
+    /// Set the wall at a world position, returning `false` if its chunk isn't loaded.
+    pub fn set_wall_at(&self, position: Vector<i64, 2>, wall_type: &'static WallType) -> bool {
+        let coord_x = BlockCoord::from(position.x());
+        let coord_y = BlockCoord::from(position.y());
+        let Some(mut chunk) = self.get_mut(Vector([coord_x.chunk, coord_y.chunk])) else {
+            return false;
+        };
+        chunk.set_wall_at(coord_x.offset, coord_y.offset, wall_type, self);
+        true
+    }
+
     pub fn get_or_load(&mut self, location: ChunkLocation, physics: &mut Physics) -> Ref<'_, Chunk> {
         self.get_or_load_cell(location, physics).borrow()
     }
@@ -483,21 +989,73 @@ impl ChunkMap {
             &self.chunks[&location]
         }
         else {
-            self.chunks.insert(location, RefCell::new(Chunk::new(location)));
-            let cell = &self.chunks[&location];
-            if let Some(generator) = &self.generator {
-                generator.generate_chunk(&mut *cell.borrow_mut(), self, physics);
+            // Prefer the saved copy of the chunk, falling back to the generator if there isn't one
+            let saved_chunk = self.save.as_mut().and_then(|save| {
+                save.load_chunk(location).unwrap_or_else(|err| {
+                    eprintln!("Failed to load chunk: {err}");
+                    None
+                })
+            });
+            if let Some(chunk) = saved_chunk {
+                self.chunks.insert(location, RefCell::new(chunk));
+                &self.chunks[&location]
+            }
+            else {
+                self.chunks.insert(location, RefCell::new(Chunk::new(location)));
+                let cell = &self.chunks[&location];
+                if let Some(generator) = &self.generator {
+                    generator.generate_chunk(&mut *cell.borrow_mut(), self, physics);
+                }
+                cell.borrow_mut().initialize_lighting(self);
+                cell
             }
-            cell
         }
     }
 
     pub fn unload(&mut self, location: ChunkLocation, physics: &mut Physics) {
         if let Some(chunk) = self.chunks.remove(&location) {
-            chunk.into_inner().detach_physics(physics);
+            let mut chunk = chunk.into_inner();
+            self.save_chunk(&chunk);
+            chunk.detach_physics(physics);
+        }
+    }
+
+    fn save_chunk(&mut self, chunk: &Chunk) {
+        if let Some(save) = &mut self.save {
+            if let Err(err) = save.save_chunk(chunk) {
+                eprintln!("Failed to save chunk: {err}");
+            }
+        }
+    }
+
+    fn flush_save(&mut self) {
+        if let Some(save) = &mut self.save {
+            if let Err(err) = save.flush() {
+                eprintln!("Failed to save world: {err}");
+            }
         }
     }
 
+    pub fn generator(&self) -> Option<&dyn WorldGenerator> {
+        self.generator.as_deref()
+    }
+
+    pub fn save(&self) -> Option<&WorldSave> {
+        self.save.as_ref()
+    }
+
+    /// Write every loaded chunk to the world save, if there is one.
+    pub fn save_all(&mut self) {
+        if let Some(save) = &mut self.save {
+            for chunk in self.chunks.values() {
+                if let Err(err) = save.save_chunk(&chunk.borrow()) {
+                    eprintln!("Failed to save chunk: {err}");
+                }
+            }
+        }
+        self.flush_save();
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = Ref<'_, Chunk>> {
         self.chunks.values().map(RefCell::borrow)
     }
@@ -506,6 +1064,112 @@ impl ChunkMap {
         self.chunks.values().map(RefCell::borrow_mut)
     }
 
+    pub fn power_grid(&self) -> &PowerGrid {
+        &self.power_grid
+    }
+
+    pub fn pipe_grid(&self) -> &PipeGrid {
+        &self.pipe_grid
+    }
+
+    /// The number of ticks the world has been simulated for.
+    pub fn current_tick(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn set_current_tick(&mut self, tick: u64) {
+        self.ticks = tick;
+    }
+
+    /// Schedule a tick for the block at a position `delay` ticks from now (at least one).
+    /// Returns `false` if its chunk isn't loaded.
+    pub fn schedule_tick(&self, position: Vector<i64, 2>, delay: u64) -> bool {
+        let coord_x = BlockCoord::from(position.x());
+        let coord_y = BlockCoord::from(position.y());
+        let Some(mut chunk) = self.get_mut(Vector([coord_x.chunk, coord_y.chunk])) else {
+            return false;
+        };
+        chunk.schedule_tick(coord_x.offset, coord_y.offset, self.ticks + delay.max(1));
+        true
+    }
+
+    /// Let the block at a position react to a change in itself or its neighbors.
+    pub fn update_block_at(&self, position: Vector<i64, 2>, physics: &mut Physics) {
+        let mut update = BlockUpdate::new(self, position);
+        if self.with_block_at(position, |block| block.handle_neighbor_changed(&mut update)).is_some() {
+            update.apply(physics);
+        }
+    }
+
+    fn run_scheduled_ticks(&self, chunk_range: Rectangle<i64>, physics: &mut Physics) {
+        let mut positions = Vec::new();
+        for chunk_y in chunk_range.min.y() ..= chunk_range.max.y() {
+            for chunk_x in chunk_range.min.x() ..= chunk_range.max.x() {
+                let Some(mut chunk) = self.get_mut(Vector([chunk_x, chunk_y])) else {
+                    continue;
+                };
+                for scheduled_tick in chunk.take_due_ticks(self.ticks) {
+                    positions.push(chunk.block_position(scheduled_tick.x, scheduled_tick.y));
+                }
+            }
+        }
+
+        for position in positions {
+            let mut update = BlockUpdate::new(self, position);
+            if self.with_block_at(position, |block| block.handle_scheduled_tick(&mut update)).is_some() {
+                update.apply(physics);
+            }
+        }
+    }
+
+    fn run_random_ticks(&self, chunk_range: Rectangle<i64>, physics: &mut Physics) {
+        for chunk_y in chunk_range.min.y() ..= chunk_range.max.y() {
+            for chunk_x in chunk_range.min.x() ..= chunk_range.max.x() {
+                let location = Vector([chunk_x, chunk_y]);
+                if !self.chunks.contains_key(&location) {
+                    continue;
+                }
+                // Derived from the chunk seed so that the same world always evolves the same way
+                let mut hasher = SimpleHasher::with_seed(self.chunk_seed(location));
+                self.ticks.hash(&mut hasher);
+                let mut random = SimpleRandom::with_seed(hasher.finish());
+
+                for _ in 0..RANDOM_TICKS_PER_CHUNK {
+                    let position = Vector([
+                        i64::from(BlockCoord::new(chunk_x, random.next_below(CHUNK_SIZE as u64) as usize)),
+                        i64::from(BlockCoord::new(chunk_y, random.next_below(CHUNK_SIZE as u64) as usize)),
+                    ]);
+                    let mut update = BlockUpdate::with_random(self, position, SimpleRandom::with_seed(random.next_u64()));
+                    if self.with_block_at(position, |block| block.handle_random_tick(&mut update)).is_some() {
+                        update.apply(physics);
+                    }
+                }
+            }
+        }
+    }
+
+    fn chunk_seed(&self, location: ChunkLocation) -> u64 {
+        match &self.generator {
+            Some(generator) => generator.get_chunk_seed(location),
+            None => {
+                let mut hasher = SimpleHasher::default();
+                location.x().hash(&mut hasher);
+                location.y().hash(&mut hasher);
+                hasher.finish()
+            }
+        }
+    }
+
+    /// Request that the block at a position be checked for changes in its surroundings.
+    pub fn queue_block_update(&self, position: Vector<i64, 2>) {
+        self.block_updates.borrow_mut().insert(position);
+    }
+
+    /// Take every block update queued since the last call.
+    pub fn take_block_updates(&self) -> BTreeSet<Vector<i64, 2>> {
+        std::mem::take(&mut *self.block_updates.borrow_mut())
+    }
+
     pub fn locations(&self) -> impl Iterator<Item = ChunkLocation> + '_ {
         self.chunks.keys().copied()
     }
@@ -545,13 +1209,190 @@ impl ChunkMap {
             .filter(|&location| !chunk_simulate_range.contains_inclusive(location))
             .collect();
 
-        for location in locations_to_unload {
-            self.unload(location, physics);
+        if !locations_to_unload.is_empty() {
+            for location in locations_to_unload {
+                self.unload(location, physics);
+            }
+            self.flush_save();
         }
         for location in locations_to_detach {
             if let Some(mut chunk) = self.get_mut(location) {
                 chunk.detach_physics(physics);
             }
         }
+
+        self.ticks += 1;
+        self.run_scheduled_ticks(chunk_simulate_range, physics);
+        self.run_random_ticks(chunk_simulate_range, physics);
+        update_liquids(self, chunk_simulate_range, self.ticks, physics);
+        update_pipes(self, chunk_simulate_range, physics);
+        if self.power_grid.has_pending_updates() {
+            self.power_grid.update(self, physics);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_attribute(mut block: Block, name: &str, value: AttributeValue) -> Block {
+        let (index, _) = block.block_type().get_attribute_info(name).unwrap();
+        block.set_attribute_value(index, value);
+        block
+    }
+
+    /// A chunk using every part of the format: non-default attributes, colored light, walls and
+    /// scheduled ticks.
+    fn sample_chunk() -> Chunk {
+        let mut chunk = Chunk::new(Vector([3, -2]));
+        chunk.light_initialized = true;
+        chunk.height_map = std::array::from_fn(|x| x as i64 * 2 - 40);
+
+        let spout = Block::new(&types::PIPE_SPOUT, BlockSide::None);
+        let spout = with_attribute(spout, "direction", AttributeValue::U8(2));
+        let spout = with_attribute(spout, "fluid", AttributeValue::U8(2));
+        let spout = with_attribute(spout, "amount", AttributeValue::U8(5));
+        let battery = Block::new(&types::VOLTAGITE_BATTERY, BlockSide::None);
+        let battery = with_attribute(battery, "charge", AttributeValue::U8(7));
+
+        for (y, row) in chunk.block_slots.iter_mut().enumerate() {
+            for (x, slot) in row.iter_mut().enumerate() {
+                if y < 4 {
+                    slot.block = Block::new(&types::STONE, BlockSide::None);
+                    slot.wall = &wall::STONE_WALL;
+                }
+                slot.block_light = Vector([x as u8 % 16, y as u8 % 16, (x + y) as u8 % 16]);
+                slot.sky_light = (15 - y) as u8;
+            }
+        }
+        chunk.block_slots[6][2].block = spout;
+        chunk.block_slots[6][3].block = battery;
+        chunk.block_slots[6][3].wall = &wall::OAK_WALL;
+        chunk.schedule_tick(2, 6, 120);
+        chunk.schedule_tick(15, 0, u64::MAX);
+        chunk
+    }
+
+    fn assert_chunks_equal(expected: &Chunk, actual: &Chunk) {
+        assert_eq!(expected.location, actual.location);
+        assert_eq!(expected.height_map, actual.height_map);
+        for y in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let expected_slot = expected.block_slot_at(x, y);
+                let actual_slot = actual.block_slot_at(x, y);
+                assert_eq!(expected_slot.block().block_type(), actual_slot.block().block_type(), "block type at ({x}, {y})");
+                assert_eq!(expected_slot.block().attributes(), actual_slot.block().attributes(), "attributes at ({x}, {y})");
+                assert_eq!(expected_slot.wall(), actual_slot.wall(), "wall at ({x}, {y})");
+                assert_eq!(expected_slot.block_light(), actual_slot.block_light(), "block light at ({x}, {y})");
+                assert_eq!(expected_slot.sky_light(), actual_slot.sky_light(), "sky light at ({x}, {y})");
+            }
+        }
+        assert_eq!(expected.scheduled_ticks(), actual.scheduled_ticks());
+    }
+
+    /// Write a chunk the way an older version of the format did. Every block is stone except for a
+    /// charged battery at (4, 5), and every slot has the same block light.
+    fn write_legacy_chunk(version: u32, block_light: BlockLight) -> Vec<u8> {
+        let mut writer = DataWriter::new();
+        writer.write_u32(version);
+        for x in 0..CHUNK_SIZE {
+            writer.write_i64(x as i64);
+        }
+        writer.write_u16(2);
+        writer.write_str("stone");
+        writer.write_u8(0);
+        writer.write_str("voltagite_battery");
+        writer.write_u8(1);
+        writer.write_str("charge");
+        for y in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                if (x, y) == (4, 5) {
+                    writer.write_u16(1);
+                    writer.write_u8(ATTRIBUTE_TAG_U8);
+                    writer.write_u8(6);
+                }
+                else {
+                    writer.write_u16(0);
+                }
+                if version >= 3 {
+                    for channel in block_light.0 {
+                        writer.write_u8(channel);
+                    }
+                }
+                else {
+                    writer.write_u8(block_light.x());
+                }
+                writer.write_u8(11);
+            }
+        }
+        if version >= 2 {
+            writer.write_u32(1);
+            writer.write_u8(4);
+            writer.write_u8(5);
+            writer.write_u64(300);
+        }
+        writer.into_bytes()
+    }
+
+    fn read_legacy_chunk(version: u32, block_light: BlockLight) -> Chunk {
+        let bytes = write_legacy_chunk(version, block_light);
+        let mut reader = DataReader::new(&bytes);
+        let chunk = Chunk::read_data(Vector([0, 0]), &mut reader).unwrap();
+        assert!(reader.is_at_end());
+
+        assert_eq!(chunk.height_map, std::array::from_fn(|x| x as i64));
+        let battery = chunk.block_at(4, 5);
+        assert_eq!(battery.block_type(), &types::VOLTAGITE_BATTERY);
+        assert_eq!(battery.attributes(), &[AttributeValue::U8(6)]);
+        assert_eq!(chunk.block_at(5, 5).block_type(), &types::STONE);
+        for slot in chunk.block_slots.iter().flatten() {
+            assert_eq!(slot.wall(), &wall::AIR);
+            assert_eq!(slot.sky_light(), 11);
+        }
+        chunk
+    }
+
+    #[test]
+    fn chunk_data_round_trip() {
+        let chunk = sample_chunk();
+        let mut writer = DataWriter::new();
+        chunk.write_data(&mut writer);
+
+        let bytes = writer.into_bytes();
+        let mut reader = DataReader::new(&bytes);
+        let loaded = Chunk::read_data(chunk.location(), &mut reader).unwrap();
+        assert!(reader.is_at_end());
+        assert_chunks_equal(&chunk, &loaded);
+    }
+
+    #[test]
+    fn chunk_data_version_1() {
+        let chunk = read_legacy_chunk(1, Vector([9, 0, 0]));
+        // Single-channel light becomes white light of the same level
+        assert_eq!(chunk.block_light_at(0, 0), Vector([9, 9, 9]));
+        assert!(chunk.scheduled_ticks().is_empty());
+    }
+
+    #[test]
+    fn chunk_data_version_2() {
+        let chunk = read_legacy_chunk(2, Vector([9, 0, 0]));
+        assert_eq!(chunk.block_light_at(0, 0), Vector([9, 9, 9]));
+        assert_eq!(chunk.scheduled_ticks(), &[ScheduledTick { x: 4, y: 5, due_tick: 300 }]);
+    }
+
+    #[test]
+    fn chunk_data_version_3() {
+        let chunk = read_legacy_chunk(3, Vector([9, 12, 3]));
+        assert_eq!(chunk.block_light_at(0, 0), Vector([9, 12, 3]));
+        assert_eq!(chunk.scheduled_ticks(), &[ScheduledTick { x: 4, y: 5, due_tick: 300 }]);
+    }
+
+    #[test]
+    fn chunk_data_unsupported_version() {
+        let mut writer = DataWriter::new();
+        writer.write_u32(CHUNK_DATA_VERSION + 1);
+        let bytes = writer.into_bytes();
+        assert!(Chunk::read_data(Vector([0, 0]), &mut DataReader::new(&bytes)).is_err());
     }
 }
@@ -0,0 +1,73 @@
+use innovus::gfx::MeshRenderer;
+use innovus::tools::Vector;
+use crate::tools::asset::AssetPool;
+use crate::world::block::{BlockCoord, BlockVertex, ChunkMap};
+
+/// Cracks drawn over the block the player is mining, which grow as the block gets closer to
+/// breaking.
+pub struct CrackOverlay {
+    mesh: MeshRenderer<BlockVertex>,
+}
+
+impl CrackOverlay {
+    const VERTEX_OFFSETS: [Vector<f32, 2>; 4] = [
+        Vector([0.0, 0.0]), // Bottom left
+        Vector([0.0, 1.0]), // Top left
+        Vector([1.0, 1.0]), // Top right
+        Vector([1.0, 0.0]), // Bottom right
+    ];
+
+    pub fn new() -> Self {
+        Self {
+            mesh: MeshRenderer::create(),
+        }
+    }
+
+    /// Draw the crack stage for the given mining progress (from 0 to 1) over a block.
+    pub fn render(&mut self, block_position: Vector<i64, 2>, progress: f32, assets: &AssetPool, chunks: &ChunkMap) {
+        let Some(atlas_region) = assets.crack_image() else {
+            return;
+        };
+        let coord_x = BlockCoord::from(block_position.x());
+        let coord_y = BlockCoord::from(block_position.y());
+        let Some(chunk) = chunks.get(Vector([coord_x.chunk, coord_y.chunk])) else {
+            return;
+        };
+        let vertex_light = chunk.block_slot_at(coord_x.offset, coord_y.offset).vertex_light();
+
+        // Each stage is a square frame, so the number of stages comes from the image's aspect ratio
+        let stage_size = atlas_region.y_span() as f32;
+        let stage_count = (atlas_region.x_span() / atlas_region.y_span()).max(1);
+        let stage = ((progress * stage_count as f32) as u32).min(stage_count - 1);
+        let stage_x = atlas_region.min.x() as f32 + stage as f32 * stage_size;
+        let stage_y = atlas_region.min.y() as f32;
+        let block_origin = block_position.map(|x| x as f32);
+
+        let vertices = Self::VERTEX_OFFSETS.map(|vertex_offset| {
+            BlockVertex::new(
+                (block_origin + vertex_offset).with_z(0.0),
+                Some(vertex_light),
+                Some(Vector([
+                    stage_x + vertex_offset.x() * stage_size,
+                    stage_y + (1.0 - vertex_offset.y()) * stage_size,
+                ])),
+            )
+        });
+
+        self.mesh.clear();
+        self.mesh.add(&vertices, &[
+            [0, 1, 2],
+            [2, 3, 0],
+        ]);
+
+        assets.block_texture().bind();
+        assets.block_shaders().bind();
+        self.mesh.render();
+    }
+}
+
+impl Default for CrackOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,245 @@
+use std::collections::{BTreeSet, VecDeque};
+use innovus::tools::Vector;
+use super::*;
+
+/// The brightest level any light channel can reach.
+pub const MAX_LIGHT: u8 = 15;
+/// Light is tracked in four channels: red, green and blue block light, followed by sky light.
+pub const LIGHT_CHANNEL_COUNT: usize = 4;
+pub const SKY_LIGHT_CHANNEL: usize = 3;
+
+const ADJACENT_OFFSETS: [Vector<i64, 2>; 4] = [
+    Vector([0, 1]),
+    Vector([0, -1]),
+    Vector([-1, 0]),
+    Vector([1, 0]),
+];
+
+/// Recalculates lighting after blocks change. Each channel is updated in two passes: the first
+/// clears out all light which may have come from the changed blocks, and the second spreads light
+/// back in from every remaining source and from the edges of the cleared area.
+///
+/// The chunk being modified is usually borrowed from the chunk map already, so it is accessed
+/// directly while every other loaded chunk is accessed through the map.
+pub(super) struct LightEngine<'a> {
+    chunk: &'a mut Chunk,
+    chunk_map: &'a ChunkMap,
+    /// Every chunk which needs to be rendered again once relighting is done.
+    dirty_chunks: BTreeSet<ChunkLocation>,
+}
+
+impl<'a> LightEngine<'a> {
+    pub fn new(chunk: &'a mut Chunk, chunk_map: &'a ChunkMap) -> Self {
+        Self {
+            chunk,
+            chunk_map,
+            dirty_chunks: BTreeSet::new(),
+        }
+    }
+
+    fn with_chunk<T>(&self, position: Vector<i64, 2>, f: impl FnOnce(&Chunk, usize, usize) -> T) -> Option<T> {
+        let coord_x = BlockCoord::from(position.x());
+        let coord_y = BlockCoord::from(position.y());
+        let location = Vector([coord_x.chunk, coord_y.chunk]);
+        if location == self.chunk.location() {
+            Some(f(self.chunk, coord_x.offset, coord_y.offset))
+        }
+        else {
+            Some(f(&*self.chunk_map.get(location)?, coord_x.offset, coord_y.offset))
+        }
+    }
+
+    fn with_chunk_mut<T>(&mut self, position: Vector<i64, 2>, f: impl FnOnce(&mut Chunk, usize, usize) -> T) -> Option<T> {
+        let coord_x = BlockCoord::from(position.x());
+        let coord_y = BlockCoord::from(position.y());
+        let location = Vector([coord_x.chunk, coord_y.chunk]);
+        if location == self.chunk.location() {
+            Some(f(self.chunk, coord_x.offset, coord_y.offset))
+        }
+        else {
+            Some(f(&mut *self.chunk_map.get_mut(location)?, coord_x.offset, coord_y.offset))
+        }
+    }
+
+    fn light(&self, position: Vector<i64, 2>, channel: usize) -> Option<u8> {
+        self.with_chunk(position, |chunk, x, y| chunk.block_slot_at(x, y).light_channel(channel))
+    }
+
+    fn source(&self, position: Vector<i64, 2>, channel: usize) -> Option<u8> {
+        self.with_chunk(position, |chunk, x, y| {
+            if channel == SKY_LIGHT_CHANNEL {
+                // Everything at or above the highest block casting a shadow is in direct sunlight
+                if chunk.block_position(x, y).y() >= chunk.height_map()[x] { MAX_LIGHT } else { 0 }
+            }
+            else {
+                chunk.block_at(x, y).light_emission()[channel].min(MAX_LIGHT)
+            }
+        })
+    }
+
+    fn set_light(&mut self, position: Vector<i64, 2>, channel: usize, light: u8) {
+        if self.with_chunk_mut(position, |chunk, x, y| chunk.set_light_channel(x, y, channel, light)).is_none() {
+            return;
+        }
+        // Lighting is smoothed across neighboring blocks, so blocks in the chunks next door may be
+        // affected too when this one is on an edge
+        for dy in [-1, 0, 1] {
+            for dx in [-1, 0, 1] {
+                let neighbor = position + Vector([dx, dy]);
+                self.dirty_chunks.insert(Vector([BlockCoord::from(neighbor.x()).chunk, BlockCoord::from(neighbor.y()).chunk]));
+            }
+        }
+    }
+
+    /// Recalculate every light channel around positions whose blocks or sunlight have changed.
+    pub fn relight(&mut self, positions: &[Vector<i64, 2>]) {
+        for channel in 0..LIGHT_CHANNEL_COUNT {
+            self.relight_channel(positions, channel);
+        }
+        for location in std::mem::take(&mut self.dirty_chunks) {
+            if location == self.chunk.location() {
+                self.chunk.set_all_need_render();
+            }
+            else if let Some(mut chunk) = self.chunk_map.get_mut(location) {
+                chunk.set_all_need_render();
+            }
+        }
+    }
+
+    fn relight_channel(&mut self, positions: &[Vector<i64, 2>], channel: usize) {
+        let mut removal_queue = VecDeque::new();
+        let mut addition_queue = VecDeque::new();
+        for &position in positions {
+            if let Some(light) = self.light(position, channel) {
+                self.set_light(position, channel, 0);
+                removal_queue.push_back((position, light));
+            }
+        }
+
+        // Light always dims as it spreads, so any dimmer neighbor may have been lit from here
+        while let Some((position, light)) = removal_queue.pop_front() {
+            for offset in ADJACENT_OFFSETS {
+                let neighbor = position + offset;
+                let Some(neighbor_light) = self.light(neighbor, channel) else {
+                    continue;
+                };
+                if neighbor_light == 0 {
+                    continue;
+                }
+                if neighbor_light < light {
+                    self.set_light(neighbor, channel, 0);
+                    removal_queue.push_back((neighbor, neighbor_light));
+                }
+                else {
+                    // Lit from somewhere else, so it can help fill the cleared area back in
+                    addition_queue.push_back(neighbor);
+                }
+            }
+            let source = self.source(position, channel).unwrap_or(0);
+            if source > 0 {
+                if source > self.light(position, channel).unwrap_or(0) {
+                    self.set_light(position, channel, source);
+                }
+                addition_queue.push_back(position);
+            }
+        }
+
+        while let Some(position) = addition_queue.pop_front() {
+            let Some(light) = self.light(position, channel) else {
+                continue;
+            };
+            for offset in ADJACENT_OFFSETS {
+                let neighbor = position + offset;
+                let neighbor_info = self.with_chunk(neighbor, |chunk, x, y| {
+                    let slot = chunk.block_slot_at(x, y);
+                    (slot.light_channel(channel), slot.light_opacity(channel))
+                });
+                let Some((neighbor_light, opacity)) = neighbor_info else {
+                    continue;
+                };
+                let spread_light = light.saturating_sub(opacity.max(1));
+                if spread_light > neighbor_light {
+                    self.set_light(neighbor, channel, spread_light);
+                    addition_queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use innovus::tools::phys::Physics;
+    use super::*;
+
+    fn set_block(chunk_map: &ChunkMap, position: Vector<i64, 2>, block_type: &'static BlockType, physics: &mut Physics) {
+        assert!(chunk_map.set_block_at(position, Block::new(block_type, BlockSide::None), physics));
+    }
+
+    fn block_light(chunk_map: &ChunkMap, position: Vector<i64, 2>) -> BlockLight {
+        chunk_map.with_block_slot_at(position, BlockSlot::block_light).unwrap()
+    }
+
+    fn sky_light(chunk_map: &ChunkMap, position: Vector<i64, 2>) -> u8 {
+        chunk_map.with_block_slot_at(position, BlockSlot::sky_light).unwrap()
+    }
+
+    #[test]
+    fn block_light_spreads_across_chunks_and_is_removed() {
+        let mut physics = Physics::new();
+        let chunk_map = loaded_chunk_map(&mut physics);
+        // Right at the edge of chunk 0, so the light spills into chunk 1
+        let lantern = Vector([CHUNK_SIZE as i64 - 1, 5]);
+        set_block(&chunk_map, lantern, &types::LANTERN, &mut physics);
+
+        for (offset, distance) in [([0, 0], 0), ([1, 0], 1), ([-1, 0], 1), ([0, 3], 3), ([2, 1], 3), ([6, -4], 10)] {
+            let expected = Vector([15u8, 12, 8]).map(|light| light.saturating_sub(distance));
+            assert_eq!(block_light(&chunk_map, lantern + Vector(offset)), expected, "offset {offset:?}");
+        }
+
+        set_block(&chunk_map, lantern, &types::AIR, &mut physics);
+        for dy in -16..=16 {
+            for dx in -16..=16 {
+                assert_eq!(block_light(&chunk_map, lantern + Vector([dx, dy])), Vector([0, 0, 0]));
+            }
+        }
+    }
+
+    #[test]
+    fn sky_light_floods_into_an_opened_cave() {
+        let mut physics = Physics::new();
+        let chunk_map = loaded_chunk_map(&mut physics);
+        // A cave crossing the edge between chunks -1 and 0, buried deep enough in stone that no
+        // sky light can seep through to it
+        for y in 2..=20 {
+            for x in -12..=12 {
+                let in_cave = (-4..=4).contains(&x) && (10..=12).contains(&y);
+                if !in_cave {
+                    set_block(&chunk_map, Vector([x, y]), &types::STONE, &mut physics);
+                }
+            }
+        }
+        for x in -4..=4 {
+            assert_eq!(sky_light(&chunk_map, Vector([x, 11])), 0);
+        }
+
+        // Dig a shaft from the surface down into the cave
+        for y in (13..=20).rev() {
+            set_block(&chunk_map, Vector([0, y]), &types::AIR, &mut physics);
+        }
+        for y in 10..=20 {
+            assert_eq!(sky_light(&chunk_map, Vector([0, y])), MAX_LIGHT);
+        }
+        for x in -4..=4i64 {
+            assert_eq!(sky_light(&chunk_map, Vector([x, 11])), MAX_LIGHT - x.unsigned_abs() as u8, "x = {x}");
+        }
+
+        // Filling the shaft back in darkens the cave again
+        for y in 13..=20 {
+            set_block(&chunk_map, Vector([0, y]), &types::STONE, &mut physics);
+        }
+        for x in -4..=4 {
+            assert_eq!(sky_light(&chunk_map, Vector([x, 11])), 0);
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::BTreeSet;
+use innovus::gfx::color::RGBColor;
+use innovus::tools::{Rectangle, Vector};
+use innovus::tools::phys::Physics;
+use super::*;
+
+/// The level of a liquid block which fills its entire space.
+pub const MAX_LIQUID_LEVEL: u8 = 8;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum FluidType {
+    Water,
+    Lava,
+}
+
+impl FluidType {
+    pub const ALL: [Self; 2] = [Self::Water, Self::Lava];
+
+    /// Names as they appear in the "fluid" attribute of pipes, where index 0 is "none".
+    pub const ATTRIBUTE_NAMES: &'static [&'static str] = &["none", "water", "lava"];
+
+    pub fn name(&self) -> &'static str {
+        Self::ATTRIBUTE_NAMES[self.attribute_index() as usize]
+    }
+
+    pub fn attribute_index(&self) -> u8 {
+        match self {
+            Self::Water => 1,
+            Self::Lava => 2,
+        }
+    }
+
+    pub fn from_attribute_index(index: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|fluid| fluid.attribute_index() == index)
+    }
+
+    pub fn color(&self) -> RGBColor {
+        match self {
+            Self::Water => RGBColor::new(0.2, 0.45, 0.9),
+            Self::Lava => RGBColor::new(1.0, 0.45, 0.1),
+        }
+    }
+
+    pub fn block_type(&self) -> &'static BlockType {
+        match self {
+            Self::Water => &types::WATER,
+            Self::Lava => &types::LAVA,
+        }
+    }
+
+    /// Get the fluid and level of a liquid block, or `None` if the block isn't a liquid.
+    pub fn from_block(block: &Block) -> Option<(Self, u8)> {
+        let fluid = Self::ALL.into_iter().find(|fluid| fluid.block_type() == block.block_type())?;
+        Some((fluid, block.attribute_value(0).expect_u8()))
+    }
+
+    pub fn create_block(&self, level: u8) -> Block {
+        let mut block = Block::new(self.block_type(), BlockSide::None);
+        block.set_attribute_value(0, AttributeValue::U8(level.min(MAX_LIQUID_LEVEL)));
+        block
+    }
+
+    /// The number of ticks between each sideways flow. Liquids always fall every tick.
+    pub fn flow_interval(&self) -> u64 {
+        match self {
+            Self::Water => 1,
+            Self::Lava => 4,
+        }
+    }
+
+    /// Upward acceleration applied to an entity which is fully submerged in this liquid.
+    pub fn buoyancy(&self) -> f32 {
+        match self {
+            Self::Water => 36.0,
+            Self::Lava => 34.0,
+        }
+    }
+
+    /// Fraction of an entity's velocity lost per second while submerged in this liquid.
+    pub fn drag(&self) -> f32 {
+        match self {
+            Self::Water => 2.5,
+            Self::Lava => 6.0,
+        }
+    }
+
+    /// The block produced when this liquid touches another one, if they react.
+    pub fn mix_with(&self, other: Self, level: u8) -> Option<Block> {
+        match (self, other) {
+            (Self::Lava, Self::Water) if level == MAX_LIQUID_LEVEL => {
+                Some(Block::new(&types::OBSIDIAN_BLOCK, BlockSide::None))
+            }
+            (Self::Lava, Self::Water) => Some(Block::new(&types::COBBLES, BlockSide::None)),
+            _ => None,
+        }
+    }
+}
+
+const ADJACENT_OFFSETS: [Vector<i64, 2>; 4] = [
+    Vector([0, 1]),
+    Vector([0, -1]),
+    Vector([-1, 0]),
+    Vector([1, 0]),
+];
+
+/// The current level of `fluid` at a position if liquid can flow into it, or `None` if it can't.
+fn liquid_level_at(chunk_map: &ChunkMap, position: Vector<i64, 2>, fluid: FluidType) -> Option<u8> {
+    chunk_map.with_block_at(position, |block| {
+        if block.block_type() == &types::AIR {
+            Some(0)
+        }
+        else {
+            FluidType::from_block(block)
+                .and_then(|(other_fluid, level)| (other_fluid == fluid).then_some(level))
+        }
+    })?
+}
+
+fn set_liquid_level(chunk_map: &ChunkMap, position: Vector<i64, 2>, fluid: FluidType, level: u8, physics: &mut Physics) {
+    let block = if level == 0 {
+        Block::new(&types::AIR, BlockSide::None)
+    }
+    else {
+        fluid.create_block(level)
+    };
+    chunk_map.set_block_at(position, block, physics);
+}
+
+/// Advance every liquid block in the given chunks by one tick of flow.
+///
+/// Liquids first fall as far as they can into the block below, then spread one level at a time
+/// to either side as long as the neighbor is at least two levels lower, which lets them settle.
+pub fn update_liquids(chunk_map: &ChunkMap, chunk_range: Rectangle<i64>, tick: u64, physics: &mut Physics) {
+    let mut positions = Vec::new();
+    for chunk_y in chunk_range.min.y() ..= chunk_range.max.y() {
+        for chunk_x in chunk_range.min.x() ..= chunk_range.max.x() {
+            let Some(chunk) = chunk_map.get(Vector([chunk_x, chunk_y])) else {
+                continue;
+            };
+            for y in 0..CHUNK_SIZE {
+                for x in 0..CHUNK_SIZE {
+                    if FluidType::from_block(chunk.block_at(x, y)).is_some() {
+                        positions.push(chunk.block_position(x, y));
+                    }
+                }
+            }
+        }
+    }
+    // Update from the bottom up so falling liquid isn't moved twice in one tick
+    positions.sort_by_key(|position| (position.y(), position.x()));
+
+    // Alternate the direction liquid spreads first so it doesn't favor one side
+    let sides = if tick.is_multiple_of(2) {
+        [Vector([-1, 0]), Vector([1, 0])]
+    }
+    else {
+        [Vector([1, 0]), Vector([-1, 0])]
+    };
+
+    let mut changed_positions = BTreeSet::new();
+    for position in positions {
+        if changed_positions.contains(&position) {
+            continue;
+        }
+        let Some(Some((fluid, original_level))) = chunk_map.with_block_at(position, FluidType::from_block) else {
+            continue;
+        };
+
+        let mixed_block = ADJACENT_OFFSETS.iter().find_map(|&offset| {
+            let (other_fluid, _) = chunk_map.with_block_at(position + offset, FluidType::from_block)??;
+            fluid.mix_with(other_fluid, original_level)
+        });
+        if let Some(block) = mixed_block {
+            chunk_map.set_block_at(position, block, physics);
+            changed_positions.insert(position);
+            continue;
+        }
+
+        let mut level = original_level;
+        let below = position + Vector([0, -1]);
+        if let Some(below_level) = liquid_level_at(chunk_map, below, fluid) {
+            let moved = level.min(MAX_LIQUID_LEVEL - below_level);
+            if moved > 0 {
+                set_liquid_level(chunk_map, below, fluid, below_level + moved, physics);
+                changed_positions.insert(below);
+                level -= moved;
+            }
+        }
+
+        if tick.is_multiple_of(fluid.flow_interval()) {
+            for side in sides {
+                let neighbor = position + side;
+                let Some(neighbor_level) = liquid_level_at(chunk_map, neighbor, fluid) else {
+                    continue;
+                };
+                if neighbor_level + 1 < level {
+                    set_liquid_level(chunk_map, neighbor, fluid, neighbor_level + 1, physics);
+                    changed_positions.insert(neighbor);
+                    level -= 1;
+                }
+            }
+        }
+
+        if level != original_level {
+            set_liquid_level(chunk_map, position, fluid, level, physics);
+            changed_positions.insert(position);
+        }
+    }
+}
+
+/// Add liquid to a block, returning how many levels were added.
+pub fn add_liquid(chunk_map: &ChunkMap, position: Vector<i64, 2>, fluid: FluidType, levels: u8, physics: &mut Physics) -> u8 {
+    let Some(current_level) = liquid_level_at(chunk_map, position, fluid) else {
+        return 0;
+    };
+    let added = levels.min(MAX_LIQUID_LEVEL - current_level);
+    if added > 0 {
+        set_liquid_level(chunk_map, position, fluid, current_level + added, physics);
+    }
+    added
+}
+
+/// Measure how much of a rectangle is covered by liquid, returning the most common liquid and
+/// the fraction of the rectangle's area it covers.
+pub fn liquid_submersion(chunk_map: &ChunkMap, rectangle: Rectangle<f32>) -> Option<(FluidType, f32)> {
+    let area = rectangle.span().x() * rectangle.span().y();
+    if area <= 0.0 {
+        return None;
+    }
+    let mut covered_areas = [0.0; FluidType::ALL.len()];
+    for y in rectangle.min.y().floor() as i64 ..= rectangle.max.y().floor() as i64 {
+        for x in rectangle.min.x().floor() as i64 ..= rectangle.max.x().floor() as i64 {
+            let Some(Some((fluid, level))) = chunk_map.with_block_at(Vector([x, y]), FluidType::from_block) else {
+                continue;
+            };
+            let surface = y as f32 + level as f32 / MAX_LIQUID_LEVEL as f32;
+            let width = (rectangle.max.x().min(x as f32 + 1.0) - rectangle.min.x().max(x as f32)).max(0.0);
+            let height = (rectangle.max.y().min(surface) - rectangle.min.y().max(y as f32)).max(0.0);
+            covered_areas[fluid.attribute_index() as usize - 1] += width * height;
+        }
+    }
+    std::iter::zip(FluidType::ALL, covered_areas)
+        .filter(|&(_, covered_area)| covered_area > 0.0)
+        .max_by(|(_, area_1), (_, area_2)| area_1.total_cmp(area_2))
+        .map(|(fluid, covered_area)| (fluid, (covered_area / area).min(1.0)))
+}
@@ -1,12 +1,29 @@
+use std::mem::offset_of;
+use innovus::gfx::{Vertex, VertexAttribute, VertexAttributeType};
 use innovus::tools::*;
-use crate::world::item::{Item, ItemType};
+use innovus::tools::phys::{ColliderShape, CollisionSide};
+use crate::tools::noise::SimpleRandom;
+use crate::world::item::{Item, ItemType, ToolClass};
 
 mod chunk;
+pub mod crack;
+mod light;
+mod liquid;
+mod pipe;
+mod power;
 pub mod preview;
 pub mod types;
+mod update;
+pub mod wall;
 
 pub use types::BLOCK_TYPES;
 pub use chunk::*;
+pub use light::*;
+pub use liquid::*;
+pub use pipe::*;
+pub use power::*;
+pub use update::*;
+pub use wall::{get_wall_type, WallType, WALL_TYPES};
 
 #[derive(Default, Copy, Clone, PartialEq, Debug)]
 pub enum BlockSide {
@@ -115,15 +132,88 @@ impl AttributeValue {
     }
 }
 
+/// A physics collider belonging to a block. Bounds are measured in 32nds of a block.
+#[derive(Copy, Clone, Debug)]
+pub struct BlockCollider {
+    pub bounds: Rectangle<i32>,
+    pub shape: ColliderShape,
+    pub one_way: Option<CollisionSide>,
+}
+
+impl BlockCollider {
+    pub const fn solid(bounds: Rectangle<i32>) -> Self {
+        Self {
+            bounds,
+            shape: ColliderShape::Rectangle,
+            one_way: None,
+        }
+    }
+
+    pub const fn slope(bounds: Rectangle<i32>, rises_right: bool) -> Self {
+        Self {
+            bounds,
+            shape: ColliderShape::Slope { rises_right },
+            one_way: None,
+        }
+    }
+
+    /// A collider which can only be collided with from the given side, such as a platform
+    /// which can be jumped through from below.
+    pub const fn one_way(bounds: Rectangle<i32>, side: CollisionSide) -> Self {
+        Self {
+            bounds,
+            shape: ColliderShape::Rectangle,
+            one_way: Some(side),
+        }
+    }
+}
+
+/// An entry in a block's drop table. Breaking the block drops between `min_count` and `max_count`
+/// of the item, inclusive.
+#[derive(Copy, Clone, Debug)]
+pub struct BlockDrop {
+    pub item_type: &'static ItemType,
+    pub min_count: u32,
+    pub max_count: u32,
+}
+
+impl BlockDrop {
+    pub const fn new(item_type: &'static ItemType, min_count: u32, max_count: u32) -> Self {
+        Self {
+            item_type,
+            min_count,
+            max_count,
+        }
+    }
+
+    pub const fn one(item_type: &'static ItemType) -> Self {
+        Self::new(item_type, 1, 1)
+    }
+}
+
 pub struct BlockType {
     name: &'static str,
     attributes: &'static [(&'static str, AttributeType)],
     item_type: Option<&'static ItemType>,
-    colliders: &'static [Rectangle<i32>],
+    drops: Option<&'static [BlockDrop]>,
+    colliders: fn(&Block) -> &'static [BlockCollider],
+    friction: f32,
+    restitution: f32,
+    affected_by_gravity: bool,
+    hardness: f32,
+    preferred_tool: Option<ToolClass>,
+    required_tier: u8,
     palette_key: Option<&'static str>,
     is_full_block: fn(&Block) -> bool,
-    light_emission: fn(&Block) -> u8,
+    light_opacity: u8,
+    light_emission: fn(&Block) -> BlockLight,
+    power_loss: Option<u8>,
+    power_output: fn(&Block) -> u8,
+    power_changed: fn(&Block, u8) -> Option<Block>,
     connects_to: fn(&Block, &Block) -> bool,
+    neighbor_changed: fn(&Block, &mut BlockUpdate),
+    scheduled_tick: fn(&Block, &mut BlockUpdate),
+    random_tick: fn(&Block, &mut BlockUpdate),
     right_click: fn(&Block, &Item, BlockSide) -> (Option<Block>, Option<Item>),
 }
 
@@ -140,14 +230,84 @@ impl BlockType {
         self.item_type
     }
 
-    pub fn colliders(&self) -> &'static [Rectangle<i32>] {
-        self.colliders
+    /// The items dropped when a block of this type is broken, picking a count for each entry in
+    /// the drop table. Blocks without a drop table drop one of their own item.
+    pub fn roll_drops(&self, random: &mut SimpleRandom) -> Vec<Item> {
+        let Some(drops) = self.drops else {
+            return self.item_type.map(|item_type| Item::new(item_type, 1)).into_iter().collect();
+        };
+        drops
+            .iter()
+            .map(|drop| {
+                let spread = drop.max_count.saturating_sub(drop.min_count) as u64;
+                Item::new(drop.item_type, drop.min_count + random.next_below(spread + 1) as u32)
+            })
+            .filter(|item| !item.is_empty())
+            .collect()
+    }
+
+    pub fn friction(&self) -> f32 {
+        self.friction
+    }
+
+    pub fn restitution(&self) -> f32 {
+        self.restitution
+    }
+
+    /// Whether blocks of this type fall when there is nothing underneath them.
+    pub fn affected_by_gravity(&self) -> bool {
+        self.affected_by_gravity
+    }
+
+    /// How many seconds it takes to mine a block of this type by hand.
+    pub fn hardness(&self) -> f32 {
+        self.hardness
+    }
+
+    /// The kind of tool which mines this block faster, if any.
+    pub fn preferred_tool(&self) -> Option<ToolClass> {
+        self.preferred_tool
+    }
+
+    /// The lowest tier of the preferred tool which makes this block drop anything when mined,
+    /// where 0 means it always drops.
+    pub fn required_tier(&self) -> u8 {
+        self.required_tier
+    }
+
+    /// Whether mining this block with the given item makes it drop anything.
+    pub fn can_harvest_with(&self, tool: &ItemType) -> bool {
+        self.required_tier == 0
+            || (tool.tool_class() == self.preferred_tool && tool.tool_tier() >= self.required_tier)
+    }
+
+    /// How many seconds it takes to mine this block with the given item. Blocks which can't be
+    /// harvested with the item take much longer.
+    pub fn mining_time(&self, tool: &ItemType) -> f32 {
+        let time = self.hardness / tool.mining_speed(self.preferred_tool);
+        if self.can_harvest_with(tool) {
+            time
+        }
+        else {
+            time * 3.0
+        }
     }
 
     pub fn palette_key(&self) -> Option<&'static str> {
         self.palette_key
     }
 
+    /// How much power is lost when passing through this block, or `None` if it isn't a conductor.
+    pub fn power_loss(&self) -> Option<u8> {
+        self.power_loss
+    }
+
+    /// How much light is lost when passing into this block. Blocks which lose more than one level
+    /// also cast shadows from the sky.
+    pub fn light_opacity(&self) -> u8 {
+        self.light_opacity
+    }
+
     pub fn get_attribute_info(&self, name: &str) -> Option<(usize, &AttributeType)> {
         self.attributes
             .iter()
@@ -175,6 +335,13 @@ impl BlockType {
     }
 }
 
+pub fn get_block_type(name: &str) -> Option<&'static BlockType> {
+    BLOCK_TYPES
+        .iter()
+        .copied()
+        .find(|block_type| block_type.name() == name)
+}
+
 impl PartialEq for BlockType {
     fn eq(&self, other: &Self) -> bool {
         // Comparing pointers is sufficient; only the static BlockType objects should be used.
@@ -208,6 +375,43 @@ pub const QUADRANT_VERTEX_OFFSETS: [Vector<f32, 2>; 4] = [
 ];
 pub const VERTICES_PER_BLOCK: usize = QUADRANT_OFFSETS.len() * QUADRANT_VERTEX_OFFSETS.len();
 
+/// A vertex of a block mesh. Block light and sky light are stored separately so that sky light
+/// can follow the time of day without the mesh being rebuilt.
+#[repr(C)]
+#[derive(Clone, Debug)]
+pub struct BlockVertex {
+    pub position: Vector<f32, 3>,
+    /// Red, green and blue block light, followed by sky light.
+    pub light: Vector<f32, 4>,
+    pub uv: Vector<f32, 2>,
+    pub opacity: f32,
+}
+
+impl BlockVertex {
+    pub fn new(position: Vector<f32, 3>, light: Option<Vector<f32, 4>>, uv: Option<Vector<f32, 2>>) -> Self {
+        Self {
+            position,
+            light: light.unwrap_or(Vector::one()),
+            uv: uv.unwrap_or(Vector::filled(f32::NAN)),
+            opacity: 1.0,
+        }
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+}
+
+impl Vertex for BlockVertex {
+    const ATTRIBUTES: &'static [VertexAttribute] = &[
+        VertexAttribute::new(VertexAttributeType::F32, 3, offset_of!(Self, position)),
+        VertexAttribute::new(VertexAttributeType::F32, 4, offset_of!(Self, light)),
+        VertexAttribute::new(VertexAttributeType::F32, 2, offset_of!(Self, uv)),
+        VertexAttribute::new(VertexAttributeType::F32, 1, offset_of!(Self, opacity)),
+    ];
+}
+
 #[derive(Clone, Debug)]
 pub struct Block {
     block_type: &'static BlockType,
@@ -238,18 +442,68 @@ impl Block {
         self.attributes[index] = value;
     }
 
+    pub fn colliders(&self) -> &'static [BlockCollider] {
+        (self.block_type.colliders)(self)
+    }
+
     pub fn is_full_block(&self) -> bool {
         (self.block_type.is_full_block)(self)
     }
 
-    pub fn light_emission(&self) -> u8 {
+    /// Whether other blocks can take this block's place, e.g. when falling into it.
+    pub fn is_replaceable(&self) -> bool {
+        self.block_type == &types::AIR || FluidType::from_block(self).is_some()
+    }
+
+    pub fn light_opacity(&self) -> u8 {
+        self.block_type.light_opacity
+    }
+
+    pub fn blocks_sky_light(&self) -> bool {
+        self.light_opacity() > 1
+    }
+
+    pub fn light_emission(&self) -> BlockLight {
         (self.block_type.light_emission)(self)
     }
 
+    pub fn power_loss(&self) -> Option<u8> {
+        self.block_type.power_loss
+    }
+
+    pub fn power_output(&self) -> u8 {
+        (self.block_type.power_output)(self)
+    }
+
+    pub fn is_power_component(&self) -> bool {
+        self.power_loss().is_some() || self.power_output() > 0
+    }
+
+    /// Get the block this conductor becomes when its network supplies it with `power`,
+    /// or `None` if it doesn't change.
+    pub fn handle_power_change(&self, power: u8) -> Option<Self> {
+        (self.block_type.power_changed)(self, power)
+    }
+
     pub fn connects_to(&self, other: &Self) -> bool {
         (self.block_type.connects_to)(self, other)
     }
 
+    /// React to a change in this block or one of its neighbors.
+    pub fn handle_neighbor_changed(&self, update: &mut BlockUpdate) {
+        (self.block_type.neighbor_changed)(self, update)
+    }
+
+    /// React to a tick previously scheduled for this block's position.
+    pub fn handle_scheduled_tick(&self, update: &mut BlockUpdate) {
+        (self.block_type.scheduled_tick)(self, update)
+    }
+
+    /// React to being chosen for a random tick, which is how slow processes like growth happen.
+    pub fn handle_random_tick(&self, update: &mut BlockUpdate) {
+        (self.block_type.random_tick)(self, update)
+    }
+
     pub fn handle_right_click(&self, held_item: &Item, side: BlockSide) -> (Option<Self>, Option<Item>) {
         (self.block_type.right_click)(self, held_item, side)
     }
@@ -260,3 +514,11 @@ impl Default for Block {
         Self::new(&types::AIR, Default::default())
     }
 }
+
+/// An empty world with the chunks around the origin loaded and lit.
+#[cfg(test)]
+fn loaded_chunk_map(physics: &mut innovus::tools::phys::Physics) -> ChunkMap {
+    let mut chunk_map = ChunkMap::new(None, None);
+    chunk_map.tick(Vector([8.0, 8.0]), physics);
+    chunk_map
+}
@@ -0,0 +1,400 @@
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeSet;
+use innovus::tools::{Rectangle, Vector};
+use innovus::tools::phys::Physics;
+use super::*;
+
+/// The most fluid a single pipe or spout can hold.
+pub const PIPE_CAPACITY: u32 = 100;
+/// The most fluid a spout can emit per tick, which fills one level of a liquid block.
+pub const SPOUT_FLOW_RATE: u32 = 5;
+
+const ADJACENT_OFFSETS: [Vector<i64, 2>; 4] = [
+    Vector([0, 1]),
+    Vector([0, -1]),
+    Vector([-1, 0]),
+    Vector([1, 0]),
+];
+// Indexed by the value of the spout's "direction" attribute
+const SPOUT_DIRECTIONS: [Vector<i64, 2>; 4] = [
+    Vector([0, -1]),
+    Vector([-1, 0]),
+    Vector([1, 0]),
+    Vector([0, 1]),
+];
+
+/// Fluid leaving a spout during a tick.
+#[derive(Clone, Debug)]
+pub struct FluidEmission {
+    /// The block the fluid is emitted into.
+    pub target: Vector<i64, 2>,
+    pub direction: Vector<i64, 2>,
+    pub fluid: FluidType,
+    pub amount: u32,
+}
+
+pub fn is_pipe(block: &Block) -> bool {
+    block.block_type() == &types::PIPE || block.block_type() == &types::PIPE_SPOUT
+}
+
+fn pipe_contents(block: &Block) -> (Option<FluidType>, u32) {
+    let block_type = block.block_type();
+    let (Some((fluid_index, _)), Some((amount_index, _))) = (block_type.get_attribute_info("fluid"), block_type.get_attribute_info("amount")) else {
+        return (None, 0);
+    };
+    let fluid = FluidType::from_attribute_index(block.attribute_value(fluid_index).expect_u8());
+    let amount = block.attribute_value(amount_index).expect_u8() as u32;
+    (fluid, amount)
+}
+
+fn with_pipe_contents(block: &Block, fluid: Option<FluidType>, amount: u32) -> Option<Block> {
+    let block_type = block.block_type();
+    let (fluid_index, _) = block_type.get_attribute_info("fluid")?;
+    let (amount_index, _) = block_type.get_attribute_info("amount")?;
+    let fluid_value = AttributeValue::U8(fluid.map_or(0, |fluid| fluid.attribute_index()));
+    let amount_value = AttributeValue::U8(amount.min(u8::MAX as u32) as u8);
+    if block.attribute_value(fluid_index) == &fluid_value && block.attribute_value(amount_index) == &amount_value {
+        return None;
+    }
+    let mut block = block.clone();
+    block.set_attribute_value(fluid_index, fluid_value);
+    block.set_attribute_value(amount_index, amount_value);
+    Some(block)
+}
+
+/// Caches the pipe networks within the simulate range between ticks. A network is only
+/// flood-filled again when one of its pipes is placed or broken, or the simulate range moves.
+#[derive(Debug)]
+pub struct PipeGrid {
+    networks: RefCell<Vec<PipeNetwork>>,
+    dirty_positions: RefCell<BTreeSet<Vector<i64, 2>>>,
+    chunk_range: Cell<Option<Rectangle<i64>>>,
+    emissions: RefCell<Vec<FluidEmission>>,
+}
+
+impl PipeGrid {
+    pub fn new() -> Self {
+        Self {
+            networks: RefCell::new(Vec::new()),
+            dirty_positions: RefCell::new(BTreeSet::new()),
+            chunk_range: Cell::new(None),
+            emissions: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn mark_dirty(&self, position: Vector<i64, 2>) {
+        self.dirty_positions.borrow_mut().insert(position);
+    }
+
+    /// Take the fluid emitted by spouts since the last call.
+    pub fn take_emissions(&self) -> Vec<FluidEmission> {
+        std::mem::take(&mut *self.emissions.borrow_mut())
+    }
+
+    /// The number of cached networks.
+    pub fn network_count(&self) -> usize {
+        self.networks.borrow().len()
+    }
+
+    /// Rebuild the networks touching a dirty position, or every network if the range has moved.
+    fn refresh(&self, chunk_map: &ChunkMap, chunk_range: Rectangle<i64>) {
+        if self.chunk_range.get() != Some(chunk_range) {
+            self.chunk_range.set(Some(chunk_range));
+            self.networks.borrow_mut().clear();
+            let mut dirty_positions = self.dirty_positions.borrow_mut();
+            for chunk_y in chunk_range.min.y() ..= chunk_range.max.y() {
+                for chunk_x in chunk_range.min.x() ..= chunk_range.max.x() {
+                    let Some(chunk) = chunk_map.get(Vector([chunk_x, chunk_y])) else {
+                        continue;
+                    };
+                    for y in 0..CHUNK_SIZE {
+                        for x in 0..CHUNK_SIZE {
+                            if is_pipe(chunk.block_at(x, y)) {
+                                dirty_positions.insert(chunk.block_position(x, y));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let dirty_positions = std::mem::take(&mut *self.dirty_positions.borrow_mut());
+        if dirty_positions.is_empty() {
+            return;
+        }
+        // A changed pipe may have joined or split the networks next to it
+        let mut starts: BTreeSet<Vector<i64, 2>> = BTreeSet::new();
+        for &position in &dirty_positions {
+            starts.insert(position);
+            starts.extend(ADJACENT_OFFSETS.map(|offset| position + offset));
+        }
+        let mut networks = self.networks.borrow_mut();
+        networks.retain(|network| {
+            if starts.iter().any(|&position| network.contains(position)) {
+                starts.extend(network.members.iter().copied());
+                false
+            }
+            else {
+                true
+            }
+        });
+
+        let mut visited = BTreeSet::new();
+        for start in starts {
+            if visited.contains(&start) {
+                continue;
+            }
+            if let Some(network) = PipeNetwork::collect(start, chunk_map, Some(chunk_range)) {
+                visited.extend(network.members.iter().copied());
+                networks.push(network);
+            }
+        }
+    }
+}
+
+impl Default for PipeGrid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A connected group of pipes and spouts which share a single fluid.
+///
+/// The fluid itself is stored in the attributes of each member, so it is read back from the
+/// world whenever the network is used. This keeps it correct as pipes are loaded and unloaded.
+#[derive(Debug)]
+pub struct PipeNetwork {
+    members: BTreeSet<Vector<i64, 2>>,
+    spouts: Vec<(Vector<i64, 2>, Vector<i64, 2>)>,
+    fluid: Option<FluidType>,
+    amount: u32,
+}
+
+impl PipeNetwork {
+    /// Flood-fill the network containing the pipe at `start`. Pipes in unloaded chunks are skipped.
+    pub fn find_at(start: Vector<i64, 2>, chunk_map: &ChunkMap) -> Option<Self> {
+        Self::collect(start, chunk_map, None)
+    }
+
+    /// Flood-fill the network containing the pipe at `start`, skipping any pipes outside of
+    /// `chunk_range`.
+    fn collect(start: Vector<i64, 2>, chunk_map: &ChunkMap, chunk_range: Option<Rectangle<i64>>) -> Option<Self> {
+        let in_range = |position: Vector<i64, 2>| chunk_range.is_none_or(|chunk_range| {
+            chunk_range.contains_inclusive(Vector([BlockCoord::from(position.x()).chunk, BlockCoord::from(position.y()).chunk]))
+        });
+        if !chunk_map.with_block_at(start, is_pipe)? || !in_range(start) {
+            return None;
+        }
+
+        let mut network = Self {
+            members: BTreeSet::new(),
+            spouts: Vec::new(),
+            fluid: None,
+            amount: 0,
+        };
+        let mut stack = vec![start];
+        while let Some(position) = stack.pop() {
+            if network.members.contains(&position) || !in_range(position) {
+                continue;
+            }
+            let Some(block) = chunk_map.with_block_at(position, Block::clone) else {
+                continue;
+            };
+            if !is_pipe(&block) {
+                continue;
+            }
+            network.members.insert(position);
+
+            if block.block_type() == &types::PIPE_SPOUT {
+                let direction = SPOUT_DIRECTIONS[block.attribute_value(0).expect_u8() as usize % 4];
+                network.spouts.push((position, direction));
+            }
+            stack.extend(ADJACENT_OFFSETS.map(|offset| position + offset));
+        }
+        network.load(chunk_map);
+        Some(network)
+    }
+
+    /// Read the network's fluid back from its members.
+    fn load(&mut self, chunk_map: &ChunkMap) {
+        // Fluids can't mix, so whichever fluid there is most of in the network wins out
+        let mut fluid_amounts = [0; FluidType::ALL.len()];
+        for &position in &self.members {
+            if let Some((Some(fluid), amount)) = chunk_map.with_block_at(position, pipe_contents) {
+                fluid_amounts[fluid.attribute_index() as usize - 1] += amount;
+            }
+        }
+        (self.fluid, self.amount) = std::iter::zip(FluidType::ALL, fluid_amounts)
+            .filter(|&(_, amount)| amount > 0)
+            .max_by_key(|&(_, amount)| amount)
+            .map_or((None, 0), |(fluid, amount)| (Some(fluid), amount));
+    }
+
+    pub fn contains(&self, position: Vector<i64, 2>) -> bool {
+        self.members.contains(&position)
+    }
+
+    pub fn fluid(&self) -> Option<FluidType> {
+        self.fluid
+    }
+
+    pub fn amount(&self) -> u32 {
+        self.amount
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.members.len() as u32 * PIPE_CAPACITY
+    }
+
+    /// Add fluid to the network, returning how much was actually added. Nothing is added if the
+    /// network already holds a different fluid.
+    pub fn insert_fluid(&mut self, fluid: FluidType, amount: u32) -> u32 {
+        if self.fluid.is_some_and(|current| current != fluid) {
+            return 0;
+        }
+        let inserted = amount.min(self.capacity().saturating_sub(self.amount));
+        if inserted > 0 {
+            self.fluid = Some(fluid);
+            self.amount += inserted;
+        }
+        inserted
+    }
+
+    /// Push fluid out of each spout whose outlet isn't blocked, filling the block in front of it.
+    pub fn emit(&mut self, chunk_map: &ChunkMap, physics: &mut Physics) -> Vec<FluidEmission> {
+        let mut emissions = Vec::new();
+        let Some(fluid) = self.fluid else {
+            return emissions;
+        };
+        for &(position, direction) in &self.spouts {
+            if self.amount == 0 {
+                break;
+            }
+            let target = position + direction;
+            if add_liquid(chunk_map, target, fluid, 1, physics) == 0 {
+                continue;
+            }
+            let amount = SPOUT_FLOW_RATE.min(self.amount);
+            self.amount -= amount;
+            emissions.push(FluidEmission {
+                target,
+                direction,
+                fluid,
+                amount,
+            });
+        }
+        if self.amount == 0 {
+            self.fluid = None;
+        }
+        emissions
+    }
+
+    /// Spread the network's fluid evenly across its members and write it back to the world.
+    pub fn store(&self, chunk_map: &ChunkMap, physics: &mut Physics) {
+        let member_count = self.members.len() as u32;
+        for (index, &position) in self.members.iter().enumerate() {
+            let mut amount = self.amount / member_count;
+            if (index as u32) < self.amount % member_count {
+                amount += 1;
+            }
+            let fluid = self.fluid.filter(|_| amount > 0);
+
+            let coord_x = BlockCoord::from(position.x());
+            let coord_y = BlockCoord::from(position.y());
+            let Some(mut chunk) = chunk_map.get_mut(Vector([coord_x.chunk, coord_y.chunk])) else {
+                continue;
+            };
+            if let Some(block) = with_pipe_contents(chunk.block_at(coord_x.offset, coord_y.offset), fluid, amount) {
+                chunk.replace_block_at(coord_x.offset, coord_y.offset, block, chunk_map, physics);
+            }
+        }
+    }
+}
+
+/// Move fluid through every pipe network within the given range of chunks for one tick.
+/// The fluid emitted by spouts is collected by the chunk map's [`PipeGrid`].
+pub fn update_pipes(chunk_map: &ChunkMap, chunk_range: Rectangle<i64>, physics: &mut Physics) {
+    let pipe_grid = chunk_map.pipe_grid();
+    pipe_grid.refresh(chunk_map, chunk_range);
+    let mut emissions = Vec::new();
+    for network in pipe_grid.networks.borrow_mut().iter_mut() {
+        network.load(chunk_map);
+        emissions.extend(network.emit(chunk_map, physics));
+        network.store(chunk_map, physics);
+    }
+    pipe_grid.emissions.borrow_mut().extend(emissions);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_block(chunk_map: &mut ChunkMap, position: [i64; 2], block_type: &'static BlockType, physics: &mut Physics) {
+        assert!(chunk_map.set_block_at(Vector(position), Block::new(block_type, BlockSide::None), physics));
+    }
+
+    #[test]
+    fn networks_are_rebuilt_when_pipes_change() {
+        let mut physics = Physics::new();
+        let mut chunk_map = loaded_chunk_map(&mut physics);
+        for x in 0..3 {
+            set_block(&mut chunk_map, [x, 5], &types::PIPE, &mut physics);
+        }
+        set_block(&mut chunk_map, [4, 5], &types::PIPE, &mut physics);
+        chunk_map.tick(Vector([8.0, 8.0]), &mut physics);
+        assert_eq!(chunk_map.pipe_grid().network_count(), 2);
+
+        // Joining the two networks
+        set_block(&mut chunk_map, [3, 5], &types::PIPE, &mut physics);
+        chunk_map.tick(Vector([8.0, 8.0]), &mut physics);
+        assert_eq!(chunk_map.pipe_grid().network_count(), 1);
+
+        // Splitting the network again
+        set_block(&mut chunk_map, [1, 5], &types::AIR, &mut physics);
+        chunk_map.tick(Vector([8.0, 8.0]), &mut physics);
+        assert_eq!(chunk_map.pipe_grid().network_count(), 2);
+    }
+
+    #[test]
+    fn pipes_outside_the_simulate_range_are_ignored() {
+        let mut physics = Physics::new();
+        let mut chunk_map = loaded_chunk_map(&mut physics);
+        // Chunk 2 is loaded, but only chunks -1 to 1 are simulated around the player
+        let outside_x = 2 * CHUNK_SIZE as i64;
+        set_block(&mut chunk_map, [outside_x, 5], &types::PIPE, &mut physics);
+        set_block(&mut chunk_map, [outside_x - 1, 5], &types::PIPE, &mut physics);
+        chunk_map.tick(Vector([8.0, 8.0]), &mut physics);
+        let pipe_grid = chunk_map.pipe_grid();
+        assert_eq!(pipe_grid.network_count(), 1);
+        assert!(pipe_grid.networks.borrow()[0].contains(Vector([outside_x - 1, 5])));
+        assert!(!pipe_grid.networks.borrow()[0].contains(Vector([outside_x, 5])));
+
+        // Once the player moves over, the whole network is simulated
+        chunk_map.tick(Vector([outside_x as f32, 8.0]), &mut physics);
+        let pipe_grid = chunk_map.pipe_grid();
+        assert_eq!(pipe_grid.network_count(), 1);
+        assert_eq!(pipe_grid.networks.borrow()[0].capacity(), 2 * PIPE_CAPACITY);
+    }
+
+    #[test]
+    fn cached_networks_emit_pumped_fluid() {
+        let mut physics = Physics::new();
+        let mut chunk_map = loaded_chunk_map(&mut physics);
+        set_block(&mut chunk_map, [3, 8], &types::PIPE, &mut physics);
+        set_block(&mut chunk_map, [3, 7], &types::PIPE_SPOUT, &mut physics);
+        chunk_map.tick(Vector([8.0, 8.0]), &mut physics);
+        assert!(chunk_map.pipe_grid().take_emissions().is_empty());
+
+        let mut network = PipeNetwork::find_at(Vector([3, 8]), &chunk_map).unwrap();
+        assert_eq!(network.insert_fluid(FluidType::Water, 20), 20);
+        network.store(&chunk_map, &mut physics);
+        chunk_map.tick(Vector([8.0, 8.0]), &mut physics);
+
+        let emissions = chunk_map.pipe_grid().take_emissions();
+        assert_eq!(emissions.len(), 1);
+        assert_eq!(emissions[0].target, Vector([3, 6]));
+        assert_eq!(emissions[0].amount, SPOUT_FLOW_RATE);
+        let network = PipeNetwork::find_at(Vector([3, 8]), &chunk_map).unwrap();
+        assert_eq!(network.amount(), 20 - SPOUT_FLOW_RATE);
+    }
+}
@@ -0,0 +1,133 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use innovus::tools::Vector;
+use innovus::tools::phys::Physics;
+use super::*;
+
+/// The strongest power level a source can supply.
+pub const MAX_POWER: u8 = 15;
+/// Networks larger than this are only partially recalculated, to keep a single update from
+/// stalling the game.
+pub const MAX_NETWORK_SIZE: usize = 4096;
+
+const ADJACENT_OFFSETS: [Vector<i64, 2>; 4] = [
+    Vector([0, 1]),
+    Vector([0, -1]),
+    Vector([-1, 0]),
+    Vector([1, 0]),
+];
+
+/// Tracks which power networks need recalculating. Conductors are only revisited when a
+/// conductor or power source near them changes, so idle networks cost nothing per tick.
+#[derive(Debug)]
+pub struct PowerGrid {
+    dirty_positions: RefCell<BTreeSet<Vector<i64, 2>>>,
+}
+
+impl PowerGrid {
+    pub fn new() -> Self {
+        Self {
+            dirty_positions: RefCell::new(BTreeSet::new()),
+        }
+    }
+
+    pub fn mark_dirty(&self, position: Vector<i64, 2>) {
+        self.dirty_positions.borrow_mut().insert(position);
+    }
+
+    pub fn has_pending_updates(&self) -> bool {
+        !self.dirty_positions.borrow().is_empty()
+    }
+
+    /// Recalculate the power level of every network touching a dirty position.
+    pub fn update(&self, chunk_map: &ChunkMap, physics: &mut Physics) {
+        let dirty_positions = std::mem::take(&mut *self.dirty_positions.borrow_mut());
+        let mut visited = BTreeSet::new();
+        for position in dirty_positions {
+            // A removed conductor may have split its network, so each neighbor is checked separately
+            let starts = std::iter::once(position).chain(ADJACENT_OFFSETS.map(|offset| position + offset));
+            for start in starts {
+                if visited.contains(&start) {
+                    continue;
+                }
+                let network = PowerNetwork::collect(start, chunk_map, &mut visited);
+                network.apply(chunk_map, physics);
+            }
+        }
+    }
+}
+
+impl Default for PowerGrid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A connected group of conductors, along with the power supplied to it by adjacent sources.
+struct PowerNetwork {
+    conductors: BTreeMap<Vector<i64, 2>, u8>,
+    supplies: BinaryHeap<(u8, Vector<i64, 2>)>,
+}
+
+impl PowerNetwork {
+    fn collect(start: Vector<i64, 2>, chunk_map: &ChunkMap, visited: &mut BTreeSet<Vector<i64, 2>>) -> Self {
+        let mut network = Self {
+            conductors: BTreeMap::new(),
+            supplies: BinaryHeap::new(),
+        };
+        let mut stack = vec![start];
+        while let Some(position) = stack.pop() {
+            if network.conductors.len() >= MAX_NETWORK_SIZE || visited.contains(&position) {
+                continue;
+            }
+            // Blocks in unloaded chunks are treated as insulators
+            let Some(Some(power_loss)) = chunk_map.with_block_at(position, Block::power_loss) else {
+                continue;
+            };
+            visited.insert(position);
+            network.conductors.insert(position, power_loss);
+
+            for offset in ADJACENT_OFFSETS {
+                let neighbor = position + offset;
+                let output = chunk_map.with_block_at(neighbor, Block::power_output).unwrap_or(0);
+                if output > power_loss {
+                    network.supplies.push((output - power_loss, position));
+                }
+                stack.push(neighbor);
+            }
+        }
+        network
+    }
+
+    fn apply(mut self, chunk_map: &ChunkMap, physics: &mut Physics) {
+        // Power always flows from the strongest remaining supply first, so each conductor
+        // is assigned its final level the first time it is reached
+        let mut power_levels = BTreeMap::new();
+        while let Some((power, position)) = self.supplies.pop() {
+            if power_levels.contains_key(&position) {
+                continue;
+            }
+            power_levels.insert(position, power);
+            for offset in ADJACENT_OFFSETS {
+                let neighbor = position + offset;
+                if let Some(&power_loss) = self.conductors.get(&neighbor) {
+                    if power > power_loss && !power_levels.contains_key(&neighbor) {
+                        self.supplies.push((power - power_loss, neighbor));
+                    }
+                }
+            }
+        }
+
+        for &position in self.conductors.keys() {
+            let power = power_levels.get(&position).copied().unwrap_or(0);
+            let coord_x = BlockCoord::from(position.x());
+            let coord_y = BlockCoord::from(position.y());
+            let Some(mut chunk) = chunk_map.get_mut(Vector([coord_x.chunk, coord_y.chunk])) else {
+                continue;
+            };
+            if let Some(block) = chunk.block_at(coord_x.offset, coord_y.offset).handle_power_change(power) {
+                chunk.replace_block_at(coord_x.offset, coord_y.offset, block, chunk_map, physics);
+            }
+        }
+    }
+}
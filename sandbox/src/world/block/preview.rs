@@ -1,7 +1,7 @@
-use innovus::gfx::{MeshRenderer, Vertex2D};
+use innovus::gfx::MeshRenderer;
 use innovus::tools::Vector;
 use crate::tools::asset::AssetPool;
-use crate::world::block::{Block, BlockSide, ChunkMap, CHUNK_SIZE, QUADRANT_OFFSETS, QUADRANT_VERTEX_OFFSETS};
+use crate::world::block::{Block, BlockSide, BlockVertex, ChunkMap, CHUNK_SIZE, QUADRANT_OFFSETS, QUADRANT_VERTEX_OFFSETS};
 use crate::world::block::types::AIR;
 use crate::world::item::ItemType;
 
@@ -9,7 +9,7 @@ pub struct BlockPreview {
     position: Vector<f32, 2>,
     item_type: &'static ItemType,
     opacity: f32,
-    mesh: MeshRenderer<Vertex2D>,
+    mesh: MeshRenderer<BlockVertex>,
 }
 
 impl BlockPreview {
@@ -68,7 +68,7 @@ impl BlockPreview {
             if slot.block().block_type() != &AIR {
                 return;
             }
-            let light_value = slot.light_value();
+            let vertex_light = slot.vertex_light();
 
             let block = Block::new(block_type, BlockSide::from_position(self.position));
             if let Some(image) = assets.get_block_image(&block, chunk_location, block_x, block_y) {
@@ -83,14 +83,14 @@ impl BlockPreview {
                     faces.push([index + 2, index + 3, index + 0]);
                     for vertex_offset in QUADRANT_VERTEX_OFFSETS {
                         let total_offset = quadrant_offset + vertex_offset;
-                        vertices.push(Vertex2D::new(
+                        vertices.push(BlockVertex::new(
                             (block_origin + total_offset).with_z(0.0),
-                            Some(Vector([light_value, light_value, light_value, self.opacity])),
+                            Some(vertex_light),
                             Some(Vector([
                                 atlas_offset.x() as f32 + total_offset.x() * image.size() as f32,
                                 atlas_offset.y() as f32 + (1.0 - total_offset.y()) * image.size() as f32,
                             ])),
-                        ));
+                        ).with_opacity(self.opacity));
                     }
                 }
 
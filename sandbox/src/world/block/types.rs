@@ -1,8 +1,54 @@
+use innovus::tools::phys::CollisionSide;
 use crate::tools::*;
 use crate::world::item;
 use super::*;
 
 const BLOCK_RECT: Rectangle<i32> = Rectangle::new(Vector([0, 0]), Vector([32, 32]));
+const LOWER_HALF_RECT: Rectangle<i32> = Rectangle::new(Vector([0, 0]), Vector([32, 16]));
+const UPPER_HALF_RECT: Rectangle<i32> = Rectangle::new(Vector([0, 16]), Vector([32, 32]));
+
+const FULL_BLOCK_COLLIDERS: &[BlockCollider] = &[BlockCollider::solid(BLOCK_RECT)];
+const PLATFORM_COLLIDERS: &[BlockCollider] = &[
+    BlockCollider::one_way(Rectangle::new(Vector([0, 24]), Vector([32, 32])), CollisionSide::Top),
+];
+// Steep slopes rise 45 degrees across a single block. Gentle slopes rise half as fast,
+// so they span two blocks: a lower one and an upper one.
+const STEEP_SLOPE_COLLIDERS: [&[BlockCollider]; 2] = [
+    &[BlockCollider::slope(BLOCK_RECT, true)],
+    &[BlockCollider::slope(BLOCK_RECT, false)],
+];
+const GENTLE_LOWER_SLOPE_COLLIDERS: [&[BlockCollider]; 2] = [
+    &[BlockCollider::slope(LOWER_HALF_RECT, true)],
+    &[BlockCollider::slope(LOWER_HALF_RECT, false)],
+];
+const GENTLE_UPPER_SLOPE_COLLIDERS: [&[BlockCollider]; 2] = [
+    &[BlockCollider::solid(LOWER_HALF_RECT), BlockCollider::slope(UPPER_HALF_RECT, true)],
+    &[BlockCollider::solid(LOWER_HALF_RECT), BlockCollider::slope(UPPER_HALF_RECT, false)],
+];
+
+fn colliders_none(this: &Block) -> &'static [BlockCollider] {
+    let _ = this;
+    &[]
+}
+
+fn colliders_full_block(this: &Block) -> &'static [BlockCollider] {
+    let _ = this;
+    FULL_BLOCK_COLLIDERS
+}
+
+fn colliders_platform(this: &Block) -> &'static [BlockCollider] {
+    let _ = this;
+    PLATFORM_COLLIDERS
+}
+
+fn colliders_slope(this: &Block) -> &'static [BlockCollider] {
+    let direction = this.attribute_value(0).expect_u8() as usize;
+    match this.attribute_value(1).expect_u8() {
+        0 => STEEP_SLOPE_COLLIDERS[direction],
+        1 => GENTLE_LOWER_SLOPE_COLLIDERS[direction],
+        _ => GENTLE_UPPER_SLOPE_COLLIDERS[direction],
+    }
+}
 
 fn full_block_always(this: &Block) -> bool {
     let _ = this;
@@ -14,19 +60,34 @@ fn full_block_never(this: &Block) -> bool {
     false
 }
 
-fn light_emission_0(this: &Block) -> u8 {
+const PHYLUMUS_LIGHT: BlockLight = Vector([6, 15, 13]);
+
+fn light_emission_none(this: &Block) -> BlockLight {
     let _ = this;
-    0
+    Vector::zero()
 }
 
-fn light_emission_5(this: &Block) -> u8 {
+fn power_output_none(this: &Block) -> u8 {
     let _ = this;
-    5
+    0
 }
 
-fn light_emission_15(this: &Block) -> u8 {
-    let _ = this;
-    15
+fn power_changed_no_action(this: &Block, power: u8) -> Option<Block> {
+    let _ = (this, power);
+    None
+}
+
+fn power_changed_wire(this: &Block, power: u8) -> Option<Block> {
+    (this.attribute_value(0).expect_u8() != power).then(|| {
+        let mut block = this.clone();
+        block.set_attribute_value(0, AttributeValue::U8(power));
+        block
+    })
+}
+
+fn wire_glow(this: &Block, color: BlockLight) -> BlockLight {
+    // Wires glow faintly while carrying power
+    scale_light(color, this.attribute_value(0).expect_u8() / 3)
 }
 
 fn connects_never(this: &Block, that: &Block) -> bool {
@@ -60,6 +121,101 @@ fn connects_to_trunk(this: &Block, that: &Block) -> bool {
     that.is_full_block() || that.block_type() == &OAK_TRUNK
 }
 
+fn update_no_action(this: &Block, update: &mut BlockUpdate) {
+    let _ = (this, update);
+}
+
+// Indexed by the value of a block's "wall" attribute
+const WALL_OFFSETS: [Vector<i64, 2>; 4] = [
+    Vector([0, -1]),
+    Vector([-1, 0]),
+    Vector([1, 0]),
+    Vector([0, 1]),
+];
+
+fn neighbor_changed_wall_mounted(this: &Block, update: &mut BlockUpdate) {
+    // Break once the block this is attached to is gone
+    let wall = WALL_OFFSETS[this.attribute_value(0).expect_u8() as usize % 4];
+    if update.with_block_at_offset(wall, Block::is_full_block) == Some(false) {
+        update.replace(Block::new(&AIR, BlockSide::None));
+    }
+}
+
+/// The sky light dirt needs before grass can spread onto it.
+const GRASS_MIN_SKY_LIGHT: u8 = 9;
+/// The most sky light phylumus mushrooms can grow in.
+const PHYLUMUS_MAX_SKY_LIGHT: u8 = 2;
+/// Crystal blocks bud once every this many random ticks, on average.
+const CRYSTAL_BUD_CHANCE: u64 = 16;
+
+fn random_tick_grassy_dirt(this: &Block, update: &mut BlockUpdate) {
+    let _ = this;
+    // Grass dies once something covers it up
+    if update.with_block_at_offset(Vector([0, 1]), Block::is_full_block) == Some(true) {
+        update.replace(Block::new(&DIRT, BlockSide::None));
+        return;
+    }
+    let offset = Vector([
+        update.random().next_below(3) as i64 - 1,
+        update.random().next_below(3) as i64 - 1,
+    ]);
+    let can_spread = update.with_slot_at_offset(offset, |slot| {
+        slot.block().block_type() == &DIRT && slot.sky_light() >= GRASS_MIN_SKY_LIGHT
+    });
+    let is_uncovered = update.with_block_at_offset(offset + Vector([0, 1]), Block::is_full_block) == Some(false);
+    if can_spread == Some(true) && is_uncovered {
+        update.set_block_at_offset(offset, Block::new(&GRASSY_DIRT, BlockSide::None));
+    }
+}
+
+fn random_tick_phylumus_mushroom(this: &Block, update: &mut BlockUpdate) {
+    let is_dark = update.with_slot_at_offset(Vector::zero(), BlockSlot::sky_light)
+        .is_some_and(|sky_light| sky_light <= PHYLUMUS_MAX_SKY_LIGHT);
+    let has_ground = update.with_block_at_offset(Vector([0, -1]), Block::is_full_block) == Some(true);
+    if !is_dark || !has_ground {
+        return;
+    }
+
+    // Small mushrooms grow large first, then large mushrooms grow into a stalk with a cap
+    if this.attribute_value(0).expect_u8() == 1 {
+        if update.random().one_in(4) {
+            let mut block = this.clone();
+            block.set_attribute_value(0, AttributeValue::U8(0));
+            update.replace(block);
+        }
+    }
+    else if update.random().one_in(8) {
+        let stalk_height = 2 + update.random().next_below(2) as i64;
+        let offsets: Vec<Vector<i64, 2>> = (1..stalk_height)
+            .map(|dy| Vector([0, dy]))
+            .chain((-1..=1).map(|dx| Vector([dx, stalk_height])))
+            .collect();
+        let has_room = offsets.iter().all(|&offset| {
+            update.with_block_at_offset(offset, |block| block.block_type() == &AIR) == Some(true)
+        });
+        if has_room {
+            update.replace(Block::new(&PHYLUMUS_BLOCK, BlockSide::None));
+            for offset in offsets {
+                update.set_block_at_offset(offset, Block::new(&PHYLUMUS_BLOCK, BlockSide::None));
+            }
+        }
+    }
+}
+
+fn bud_crystal(crystal_type: &'static BlockType, update: &mut BlockUpdate) {
+    if !update.random().one_in(CRYSTAL_BUD_CHANCE) {
+        return;
+    }
+    // The crystal's wall is this block, so it grows on the opposite side of it
+    let wall = update.random().next_below(4) as u8;
+    let offset = -WALL_OFFSETS[wall as usize];
+    if update.with_block_at_offset(offset, |block| block.block_type() == &AIR) == Some(true) {
+        let mut crystal = Block::new(crystal_type, BlockSide::None);
+        crystal.set_attribute_value(0, AttributeValue::U8(wall));
+        update.set_block_at_offset(offset, crystal);
+    }
+}
+
 fn right_click_no_action(target_block: &Block, held_item: &Item, side: BlockSide) -> (Option<Block>, Option<Item>) {
     // Defer to the held item's right click handler
     held_item.handle_right_click(target_block, side)
@@ -69,11 +225,25 @@ const DEFAULTS: BlockType = BlockType {
     name: "invalid",
     attributes: &[],
     item_type: None,
-    colliders: &[BLOCK_RECT],
+    drops: None,
+    colliders: colliders_full_block,
+    friction: 0.5,
+    restitution: 0.0,
+    affected_by_gravity: false,
+    hardness: 1.0,
+    preferred_tool: None,
+    required_tier: 0,
     palette_key: None,
     is_full_block: full_block_always,
-    light_emission: light_emission_0,
+    light_opacity: 2,
+    light_emission: light_emission_none,
+    power_loss: None,
+    power_output: power_output_none,
+    power_changed: power_changed_no_action,
     connects_to: connects_never,
+    neighbor_changed: update_no_action,
+    scheduled_tick: update_no_action,
+    random_tick: update_no_action,
     right_click: right_click_no_action,
 };
 
@@ -103,8 +273,11 @@ pub const BLOCK_TYPES: &[&BlockType] = &[
     &GRASSY_DIRT,
     &IRON_BLOCK,
     &LANTERN,
+    &LAVA,
     &LUMINITE_BLOCK,
     &MAGMIUM_BLOCK,
+    &OAK_PLATFORM,
+    &OAK_SLOPE,
     &OAK_TRUNK,
     &OAK_WOOD,
     &OBSIDIAN_BLOCK,
@@ -123,12 +296,15 @@ pub const BLOCK_TYPES: &[&BlockType] = &[
     &VERSATILIUM_BLOCK,
     &VOLTAGITE_BATTERY,
     &VOLTAGITE_BLOCK,
+    &WATER,
 ];
 
 pub static AIR: BlockType = BlockType {
     name: "air",
-    colliders: &[],
+    colliders: colliders_none,
+    hardness: 0.0,
     is_full_block: full_block_never,
+    light_opacity: 1,
     ..DEFAULTS
 };
 pub static TEST_BLOCK: BlockType = BlockType {
@@ -138,6 +314,9 @@ pub static TEST_BLOCK: BlockType = BlockType {
 pub static ALUMINUM_BLOCK: BlockType = BlockType {
     name: "aluminum_block",
     item_type: Some(&item::types::ALUMINUM_BLOCK),
+    hardness: 4.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 2,
     palette_key: Some("aluminum"),
     connects_to: connects_to_same_type,
     ..DEFAULTS
@@ -145,8 +324,12 @@ pub static ALUMINUM_BLOCK: BlockType = BlockType {
 pub static AMETHYST_BLOCK: BlockType = BlockType {
     name: "amethyst_block",
     item_type: Some(&item::types::AMETHYST_BLOCK),
+    hardness: 4.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 2,
     palette_key: Some("amethyst"),
     connects_to: connects_to_same_type,
+    random_tick: |_, update| bud_crystal(&AMETHYST_CRYSTAL, update),
     ..DEFAULTS
 };
 pub static AMETHYST_CRYSTAL: BlockType = BlockType {
@@ -158,10 +341,14 @@ pub static AMETHYST_CRYSTAL: BlockType = BlockType {
         }),
     ],
     item_type: Some(&item::types::AMETHYST_CRYSTAL),
-    colliders: &[],
+    colliders: colliders_none,
+    hardness: 0.5,
+    preferred_tool: Some(ToolClass::Pickaxe),
     palette_key: Some("amethyst"),
     is_full_block: full_block_never,
-    light_emission: light_emission_5,
+    light_opacity: 1,
+    light_emission: |_| Vector([4, 2, 5]),
+    neighbor_changed: neighbor_changed_wall_mounted,
     right_click: |target_block, _, _| {
         let mut block = target_block.clone();
         let wall = block.attribute_value(0).expect_u8();
@@ -173,6 +360,10 @@ pub static AMETHYST_CRYSTAL: BlockType = BlockType {
 pub static AMETHYST_ORE: BlockType = BlockType {
     name: "amethyst_ore",
     item_type: Some(&item::types::AMETHYST_ORE),
+    drops: Some(&[BlockDrop::new(&item::types::AMETHYST, 1, 2)]),
+    hardness: 3.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 2,
     palette_key: Some("amethyst"),
     connects_to: connects_to_full_block,
     ..DEFAULTS
@@ -180,6 +371,10 @@ pub static AMETHYST_ORE: BlockType = BlockType {
 pub static AMPLIFITE_BLOCK: BlockType = BlockType {
     name: "amplifite_block",
     item_type: Some(&item::types::AMPLIFITE_BLOCK),
+    restitution: 0.8,
+    hardness: 5.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 3,
     palette_key: Some("amplifite"),
     connects_to: connects_to_same_type,
     ..DEFAULTS
@@ -193,9 +388,12 @@ pub static CHAIN: BlockType = BlockType {
         }),
     ],
     item_type: Some(&item::types::CHAIN),
-    colliders: &[],
+    colliders: colliders_none,
+    hardness: 1.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
     palette_key: Some("iron"),
     is_full_block: full_block_never,
+    light_opacity: 1,
     right_click: |target_block, _, _| {
         let mut block = target_block.clone();
         let axis = block.attribute_value(0).expect_u8();
@@ -207,6 +405,9 @@ pub static CHAIN: BlockType = BlockType {
 pub static COAL_BLOCK: BlockType = BlockType {
     name: "coal_block",
     item_type: Some(&item::types::COAL_BLOCK),
+    hardness: 3.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 1,
     palette_key: Some("coal"),
     connects_to: connects_to_same_type,
     ..DEFAULTS
@@ -214,6 +415,9 @@ pub static COAL_BLOCK: BlockType = BlockType {
 pub static COBALT_BLOCK: BlockType = BlockType {
     name: "cobalt_block",
     item_type: Some(&item::types::COBALT_BLOCK),
+    hardness: 5.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 3,
     palette_key: Some("cobalt"),
     connects_to: connects_to_same_type,
     ..DEFAULTS
@@ -221,6 +425,9 @@ pub static COBALT_BLOCK: BlockType = BlockType {
 pub static COBBLES: BlockType = BlockType {
     name: "cobbles",
     item_type: Some(&item::types::COBBLES),
+    hardness: 2.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 1,
     palette_key: Some("stone"),
     connects_to: connects_to_full_block,
     ..DEFAULTS
@@ -228,22 +435,37 @@ pub static COBBLES: BlockType = BlockType {
 pub static COPPER_BLOCK: BlockType = BlockType {
     name: "copper_block",
     item_type: Some(&item::types::COPPER_BLOCK),
+    hardness: 4.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 2,
     palette_key: Some("copper"),
+    power_loss: Some(2),
     connects_to: connects_to_same_type,
     ..DEFAULTS
 };
 pub static COPPER_WIRE: BlockType = BlockType {
     name: "copper_wire",
+    attributes: &[
+        ("power", AttributeType::U8(0)),
+    ],
     item_type: Some(&item::types::COPPER_WIRE),
-    colliders: &[],
+    colliders: colliders_none,
+    hardness: 0.2,
     palette_key: Some("copper"),
     is_full_block: full_block_never,
+    light_opacity: 1,
+    light_emission: |block| wire_glow(block, Vector([15, 9, 5])),
+    power_loss: Some(2),
+    power_changed: power_changed_wire,
     connects_to: connects_to_electricity,
     ..DEFAULTS
 };
 pub static CORRUPTITE_BLOCK: BlockType = BlockType {
     name: "corruptite_block",
     item_type: Some(&item::types::CORRUPTITE_BLOCK),
+    hardness: 5.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 3,
     palette_key: Some("corruptite"),
     connects_to: connects_to_same_type,
     ..DEFAULTS
@@ -251,6 +473,9 @@ pub static CORRUPTITE_BLOCK: BlockType = BlockType {
 pub static DIAMOND_BLOCK: BlockType = BlockType {
     name: "diamond_block",
     item_type: Some(&item::types::DIAMOND_BLOCK),
+    hardness: 6.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 4,
     palette_key: Some("diamond"),
     connects_to: connects_to_same_type,
     ..DEFAULTS
@@ -258,6 +483,8 @@ pub static DIAMOND_BLOCK: BlockType = BlockType {
 pub static DIRT: BlockType = BlockType {
     name: "dirt",
     item_type: Some(&item::types::DIRT),
+    hardness: 0.75,
+    preferred_tool: Some(ToolClass::Shovel),
     palette_key: Some("dirt"),
     connects_to: connects_to_full_block,
     ..DEFAULTS
@@ -265,6 +492,9 @@ pub static DIRT: BlockType = BlockType {
 pub static EMERALD_BLOCK: BlockType = BlockType {
     name: "emerald_block",
     item_type: Some(&item::types::EMERALD_BLOCK),
+    hardness: 6.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 4,
     palette_key: Some("emerald"),
     connects_to: connects_to_same_type,
     ..DEFAULTS
@@ -272,14 +502,21 @@ pub static EMERALD_BLOCK: BlockType = BlockType {
 pub static FLAMARITE_BLOCK: BlockType = BlockType {
     name: "flamarite_block",
     item_type: Some(&item::types::FLAMARITE_BLOCK),
+    hardness: 5.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 3,
     palette_key: Some("flamarite"),
-    light_emission: light_emission_5,
+    light_emission: |_| Vector([5, 2, 0]),
     connects_to: connects_to_same_type,
     ..DEFAULTS
 };
 pub static FRIGIDITE_BLOCK: BlockType = BlockType {
     name: "frigidite_block",
     item_type: Some(&item::types::FRIGIDITE_BLOCK),
+    friction: 0.05,
+    hardness: 5.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 3,
     palette_key: Some("frigidite"),
     connects_to: connects_to_same_type,
     ..DEFAULTS
@@ -287,36 +524,57 @@ pub static FRIGIDITE_BLOCK: BlockType = BlockType {
 pub static GLASS: BlockType = BlockType {
     name: "glass",
     item_type: Some(&item::types::GLASS),
+    hardness: 0.5,
     palette_key: Some("glass"),
+    light_opacity: 1,
     connects_to: connects_to_same_type,
     ..DEFAULTS
 };
 pub static GOLD_BLOCK: BlockType = BlockType {
     name: "gold_block",
     item_type: Some(&item::types::GOLD_BLOCK),
+    hardness: 4.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 2,
     palette_key: Some("gold"),
+    power_loss: Some(1),
     connects_to: connects_to_same_type,
     ..DEFAULTS
 };
 pub static GOLD_WIRE: BlockType = BlockType {
     name: "gold_wire",
+    attributes: &[
+        ("power", AttributeType::U8(0)),
+    ],
     item_type: Some(&item::types::GOLD_WIRE),
-    colliders: &[],
+    colliders: colliders_none,
+    hardness: 0.2,
     palette_key: Some("gold"),
     is_full_block: full_block_never,
+    light_opacity: 1,
+    light_emission: |block| wire_glow(block, Vector([15, 12, 4])),
+    power_loss: Some(1),
+    power_changed: power_changed_wire,
     connects_to: connects_to_electricity,
     ..DEFAULTS
 };
 pub static GRASSY_DIRT: BlockType = BlockType {
     name: "grassy_dirt",
     item_type: Some(&item::types::GRASSY_DIRT),
+    drops: Some(&[BlockDrop::one(&item::types::DIRT)]),
+    hardness: 0.8,
+    preferred_tool: Some(ToolClass::Shovel),
     palette_key: Some("dirt"),
     connects_to: connects_to_full_block,
+    random_tick: random_tick_grassy_dirt,
     ..DEFAULTS
 };
 pub static IRON_BLOCK: BlockType = BlockType {
     name: "iron_block",
     item_type: Some(&item::types::IRON_BLOCK),
+    hardness: 5.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 3,
     palette_key: Some("iron"),
     connects_to: connects_to_same_type,
     ..DEFAULTS
@@ -330,10 +588,13 @@ pub static LANTERN: BlockType = BlockType {
         }),
     ],
     item_type: Some(&item::types::LANTERN),
-    colliders: &[],
+    colliders: colliders_none,
+    hardness: 1.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
     palette_key: Some("iron"),
     is_full_block: full_block_never,
-    light_emission: light_emission_15,
+    light_opacity: 1,
+    light_emission: |_| Vector([15, 12, 8]),
     right_click: |target_block, _, _| {
         let mut block = target_block.clone();
         let type_ = block.attribute_value(0).expect_u8();
@@ -342,21 +603,85 @@ pub static LANTERN: BlockType = BlockType {
     },
     ..DEFAULTS
 };
+pub static LAVA: BlockType = BlockType {
+    name: "lava",
+    attributes: &[
+        ("level", AttributeType::U8(MAX_LIQUID_LEVEL)),
+    ],
+    colliders: colliders_none,
+    hardness: 0.0,
+    palette_key: Some("lava"),
+    is_full_block: full_block_never,
+    light_opacity: 1,
+    light_emission: |block| scale_light(Vector([15, 8, 2]), 7 + block.attribute_value(0).expect_u8()),
+    ..DEFAULTS
+};
 pub static LUMINITE_BLOCK: BlockType = BlockType {
     name: "luminite_block",
     item_type: Some(&item::types::LUMINITE_BLOCK),
+    hardness: 5.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 3,
     palette_key: Some("luminite"),
-    light_emission: light_emission_15,
+    light_emission: |_| Vector([13, 14, 15]),
     connects_to: connects_to_same_type,
     ..DEFAULTS
 };
 pub static MAGMIUM_BLOCK: BlockType = BlockType {
     name: "magmium_block",
     item_type: Some(&item::types::MAGMIUM_BLOCK),
+    hardness: 8.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 5,
     palette_key: Some("magmium"),
     connects_to: connects_to_same_type,
     ..DEFAULTS
 };
+pub static OAK_PLATFORM: BlockType = BlockType {
+    name: "oak_platform",
+    item_type: Some(&item::types::OAK_PLATFORM),
+    colliders: colliders_platform,
+    hardness: 1.0,
+    preferred_tool: Some(ToolClass::Axe),
+    palette_key: Some("wood_oak"),
+    is_full_block: full_block_never,
+    light_opacity: 1,
+    connects_to: connects_to_same_type,
+    ..DEFAULTS
+};
+pub static OAK_SLOPE: BlockType = BlockType {
+    name: "oak_slope",
+    attributes: &[
+        ("direction", AttributeType::Enum {
+            side_default_values: [0, 1, 0, 0, 0],
+            value_names: &["right", "left"],
+        }),
+        ("steepness", AttributeType::Enum {
+            side_default_values: [0; 5],
+            value_names: &["steep", "gentle_lower", "gentle_upper"],
+        }),
+    ],
+    item_type: Some(&item::types::OAK_SLOPE),
+    colliders: colliders_slope,
+    friction: 1.0,
+    hardness: 2.0,
+    preferred_tool: Some(ToolClass::Axe),
+    palette_key: Some("wood_oak"),
+    is_full_block: full_block_never,
+    light_opacity: 1,
+    right_click: |target_block, _, _| {
+        // Cycle through each steepness, then switch direction
+        let mut block = target_block.clone();
+        let direction = block.attribute_value(0).expect_u8();
+        let steepness = block.attribute_value(1).expect_u8();
+        if steepness == 2 {
+            block.set_attribute_value(0, AttributeValue::U8((direction + 1) % 2));
+        }
+        block.set_attribute_value(1, AttributeValue::U8((steepness + 1) % 3));
+        (Some(block), None)
+    },
+    ..DEFAULTS
+};
 pub static OAK_TRUNK: BlockType = BlockType {
     name: "oak_trunk",
     attributes: &[
@@ -366,9 +691,12 @@ pub static OAK_TRUNK: BlockType = BlockType {
         }),
     ],
     item_type: Some(&item::types::OAK_TRUNK),
-    colliders: &[],
+    colliders: colliders_none,
+    hardness: 2.0,
+    preferred_tool: Some(ToolClass::Axe),
     palette_key: Some("bark_oak"),
     is_full_block: full_block_never,
+    light_opacity: 1,
     connects_to: connects_to_trunk,
     right_click: |target_block, _, _| {
         let mut block = target_block.clone();
@@ -381,6 +709,8 @@ pub static OAK_TRUNK: BlockType = BlockType {
 pub static OAK_WOOD: BlockType = BlockType {
     name: "oak_wood",
     item_type: Some(&item::types::OAK_WOOD),
+    hardness: 2.0,
+    preferred_tool: Some(ToolClass::Axe),
     palette_key: Some("wood_oak"),
     connects_to: connects_to_same_type,
     ..DEFAULTS
@@ -388,6 +718,9 @@ pub static OAK_WOOD: BlockType = BlockType {
 pub static OBSIDIAN_BLOCK: BlockType = BlockType {
     name: "obsidian_block",
     item_type: Some(&item::types::OBSIDIAN_BLOCK),
+    hardness: 10.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 5,
     palette_key: Some("obsidian"),
     connects_to: connects_to_full_block,
     ..DEFAULTS
@@ -395,8 +728,10 @@ pub static OBSIDIAN_BLOCK: BlockType = BlockType {
 pub static PHYLUMUS_BLOCK: BlockType = BlockType {
     name: "phylumus_block",
     item_type: Some(&item::types::PHYLUMUS_BLOCK),
+    hardness: 0.5,
+    preferred_tool: Some(ToolClass::Axe),
     palette_key: Some("phylumus"),
-    light_emission: light_emission_15,
+    light_emission: |_| PHYLUMUS_LIGHT,
     connects_to: connects_to_full_block,
     ..DEFAULTS
 };
@@ -409,13 +744,16 @@ pub static PHYLUMUS_MUSHROOM: BlockType = BlockType {
         }),
     ],
     item_type: Some(&item::types::PHYLUMUS_MUSHROOM),
-    colliders: &[],
+    colliders: colliders_none,
+    hardness: 0.2,
     palette_key: Some("phylumus"),
     is_full_block: full_block_never,
+    light_opacity: 1,
     light_emission: |block| {
         let shape = block.attribute_value(0).expect_u8();
-        if shape == 0 { 6 } else { 3 }
+        if shape == 0 { scale_light(PHYLUMUS_LIGHT, 6) } else { scale_light(PHYLUMUS_LIGHT, 3) }
     },
+    random_tick: random_tick_phylumus_mushroom,
     right_click: |target_block, _, _| {
         let mut block = target_block.clone();
         let shape = block.attribute_value(0).expect_u8();
@@ -426,10 +764,20 @@ pub static PHYLUMUS_MUSHROOM: BlockType = BlockType {
 };
 pub static PIPE: BlockType = BlockType {
     name: "pipe",
+    attributes: &[
+        ("fluid", AttributeType::Enum {
+            side_default_values: [0; 5],
+            value_names: FluidType::ATTRIBUTE_NAMES,
+        }),
+        ("amount", AttributeType::U8(0)),
+    ],
     item_type: Some(&item::types::PIPE),
-    colliders: &[],
+    colliders: colliders_none,
+    hardness: 1.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
     palette_key: Some("aluminum"),
     is_full_block: full_block_never,
+    light_opacity: 1,
     connects_to: connects_to_pipe,
     ..DEFAULTS
 };
@@ -440,11 +788,19 @@ pub static PIPE_SPOUT: BlockType = BlockType {
             side_default_values: [0, 2, 1, 3, 0],
             value_names: &["down", "left", "right", "up"],
         }),
+        ("fluid", AttributeType::Enum {
+            side_default_values: [0; 5],
+            value_names: FluidType::ATTRIBUTE_NAMES,
+        }),
+        ("amount", AttributeType::U8(0)),
     ],
     item_type: Some(&item::types::PIPE_SPOUT),
-    colliders: &[],
+    colliders: colliders_none,
+    hardness: 1.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
     palette_key: Some("aluminum"),
     is_full_block: full_block_never,
+    light_opacity: 1,
     right_click: |target_block, _, _| {
         let mut block = target_block.clone();
         let direction = block.attribute_value(0).expect_u8();
@@ -456,8 +812,12 @@ pub static PIPE_SPOUT: BlockType = BlockType {
 pub static QUARTZ_BLOCK: BlockType = BlockType {
     name: "quartz_block",
     item_type: Some(&item::types::QUARTZ_BLOCK),
+    hardness: 4.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 2,
     palette_key: Some("quartz"),
     connects_to: connects_to_same_type,
+    random_tick: |_, update| bud_crystal(&QUARTZ_CRYSTAL, update),
     ..DEFAULTS
 };
 pub static QUARTZ_CRYSTAL: BlockType = BlockType {
@@ -469,10 +829,14 @@ pub static QUARTZ_CRYSTAL: BlockType = BlockType {
         }),
     ],
     item_type: Some(&item::types::QUARTZ_CRYSTAL),
-    colliders: &[],
+    colliders: colliders_none,
+    hardness: 0.5,
+    preferred_tool: Some(ToolClass::Pickaxe),
     palette_key: Some("quartz"),
     is_full_block: full_block_never,
-    light_emission: light_emission_5,
+    light_opacity: 1,
+    light_emission: |_| Vector([5, 5, 5]),
+    neighbor_changed: neighbor_changed_wall_mounted,
     right_click: |target_block, _, _| {
         let mut block = target_block.clone();
         let wall = block.attribute_value(0).expect_u8();
@@ -484,6 +848,10 @@ pub static QUARTZ_CRYSTAL: BlockType = BlockType {
 pub static QUARTZ_ORE: BlockType = BlockType {
     name: "quartz_ore",
     item_type: Some(&item::types::QUARTZ_ORE),
+    drops: Some(&[BlockDrop::new(&item::types::QUARTZ, 1, 2)]),
+    hardness: 3.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 2,
     palette_key: Some("quartz"),
     connects_to: connects_to_full_block,
     ..DEFAULTS
@@ -491,6 +859,9 @@ pub static QUARTZ_ORE: BlockType = BlockType {
 pub static SAND: BlockType = BlockType {
     name: "sand",
     item_type: Some(&item::types::SAND),
+    affected_by_gravity: true,
+    hardness: 0.75,
+    preferred_tool: Some(ToolClass::Shovel),
     palette_key: Some("sand"),
     connects_to: connects_to_full_block,
     ..DEFAULTS
@@ -498,6 +869,9 @@ pub static SAND: BlockType = BlockType {
 pub static SANDSTONE: BlockType = BlockType {
     name: "sandstone",
     item_type: Some(&item::types::SANDSTONE),
+    hardness: 1.5,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 1,
     palette_key: Some("sand"),
     connects_to: connects_to_full_block,
     ..DEFAULTS
@@ -505,6 +879,9 @@ pub static SANDSTONE: BlockType = BlockType {
 pub static SLATE: BlockType = BlockType {
     name: "slate",
     item_type: Some(&item::types::SLATE),
+    hardness: 2.5,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 1,
     palette_key: Some("slate"),
     connects_to: connects_to_full_block,
     ..DEFAULTS
@@ -512,6 +889,9 @@ pub static SLATE: BlockType = BlockType {
 pub static STEEL_BLOCK: BlockType = BlockType {
     name: "steel_block",
     item_type: Some(&item::types::STEEL_BLOCK),
+    hardness: 6.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 4,
     palette_key: Some("steel"),
     connects_to: connects_to_same_type,
     ..DEFAULTS
@@ -519,6 +899,9 @@ pub static STEEL_BLOCK: BlockType = BlockType {
 pub static STONE: BlockType = BlockType {
     name: "stone",
     item_type: Some(&item::types::STONE),
+    hardness: 2.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 1,
     palette_key: Some("stone"),
     connects_to: connects_to_full_block,
     ..DEFAULTS
@@ -526,6 +909,9 @@ pub static STONE: BlockType = BlockType {
 pub static VERSATILIUM_BLOCK: BlockType = BlockType {
     name: "versatilium_block",
     item_type: Some(&item::types::VERSATILIUM_BLOCK),
+    hardness: 5.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 3,
     palette_key: Some("versatilium"),
     connects_to: connects_to_same_type,
     ..DEFAULTS
@@ -536,8 +922,13 @@ pub static VOLTAGITE_BATTERY: BlockType = BlockType {
         ("charge", AttributeType::U8(0)),
     ],
     item_type: Some(&item::types::VOLTAGITE_BATTERY),
+    hardness: 2.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 2,
     palette_key: Some("voltagite"),
-    light_emission: |block| block.attribute_value(0).expect_u8(),
+    light_emission: |block| scale_light(Vector([10, 15, 4]), block.attribute_value(0).expect_u8()),
+    // Each level of charge (up to 8) supplies roughly two levels of power
+    power_output: |block| (block.attribute_value(0).expect_u8() as u32 * MAX_POWER as u32 / 8) as u8,
     connects_to: connects_to_electricity,
     right_click: |target_block, _, _| {
         let mut block = target_block.clone();
@@ -550,8 +941,23 @@ pub static VOLTAGITE_BATTERY: BlockType = BlockType {
 pub static VOLTAGITE_BLOCK: BlockType = BlockType {
     name: "voltagite_block",
     item_type: Some(&item::types::VOLTAGITE_BLOCK),
+    hardness: 5.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    required_tier: 3,
     palette_key: Some("voltagite"),
-    light_emission: light_emission_5,
+    light_emission: |_| Vector([4, 5, 1]),
     connects_to: connects_to_same_type,
     ..DEFAULTS
 };
+pub static WATER: BlockType = BlockType {
+    name: "water",
+    attributes: &[
+        ("level", AttributeType::U8(MAX_LIQUID_LEVEL)),
+    ],
+    colliders: colliders_none,
+    hardness: 0.0,
+    palette_key: Some("water"),
+    is_full_block: full_block_never,
+    light_opacity: 1,
+    ..DEFAULTS
+};
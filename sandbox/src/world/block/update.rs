@@ -0,0 +1,91 @@
+use std::hash::{Hash, Hasher};
+use innovus::tools::Vector;
+use innovus::tools::phys::Physics;
+use crate::tools::noise::{SimpleHasher, SimpleRandom};
+use super::*;
+
+/// A block reacting to a change next to it, a tick it scheduled, or a random tick. The block's
+/// hooks can look at its surroundings and request changes, which are applied once the hook returns.
+pub struct BlockUpdate<'a> {
+    chunk_map: &'a ChunkMap,
+    position: Vector<i64, 2>,
+    random: SimpleRandom,
+    changes: Vec<(Vector<i64, 2>, Block)>,
+    tick_delay: Option<u64>,
+}
+
+impl<'a> BlockUpdate<'a> {
+    /// Create an update whose randomness is determined by the position and current tick.
+    pub fn new(chunk_map: &'a ChunkMap, position: Vector<i64, 2>) -> Self {
+        let mut hasher = SimpleHasher::with_seed(chunk_map.current_tick());
+        position.x().hash(&mut hasher);
+        position.y().hash(&mut hasher);
+        Self::with_random(chunk_map, position, SimpleRandom::with_seed(hasher.finish()))
+    }
+
+    pub fn with_random(chunk_map: &'a ChunkMap, position: Vector<i64, 2>, random: SimpleRandom) -> Self {
+        Self {
+            chunk_map,
+            position,
+            random,
+            changes: Vec::new(),
+            tick_delay: None,
+        }
+    }
+
+    pub fn position(&self) -> Vector<i64, 2> {
+        self.position
+    }
+
+    pub fn current_tick(&self) -> u64 {
+        self.chunk_map.current_tick()
+    }
+
+    pub fn random(&mut self) -> &mut SimpleRandom {
+        &mut self.random
+    }
+
+    /// Look at a block relative to the one being updated. Returns `None` if its chunk isn't loaded.
+    pub fn with_block_at_offset<T>(&self, offset: Vector<i64, 2>, f: impl FnOnce(&Block) -> T) -> Option<T> {
+        self.chunk_map.with_block_at(self.position + offset, f)
+    }
+
+    /// Like [`Self::with_block_at_offset`], but with access to the slot's lighting as well.
+    pub fn with_slot_at_offset<T>(&self, offset: Vector<i64, 2>, f: impl FnOnce(&BlockSlot) -> T) -> Option<T> {
+        self.chunk_map.with_block_slot_at(self.position + offset, f)
+    }
+
+    /// Replace the block being updated once the hook returns.
+    pub fn replace(&mut self, block: Block) {
+        self.set_block_at_offset(Vector::zero(), block);
+    }
+
+    /// Set a block relative to the one being updated once the hook returns.
+    pub fn set_block_at_offset(&mut self, offset: Vector<i64, 2>, block: Block) {
+        self.changes.push((self.position + offset, block));
+    }
+
+    /// Schedule a tick for this position `delay` ticks from now. If several are requested, the
+    /// earliest one is kept.
+    pub fn schedule_tick(&mut self, delay: u64) {
+        self.tick_delay = Some(self.tick_delay.map_or(delay, |current| current.min(delay)));
+    }
+
+    pub fn apply(self, physics: &mut Physics) {
+        for (position, block) in self.changes {
+            self.chunk_map.set_block_at(position, block, physics);
+        }
+        if let Some(delay) = self.tick_delay {
+            self.chunk_map.schedule_tick(self.position, delay);
+        }
+    }
+}
+
+/// A tick requested by the block at (`x`, `y`) in a chunk, which runs once the world reaches
+/// `due_tick` while the chunk is being simulated.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ScheduledTick {
+    pub x: usize,
+    pub y: usize,
+    pub due_tick: u64,
+}
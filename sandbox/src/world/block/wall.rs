@@ -0,0 +1,155 @@
+use crate::world::item::{self, ItemType, ToolClass};
+
+/// A type of background wall. Walls sit behind the block in each slot, have no collision, and
+/// don't affect block light, but they keep sky light from shining straight through.
+pub struct WallType {
+    name: &'static str,
+    item_type: Option<&'static ItemType>,
+    hardness: f32,
+    preferred_tool: Option<ToolClass>,
+    palette_key: Option<&'static str>,
+    light_opacity: u8,
+}
+
+impl WallType {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn item_type(&self) -> Option<&'static ItemType> {
+        self.item_type
+    }
+
+    pub fn hardness(&self) -> f32 {
+        self.hardness
+    }
+
+    /// The kind of tool which mines this wall faster, if any.
+    pub fn preferred_tool(&self) -> Option<ToolClass> {
+        self.preferred_tool
+    }
+
+    /// How many seconds it takes to mine this wall with the given item. Unlike blocks, walls can
+    /// be harvested with anything.
+    pub fn mining_time(&self, tool: &ItemType) -> f32 {
+        self.hardness / tool.mining_speed(self.preferred_tool)
+    }
+
+    pub fn palette_key(&self) -> Option<&'static str> {
+        self.palette_key
+    }
+
+    /// How much sky light is lost when passing into a slot with this wall. Walls which lose more
+    /// than one level also cast shadows from the sky.
+    pub fn light_opacity(&self) -> u8 {
+        self.light_opacity
+    }
+
+    pub fn blocks_sky_light(&self) -> bool {
+        self.light_opacity > 1
+    }
+
+    pub fn is_air(&self) -> bool {
+        self == &AIR
+    }
+}
+
+pub fn get_wall_type(name: &str) -> Option<&'static WallType> {
+    WALL_TYPES
+        .iter()
+        .copied()
+        .find(|wall_type| wall_type.name() == name)
+}
+
+impl PartialEq for WallType {
+    fn eq(&self, other: &Self) -> bool {
+        // Comparing pointers is sufficient; only the static WallType objects should be used.
+        std::ptr::eq(self, other)
+    }
+}
+
+impl std::fmt::Display for WallType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl std::fmt::Debug for WallType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WallType({})", self.name)
+    }
+}
+
+const DEFAULTS: WallType = WallType {
+    name: "invalid",
+    item_type: None,
+    hardness: 1.0,
+    preferred_tool: None,
+    palette_key: None,
+    light_opacity: 2,
+};
+
+pub const WALL_TYPES: &[&WallType] = &[
+    &AIR,
+    &COBBLES_WALL,
+    &DIRT_WALL,
+    &OAK_WALL,
+    &SANDSTONE_WALL,
+    &SLATE_WALL,
+    &STONE_WALL,
+];
+
+pub static AIR: WallType = WallType {
+    name: "air",
+    hardness: 0.0,
+    light_opacity: 0,
+    ..DEFAULTS
+};
+pub static COBBLES_WALL: WallType = WallType {
+    name: "cobbles_wall",
+    item_type: Some(&item::types::COBBLES_WALL),
+    hardness: 2.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    palette_key: Some("stone"),
+    ..DEFAULTS
+};
+pub static DIRT_WALL: WallType = WallType {
+    name: "dirt_wall",
+    item_type: Some(&item::types::DIRT_WALL),
+    hardness: 0.75,
+    preferred_tool: Some(ToolClass::Shovel),
+    palette_key: Some("dirt"),
+    ..DEFAULTS
+};
+pub static OAK_WALL: WallType = WallType {
+    name: "oak_wall",
+    item_type: Some(&item::types::OAK_WALL),
+    hardness: 2.0,
+    preferred_tool: Some(ToolClass::Axe),
+    palette_key: Some("wood_oak"),
+    ..DEFAULTS
+};
+pub static SANDSTONE_WALL: WallType = WallType {
+    name: "sandstone_wall",
+    item_type: Some(&item::types::SANDSTONE_WALL),
+    hardness: 1.5,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    palette_key: Some("sand"),
+    ..DEFAULTS
+};
+pub static SLATE_WALL: WallType = WallType {
+    name: "slate_wall",
+    item_type: Some(&item::types::SLATE_WALL),
+    hardness: 2.5,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    palette_key: Some("slate"),
+    ..DEFAULTS
+};
+pub static STONE_WALL: WallType = WallType {
+    name: "stone_wall",
+    item_type: Some(&item::types::STONE_WALL),
+    hardness: 2.0,
+    preferred_tool: Some(ToolClass::Pickaxe),
+    palette_key: Some("stone"),
+    ..DEFAULTS
+};
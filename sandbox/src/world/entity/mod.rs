@@ -1,9 +1,10 @@
-use innovus::tools::phys::Physics;
+use innovus::tools::phys::{ColliderHandle, Physics};
 use crate::tools::*;
 use crate::tools::asset::AssetPool;
 use crate::tools::input::InputState;
 use crate::world::block::ChunkMap;
 use crate::world::entity::render::EntityRenderer;
+use crate::world::item::Item;
 use crate::world::particle::ParticleManager;
 
 pub mod movement;
@@ -17,6 +18,48 @@ pub trait Entity {
 
     fn position(&self) -> Vector<f32, 2>;
 
+    /// The position to render the entity at, which lies between its positions after the two most
+    /// recent physics steps. This is the same as the position by default.
+    fn interpolated_position(&self) -> Vector<f32, 2> {
+        self.position()
+    }
+
+    /// Whether the entity has finished existing and should be removed from the world.
+    fn is_removed(&self) -> bool {
+        false
+    }
+
+    /// The item stack carried by this entity, if it is a dropped item which can be picked up.
+    fn dropped_item(&self) -> Option<&Item> {
+        // Not a dropped item by default
+        None
+    }
+
+    /// Replace the item stack carried by this entity, if it is a dropped item. An empty stack
+    /// means the item was picked up or merged into another stack.
+    fn set_dropped_item(&mut self, item: Item) {
+        // Do nothing by default
+        let _ = item;
+    }
+
+    /// Take the items this entity has left behind since the last call, each paired with the
+    /// block position to drop it from.
+    fn take_drops(&mut self) -> Vec<(Vector<i64, 2>, Item)> {
+        // Nothing to drop by default
+        Vec::new()
+    }
+
+    fn collider(&self) -> Option<&ColliderHandle> {
+        // No collider by default
+        None
+    }
+
+    /// A sensor which moves along with this entity, detecting whatever is around it.
+    fn sensor(&self) -> Option<&ColliderHandle> {
+        // No sensor by default
+        None
+    }
+
     fn attach_collision(&mut self, physics: &mut Physics) {
         // Do nothing by default
         let _ = physics;
@@ -37,13 +80,35 @@ pub trait Entity {
         let _ = renderer;
     }
 
-    fn update(
+    /// Advance the entity by one fixed-length step, just before the physics simulation is
+    /// stepped. Anything affecting movement belongs here so that it doesn't depend on frame rate.
+    fn fixed_update(
         &mut self,
         dt: f32,
         inputs: &InputState,
         physics: &mut Physics,
+        chunks: &mut ChunkMap,
+    ) {
+        // Do nothing by default
+        let _ = (dt, inputs, physics, chunks);
+    }
+
+    /// Update the entity once per frame. `alpha` is how far the frame lies between the two most
+    /// recent physics steps, from 0 to 1, and can be used to interpolate positions.
+    fn update(
+        &mut self,
+        dt: f32,
+        alpha: f32,
+        physics: &mut Physics,
         renderer: &mut EntityRenderer,
         chunks: &mut ChunkMap,
         particles: &mut ParticleManager,
     );
+
+    /// Draw any parts of the entity which use block textures, since the entity renderer only
+    /// handles entity textures.
+    fn render_blocks(&mut self, assets: &AssetPool, chunks: &ChunkMap) {
+        // Do nothing by default
+        let _ = (assets, chunks);
+    }
 }
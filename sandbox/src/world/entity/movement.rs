@@ -2,6 +2,7 @@ pub const DEFAULT_TERMINAL_VELOCITY: f32 = 128.0;
 
 pub const DEFAULT_GRAVITY_ACCELERATION: f32 = 32.0;
 pub const DEFAULT_FRICTION_DECELERATION: f32 = 16.0;
+pub const DEFAULT_SWIM_SPEED: f32 = 3.0;
 
 pub fn apply_gravity(velocity: f32, dt: f32, acceleration: f32, terminal_velocity: f32) -> f32 {
     if velocity - acceleration * dt < -terminal_velocity {
@@ -22,3 +23,7 @@ pub fn apply_friction(mut velocity: f32, dt: f32, deceleration: f32) -> f32 {
     }
     velocity
 }
+
+pub fn apply_drag(velocity: f32, dt: f32, drag: f32) -> f32 {
+    velocity * (1.0 - drag * dt).max(0.0)
+}
@@ -0,0 +1,195 @@
+use innovus::gfx::MeshRenderer;
+use innovus::tools::phys::{Collider, ColliderHandle, Physics};
+use crate::tools::*;
+use crate::tools::asset::AssetPool;
+use crate::tools::input::InputState;
+use crate::tools::noise::SimpleRandom;
+use crate::world::block::{Block, BlockVertex, ChunkMap, CHUNK_SIZE, QUADRANT_OFFSETS, QUADRANT_VERTEX_OFFSETS};
+use crate::world::entity::{movement, Entity};
+use crate::world::entity::render::EntityRenderer;
+use crate::world::item::Item;
+use crate::world::particle::{pseudo_random, ParticleManager};
+use crate::world::{ENTITY_LAYER, TERRAIN_LAYER};
+use super::*;
+
+/// A block which has lost its support and is falling. Once it lands, it places itself back into
+/// the world as a block, or breaks into its drops if the spot is already taken.
+pub struct FallingBlock {
+    uuid: Uuid,
+    block: Block,
+    position: Vector<f32, 2>,
+    previous_position: Vector<f32, 2>,
+    interpolated_position: Vector<f32, 2>,
+    collider: Option<ColliderHandle>,
+    mesh: MeshRenderer<BlockVertex>,
+    drops: Vec<(Vector<i64, 2>, Item)>,
+    removed: bool,
+}
+
+impl FallingBlock {
+    pub fn new(uuid: Uuid, block: Block, block_position: Vector<i64, 2>) -> Self {
+        let position = block_position.map(|x| x as f32);
+        Self {
+            uuid,
+            block,
+            position,
+            previous_position: position,
+            interpolated_position: position,
+            collider: None,
+            mesh: MeshRenderer::create(),
+            drops: Vec::new(),
+            removed: false,
+        }
+    }
+
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+
+    fn land(&mut self, physics: &mut Physics, chunks: &mut ChunkMap) {
+        if let Some(collider) = self.collider.take() {
+            physics.remove_collider(collider);
+        }
+        let target = (self.position + Vector::filled(0.5)).map(|x| x.floor() as i64);
+        if chunks.with_block_at(target, Block::is_replaceable) == Some(true) {
+            chunks.set_block_at(target, self.block.clone(), physics);
+        }
+        else {
+            // Something else already took the spot, so the block breaks instead
+            let mut random = SimpleRandom::with_seed(pseudo_random());
+            self.drops.extend(self.block.block_type().roll_drops(&mut random).into_iter().map(|item| (target, item)));
+        }
+        self.removed = true;
+    }
+}
+
+impl Entity for FallingBlock {
+    fn entity_type(&self) -> &'static str {
+        "falling_block"
+    }
+
+    fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn position(&self) -> Vector<f32, 2> {
+        self.position
+    }
+
+    fn interpolated_position(&self) -> Vector<f32, 2> {
+        self.interpolated_position
+    }
+
+    fn is_removed(&self) -> bool {
+        self.removed
+    }
+
+    fn take_drops(&mut self) -> Vec<(Vector<i64, 2>, Item)> {
+        std::mem::take(&mut self.drops)
+    }
+
+    fn collider(&self) -> Option<&ColliderHandle> {
+        self.collider.as_ref()
+    }
+
+    fn attach_collision(&mut self, physics: &mut Physics) {
+        // Slightly narrower than a block so it can fall down a one-block-wide gap
+        self.collider = Some(physics.add_collider(Collider::new(
+            Rectangle::from_span(
+                Vector([self.position.x() + pixels(1) / 4.0, self.position.y()]),
+                Vector([1.0 - pixels(1) / 2.0, 1.0]),
+            ),
+            Vector::zero(),
+        )
+            .with_layers(ENTITY_LAYER, TERRAIN_LAYER)
+            .with_material(0.0, self.block.block_type().friction())));
+    }
+
+    fn detach_collision(&mut self, physics: &mut Physics) {
+        if let Some(collider) = self.collider.take() {
+            physics.remove_collider(collider);
+        }
+    }
+
+    fn fixed_update(
+        &mut self,
+        dt: f32,
+        inputs: &InputState,
+        physics: &mut Physics,
+        chunks: &mut ChunkMap,
+    ) {
+        let _ = inputs;
+
+        let Some(handle) = &self.collider else {
+            return;
+        };
+        let collider = physics.get_collider_mut(handle).unwrap();
+        self.previous_position = Vector([collider.rectangle.min.x() - pixels(1) / 4.0, collider.rectangle.min.y()]);
+        if collider.hit_bottom && collider.velocity.y() >= 0.0 {
+            self.position = self.previous_position;
+            self.land(physics, chunks);
+            return;
+        }
+        collider.velocity.set_y(movement::apply_gravity(
+            collider.velocity.y(),
+            dt,
+            movement::DEFAULT_GRAVITY_ACCELERATION,
+            movement::DEFAULT_TERMINAL_VELOCITY,
+        ));
+    }
+
+    fn update(
+        &mut self,
+        dt: f32,
+        alpha: f32,
+        physics: &mut Physics,
+        renderer: &mut EntityRenderer,
+        chunks: &mut ChunkMap,
+        particles: &mut ParticleManager,
+    ) {
+        let _ = (dt, renderer, chunks, particles);
+
+        if let Some(collider) = &self.collider {
+            // Catch up with the most recent physics step
+            let collider = physics.get_collider(collider).unwrap();
+            self.position = Vector([collider.rectangle.min.x() - pixels(1) / 4.0, collider.rectangle.min.y()]);
+        }
+        self.interpolated_position = self.previous_position.lerp(self.position, alpha);
+    }
+
+    fn render_blocks(&mut self, assets: &AssetPool, chunks: &ChunkMap) {
+        let center = self.interpolated_position + Vector::filled(0.5);
+        let chunk_location = center.map(|x| x.div_euclid(CHUNK_SIZE as f32) as i64);
+        let block_x = center.x().rem_euclid(CHUNK_SIZE as f32) as usize;
+        let block_y = center.y().rem_euclid(CHUNK_SIZE as f32) as usize;
+        let vertex_light = chunks
+            .get(chunk_location)
+            .map_or(Vector::one(), |chunk| chunk.block_slot_at(block_x, block_y).vertex_light());
+
+        let Some(image) = assets.get_block_image(&self.block, chunk_location, block_x, block_y) else {
+            return;
+        };
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        for (quadrant_offset, atlas_offset) in std::iter::zip(QUADRANT_OFFSETS, image.get_isolated_atlas_offsets()) {
+            let index = vertices.len() as u32;
+            faces.push([index, index + 1, index + 2]);
+            faces.push([index + 2, index + 3, index]);
+            for vertex_offset in QUADRANT_VERTEX_OFFSETS {
+                let total_offset = quadrant_offset + vertex_offset;
+                vertices.push(BlockVertex::new(
+                    (self.interpolated_position + total_offset).with_z(0.0),
+                    Some(vertex_light),
+                    Some(Vector([
+                        atlas_offset.x() as f32 + total_offset.x() * image.size() as f32,
+                        atlas_offset.y() as f32 + (1.0 - total_offset.y()) * image.size() as f32,
+                    ])),
+                ));
+            }
+        }
+
+        self.mesh.clear();
+        self.mesh.add(&vertices, &faces);
+        self.mesh.render();
+    }
+}
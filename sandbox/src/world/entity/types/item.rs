@@ -0,0 +1,211 @@
+use innovus::tools::phys::{Collider, ColliderHandle, Physics};
+use crate::tools::*;
+use crate::tools::asset::AssetPool;
+use crate::tools::input::InputState;
+use crate::world::block::ChunkMap;
+use crate::world::entity::{movement, Entity};
+use crate::world::entity::render::{EntityPiece, EntityPieceHandle, EntityRenderer};
+use crate::world::item::Item;
+use crate::world::particle::ParticleManager;
+use crate::world::{ENTITY_LAYER, TERRAIN_LAYER};
+
+/// How many seconds a dropped item lies around before it disappears.
+pub const DESPAWN_SECONDS: f32 = 300.0;
+
+/// A stack of items lying in the world, such as the drops from a broken block. The world takes
+/// care of merging it with the stacks its sensor detects and letting the player pick it up.
+pub struct ItemEntity {
+    uuid: Uuid,
+    item: Item,
+    position: Vector<f32, 2>,
+    previous_position: Vector<f32, 2>,
+    interpolated_position: Vector<f32, 2>,
+    initial_velocity: Vector<f32, 2>,
+    age: f32,
+    collider: Option<ColliderHandle>,
+    sensor: Option<ColliderHandle>,
+    appearance: Option<EntityPieceHandle>,
+    removed: bool,
+}
+
+impl ItemEntity {
+    /// The width and height of the entity, which is half of a block.
+    pub const SIZE: f32 = 0.5;
+    /// How far past the edges of the entity its sensor reaches, so that stacks lying next to
+    /// each other are merged even if they aren't quite touching.
+    pub const SENSOR_MARGIN: f32 = 0.25;
+
+    /// Create a dropped item whose bottom center is at `position`.
+    pub fn new(uuid: Uuid, item: Item, position: Vector<f32, 2>, velocity: Vector<f32, 2>) -> Self {
+        Self {
+            uuid,
+            item,
+            position,
+            previous_position: position,
+            interpolated_position: position,
+            initial_velocity: velocity,
+            age: 0.0,
+            collider: None,
+            sensor: None,
+            appearance: None,
+            removed: false,
+        }
+    }
+
+    pub fn item(&self) -> &Item {
+        &self.item
+    }
+
+    /// How many seconds this item has been lying around.
+    pub fn age(&self) -> f32 {
+        self.age
+    }
+
+    fn position_from_collider(collider: &Collider) -> Vector<f32, 2> {
+        Vector([collider.rectangle.center().x(), collider.rectangle.min.y()])
+    }
+
+    fn sensor_rectangle(position: Vector<f32, 2>) -> Rectangle<f32> {
+        Rectangle::from_span(
+            Vector([position.x() - Self::SIZE / 2.0, position.y()]) - Vector::filled(Self::SENSOR_MARGIN),
+            Vector::filled(Self::SIZE + 2.0 * Self::SENSOR_MARGIN),
+        )
+    }
+}
+
+impl Entity for ItemEntity {
+    fn entity_type(&self) -> &'static str {
+        "item"
+    }
+
+    fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn position(&self) -> Vector<f32, 2> {
+        self.position
+    }
+
+    fn interpolated_position(&self) -> Vector<f32, 2> {
+        self.interpolated_position
+    }
+
+    fn is_removed(&self) -> bool {
+        self.removed
+    }
+
+    fn dropped_item(&self) -> Option<&Item> {
+        Some(&self.item)
+    }
+
+    fn set_dropped_item(&mut self, item: Item) {
+        self.removed = item.is_empty();
+        self.item = item;
+    }
+
+    fn collider(&self) -> Option<&ColliderHandle> {
+        self.collider.as_ref()
+    }
+
+    fn sensor(&self) -> Option<&ColliderHandle> {
+        self.sensor.as_ref()
+    }
+
+    fn attach_collision(&mut self, physics: &mut Physics) {
+        self.collider = Some(physics.add_collider(Collider::new(
+            Rectangle::from_span(
+                Vector([self.position.x() - Self::SIZE / 2.0, self.position.y()]),
+                Vector::filled(Self::SIZE),
+            ),
+            self.initial_velocity,
+        )
+            .with_layers(ENTITY_LAYER, TERRAIN_LAYER)
+            .with_material(0.2, 0.8)));
+
+        // The sensor only detects other entities, and is moved along with the item every step
+        self.sensor = Some(physics.add_collider(
+            Collider::new_sensor(Self::sensor_rectangle(self.position))
+                .with_layers(0, ENTITY_LAYER),
+        ));
+    }
+
+    fn attach_appearance(&mut self, assets: &mut AssetPool, renderer: &mut EntityRenderer) {
+        self.detach_appearance(renderer);
+
+        let Ok(mut image) = assets.get_item_entity_image(self.item.item_type()) else {
+            return;
+        };
+        image.world_offset = Rectangle::from_span(
+            Vector([-Self::SIZE / 2.0, 0.0]),
+            Vector::filled(Self::SIZE),
+        );
+        self.appearance = Some(renderer.add_piece(EntityPiece::new(self.interpolated_position, image)));
+    }
+
+    fn detach_collision(&mut self, physics: &mut Physics) {
+        if let Some(collider) = self.collider.take() {
+            physics.remove_collider(collider);
+        }
+        if let Some(sensor) = self.sensor.take() {
+            physics.remove_collider(sensor);
+        }
+    }
+
+    fn detach_appearance(&mut self, renderer: &mut EntityRenderer) {
+        if let Some(appearance) = self.appearance.take() {
+            renderer.remove_piece(appearance);
+        }
+    }
+
+    fn fixed_update(
+        &mut self,
+        dt: f32,
+        inputs: &InputState,
+        physics: &mut Physics,
+        chunks: &mut ChunkMap,
+    ) {
+        let _ = (inputs, chunks);
+
+        let Some(handle) = &self.collider else {
+            return;
+        };
+        let collider = physics.get_collider_mut(handle).unwrap();
+        self.previous_position = Self::position_from_collider(collider);
+        collider.velocity.set_y(movement::apply_gravity(
+            collider.velocity.y(),
+            dt,
+            movement::DEFAULT_GRAVITY_ACCELERATION,
+            movement::DEFAULT_TERMINAL_VELOCITY,
+        ));
+        if let Some(sensor) = self.sensor.as_ref().and_then(|sensor| physics.get_collider_mut(sensor)) {
+            sensor.rectangle = Self::sensor_rectangle(self.previous_position);
+        }
+    }
+
+    fn update(
+        &mut self,
+        dt: f32,
+        alpha: f32,
+        physics: &mut Physics,
+        renderer: &mut EntityRenderer,
+        chunks: &mut ChunkMap,
+        particles: &mut ParticleManager,
+    ) {
+        let _ = (chunks, particles);
+
+        self.age += dt;
+        if self.age >= DESPAWN_SECONDS {
+            self.removed = true;
+        }
+
+        if let Some(collider) = &self.collider {
+            // Catch up with the most recent physics step
+            self.position = Self::position_from_collider(physics.get_collider(collider).unwrap());
+        }
+        self.interpolated_position = self.previous_position.lerp(self.position, alpha);
+
+        if let Some(appearance) = &self.appearance {
+            renderer.get_piece_mut(appearance).set_world_position(self.interpolated_position);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+pub mod falling_block;
+pub mod item;
 pub mod player;
 
 /// Convert from pixels to blocks. For example, `pixels(8)` is 0.5 (half a block).
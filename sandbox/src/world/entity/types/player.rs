@@ -1,13 +1,15 @@
+use json::JsonValue;
 use innovus::tools::phys::Physics;
 use crate::tools::*;
 use crate::tools::asset::AssetPool;
 use crate::tools::asset::entity::EntityImage;
 use crate::tools::input::{InputState, Key};
-use crate::world::block::ChunkMap;
+use crate::world::block::{liquid_submersion, ChunkMap};
 use crate::world::entity::{movement, Entity};
 use crate::world::entity::render::{EntityPiece, EntityPieceHandle, EntityRenderer};
-use crate::world::item::Item;
+use crate::world::item::{Inventory, Item, Recipe, SlotClick};
 use crate::world::particle::ParticleManager;
+use crate::world::{ENTITY_LAYER, TERRAIN_LAYER};
 use super::*;
 
 struct PlayerAppearance {
@@ -26,19 +28,49 @@ pub enum PlayerMode {
     Spectating,
 }
 
+impl PlayerMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Spectating => "spectating",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "normal" => Some(Self::Normal),
+            "spectating" => Some(Self::Spectating),
+            _ => None,
+        }
+    }
+}
+
+/// The number of slots in the player's inventory.
+pub const INVENTORY_SIZE: usize = 40;
+/// The number of slots in the hotbar, which shows the first row of the inventory.
+pub const HOTBAR_SIZE: usize = 10;
+/// The number of columns in the player's square crafting grid.
+pub const CRAFTING_GRID_WIDTH: usize = 3;
+
 const JUMP_COOLDOWN_SECONDS: f32 = 0.3;
 const COYOTE_TIME_SECONDS: f32 = 0.1;
 
 pub struct Player {
     uuid: Uuid,
     position: Vector<f32, 2>,
+    previous_position: Vector<f32, 2>,
+    interpolated_position: Vector<f32, 2>,
     velocity: Vector<f32, 2>,
     collider: Option<phys::ColliderHandle>,
     appearance: Option<PlayerAppearance>,
     name: String,
     mode: PlayerMode,
-    held_item: Item,
+    inventory: Inventory,
+    selected_slot: usize,
+    cursor_item: Item,
+    crafting_grid: Inventory,
     crouching: bool,
+    touching_ground: bool,
     spawn_point: Option<Vector<i64, 2>>,
     movement_accel: f32,
     jump_speed: f32,
@@ -51,13 +83,19 @@ impl Player {
         Self {
             uuid,
             position,
+            previous_position: position,
+            interpolated_position: position,
             velocity: Vector::zero(),
             collider: None,
             appearance: None,
             name: name.unwrap_or_else(|| "(anonymous)".into()),
             mode,
-            held_item: Item::new(&crate::world::item::types::AIR, 0),
+            inventory: Inventory::new(INVENTORY_SIZE),
+            selected_slot: 0,
+            cursor_item: Item::default(),
+            crafting_grid: Inventory::new(CRAFTING_GRID_WIDTH * CRAFTING_GRID_WIDTH),
             crouching: false,
+            touching_ground: true,
             spawn_point: None,
             movement_accel: 32.0,
             jump_speed: 15.0,
@@ -66,6 +104,10 @@ impl Player {
         }
     }
 
+    pub fn eye_position(&self) -> Vector<f32, 2> {
+        self.position + Vector([0.0, pixels(22)])
+    }
+
     pub fn velocity(&self) -> Vector<f32, 2> {
         self.velocity
     }
@@ -82,12 +124,177 @@ impl Player {
         self.mode = mode;
     }
 
+    pub fn inventory(&self) -> &Inventory {
+        &self.inventory
+    }
+
+    pub fn inventory_mut(&mut self) -> &mut Inventory {
+        &mut self.inventory
+    }
+
+    /// The index of the hotbar slot which holds the item the player is using.
+    pub fn selected_slot(&self) -> usize {
+        self.selected_slot
+    }
+
+    pub fn set_selected_slot(&mut self, slot: usize) {
+        self.selected_slot = slot % HOTBAR_SIZE;
+    }
+
     pub fn held_item(&self) -> &Item {
-        &self.held_item
+        self.inventory.item(self.selected_slot)
     }
 
     pub fn set_held_item(&mut self, item: Item) {
-        self.held_item = item;
+        self.inventory.set_item(self.selected_slot, item);
+    }
+
+    /// The stack picked up with the cursor in the inventory screen, if any.
+    pub fn cursor_item(&self) -> &Item {
+        &self.cursor_item
+    }
+
+    pub fn click_inventory_slot(&mut self, index: usize, click: SlotClick) {
+        self.inventory.click_slot(index, &mut self.cursor_item, click);
+    }
+
+    /// Move the stack in an inventory slot between the hotbar and the rest of the inventory.
+    pub fn transfer_inventory_slot(&mut self, index: usize) {
+        let range = if index < HOTBAR_SIZE {
+            HOTBAR_SIZE..self.inventory.slot_count()
+        }
+        else {
+            0..HOTBAR_SIZE
+        };
+        self.inventory.transfer(index, range);
+    }
+
+    /// Put the stack held by the cursor back into the inventory, such as when the inventory screen
+    /// is closed. Anything that doesn't fit stays on the cursor.
+    pub fn return_cursor_item(&mut self) {
+        let item = std::mem::take(&mut self.cursor_item);
+        self.cursor_item = self.inventory.insert(item);
+    }
+
+    pub fn crafting_grid(&self) -> &Inventory {
+        &self.crafting_grid
+    }
+
+    pub fn click_crafting_slot(&mut self, index: usize, click: SlotClick) {
+        self.crafting_grid.click_slot(index, &mut self.cursor_item, click);
+    }
+
+    /// Move the stack in a crafting grid slot into the inventory.
+    pub fn transfer_crafting_slot(&mut self, index: usize) {
+        let item = self.crafting_grid.take_item(index);
+        let leftover = self.inventory.insert(item);
+        self.crafting_grid.set_item(index, leftover);
+    }
+
+    /// Put everything in the crafting grid back into the inventory. Anything that doesn't fit
+    /// stays in the grid.
+    pub fn return_crafting_items(&mut self) {
+        for index in 0..self.crafting_grid.slot_count() {
+            self.transfer_crafting_slot(index);
+        }
+    }
+
+    /// Craft a recipe once using the crafting grid, putting the result on the cursor. Returns
+    /// whether anything was crafted, which fails if the result can't stack onto the cursor item.
+    pub fn craft_onto_cursor(&mut self, recipe: &Recipe) -> bool {
+        let result = recipe.result();
+        let fits = self.cursor_item.is_empty() || (
+            self.cursor_item.item_type() == result.item_type()
+                && self.cursor_item.count() + result.count() <= result.item_type().max_count()
+        );
+        if !fits {
+            return false;
+        }
+        let Some(result) = recipe.craft(&mut self.crafting_grid, CRAFTING_GRID_WIDTH) else {
+            return false;
+        };
+        self.cursor_item = result.with_count(self.cursor_item.count() + result.count());
+        true
+    }
+
+    /// Craft a recipe as many times as possible using the crafting grid, putting the results into
+    /// the inventory. Stops once the grid runs out of ingredients or the results stop fitting.
+    pub fn craft_into_inventory(&mut self, recipe: &Recipe) {
+        while recipe.match_grid(self.crafting_grid.slots(), CRAFTING_GRID_WIDTH).is_some() {
+            let mut inventory = self.inventory.clone();
+            if !inventory.insert(recipe.result().clone()).is_empty() {
+                break;
+            }
+            self.inventory = inventory;
+            recipe.craft(&mut self.crafting_grid, CRAFTING_GRID_WIDTH);
+        }
+    }
+
+    pub fn spawn_point(&self) -> Option<Vector<i64, 2>> {
+        self.spawn_point
+    }
+
+    pub fn set_spawn_point(&mut self, spawn_point: Option<Vector<i64, 2>>) {
+        self.spawn_point = spawn_point;
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        json::object! {
+            uuid: self.uuid.to_string(),
+            name: self.name.as_str(),
+            position: [self.position.x(), self.position.y()],
+            mode: self.mode.name(),
+            inventory: self.inventory.to_json(),
+            selected_slot: self.selected_slot,
+            cursor_item: if self.cursor_item.is_empty() { JsonValue::Null } else { self.cursor_item.to_json() },
+            crafting_grid: self.crafting_grid.to_json(),
+            spawn_point: self.spawn_point.map(|Vector([x, y])| json::array![x, y]),
+        }
+    }
+
+    pub fn from_json(data: &JsonValue) -> Result<Self, String> {
+        let uuid = data["uuid"].as_str()
+            .and_then(|uuid| Uuid::parse_str(uuid).ok())
+            .ok_or_else(|| format!("invalid player UUID: {}", data["uuid"]))?;
+        let name = data["name"].as_str().map(String::from);
+        let position = match (data["position"][0].as_f32(), data["position"][1].as_f32()) {
+            (Some(x), Some(y)) => Vector([x, y]),
+            _ => return Err(format!("invalid player position: {}", data["position"])),
+        };
+        let mode = data["mode"].as_str()
+            .and_then(PlayerMode::from_name)
+            .ok_or_else(|| format!("invalid player mode: {}", data["mode"]))?;
+
+        let mut player = Self::new(uuid, position, name, mode);
+
+        if !data["inventory"].is_null() {
+            player.inventory.read_json(&data["inventory"])?;
+            if !data["selected_slot"].is_null() {
+                let selected_slot = data["selected_slot"].as_usize()
+                    .ok_or_else(|| format!("invalid selected slot: {}", data["selected_slot"]))?;
+                player.set_selected_slot(selected_slot);
+            }
+            if !data["crafting_grid"].is_null() {
+                player.crafting_grid.read_json(&data["crafting_grid"])?;
+            }
+            if !data["cursor_item"].is_null() {
+                player.cursor_item = Item::from_json(&data["cursor_item"])?;
+                player.return_cursor_item();
+            }
+        }
+        else if !data["held_item"].is_null() {
+            // Players saved before inventories existed only had a held item
+            player.set_held_item(Item::from_json(&data["held_item"])?);
+        }
+
+        if !data["spawn_point"].is_null() {
+            player.spawn_point = match (data["spawn_point"][0].as_i64(), data["spawn_point"][1].as_i64()) {
+                (Some(x), Some(y)) => Some(Vector([x, y])),
+                _ => return Err(format!("invalid player spawn point: {}", data["spawn_point"])),
+            };
+        }
+
+        Ok(player)
     }
 
     pub fn respawn(&mut self, physics: &mut Physics) {
@@ -116,6 +323,14 @@ impl Entity for Player {
         self.position
     }
 
+    fn interpolated_position(&self) -> Vector<f32, 2> {
+        self.interpolated_position
+    }
+
+    fn collider(&self) -> Option<&phys::ColliderHandle> {
+        self.collider.as_ref()
+    }
+
     fn attach_collision(&mut self, physics: &mut Physics) {
         self.collider = Some(physics.add_collider(phys::Collider::new(
             Rectangle::from_span(
@@ -123,7 +338,9 @@ impl Entity for Player {
                 Vector([pixels(10), pixels(26)]),
             ),
             Vector::zero(),
-        )));
+        )
+            .with_layers(ENTITY_LAYER, TERRAIN_LAYER | ENTITY_LAYER)
+            .with_material(0.0, 1.0)));
     }
 
     fn attach_appearance(&mut self, assets: &mut AssetPool, renderer: &mut EntityRenderer) {
@@ -163,22 +380,21 @@ impl Entity for Player {
         }
     }
 
-    fn update(
+    fn fixed_update(
         &mut self,
         dt: f32,
         inputs: &InputState,
         physics: &mut Physics,
-        renderer: &mut EntityRenderer,
         chunks: &mut ChunkMap,
-        particles: &mut ParticleManager,
     ) {
-        let _ = (chunks, particles);
-
-        let mut touching_ground = true;
+        self.touching_ground = true;
 
         if self.mode == PlayerMode::Spectating {
             const SPEED_LIMIT: f32 = 20.0;
 
+            // Remember where the player was at the end of the previous step for interpolation
+            self.previous_position = self.position;
+
             if inputs.key_is_held(Key::A) {
                 self.velocity.set_x((self.velocity.x() - self.movement_accel * dt).max(-SPEED_LIMIT));
             }
@@ -202,6 +418,7 @@ impl Entity for Player {
         }
         else if let Some(collider) = &self.collider {
             let collider = physics.get_collider_mut(collider).unwrap();
+            self.previous_position = Vector([collider.rectangle.min.x() + pixels(5), collider.rectangle.min.y()]);
             self.velocity = collider.velocity;
 
             if collider.hit_bottom {
@@ -211,7 +428,7 @@ impl Entity for Player {
                 self.coyote_time -= dt;
             }
             else {
-                touching_ground = false;
+                self.touching_ground = false;
             }
 
             let jump_held = inputs.key_is_held(Key::W) || inputs.key_is_held(Key::Space);
@@ -219,7 +436,7 @@ impl Entity for Player {
                 self.jump_cooldown = 0.0;
             }
             if self.jump_cooldown <= 0.0 {
-                if jump_held && touching_ground {
+                if jump_held && self.touching_ground {
                     collider.velocity.set_y(self.jump_speed);
                     self.jump_cooldown += JUMP_COOLDOWN_SECONDS;
                     self.coyote_time = 0.0;
@@ -239,7 +456,7 @@ impl Entity for Player {
                 collider.rectangle.max.set_y(collider.rectangle.min.y() + pixels(26));
             }
 
-            let speed_multiplier = if self.crouching && touching_ground {
+            let speed_multiplier = if self.crouching && self.touching_ground {
                 0.5
             } else {
                 1.0
@@ -258,25 +475,59 @@ impl Entity for Player {
                 movement::DEFAULT_GRAVITY_ACCELERATION,
                 movement::DEFAULT_TERMINAL_VELOCITY,
             ));
-            collider.velocity.set_x(movement::apply_friction(
-                collider.velocity.x(),
-                dt,
-                movement::DEFAULT_FRICTION_DECELERATION,
-            ));
+            if !collider.hit_bottom {
+                // Friction against the ground is handled by the physics engine,
+                // but there should still be some resistance while in the air
+                collider.velocity.set_x(movement::apply_friction(
+                    collider.velocity.x(),
+                    dt,
+                    movement::DEFAULT_FRICTION_DECELERATION,
+                ));
+            }
+
+            if let Some((fluid, submersion)) = liquid_submersion(chunks, collider.rectangle) {
+                // Liquids push the player up and slow them down in proportion to how submerged they are
+                collider.velocity.set_y(collider.velocity.y() + fluid.buoyancy() * submersion * dt);
+                let drag = fluid.drag() * submersion;
+                collider.velocity = collider.velocity.map(|x| movement::apply_drag(x, dt, drag));
+                if jump_held {
+                    collider.velocity.set_y(collider.velocity.y().max(movement::DEFAULT_SWIM_SPEED * submersion));
+                }
+            }
+        }
+    }
 
-            self.position.set_x(collider.rectangle.min.x() + pixels(5));
-            self.position.set_y(collider.rectangle.min.y());
+    fn update(
+        &mut self,
+        dt: f32,
+        alpha: f32,
+        physics: &mut Physics,
+        renderer: &mut EntityRenderer,
+        chunks: &mut ChunkMap,
+        particles: &mut ParticleManager,
+    ) {
+        let _ = (dt, chunks, particles);
+
+        if self.mode != PlayerMode::Spectating {
+            if let Some(collider) = &self.collider {
+                // Catch up with the most recent physics step
+                let collider = physics.get_collider(collider).unwrap();
+                self.velocity = collider.velocity;
+                self.position.set_x(collider.rectangle.min.x() + pixels(5));
+                self.position.set_y(collider.rectangle.min.y());
+            }
         }
+        self.interpolated_position = self.previous_position.lerp(self.position, alpha);
 
         if let Some(appearance) = &mut self.appearance {
             let body = renderer.get_piece_mut(&appearance.body);
-            body.set_world_position(self.position);
+            body.set_world_position(self.interpolated_position);
 
             if self.velocity.x() != 0.0 {
                 body.set_flip_x(self.velocity.x() < 0.0);
             }
 
-            if touching_ground {
+            if self.touching_ground {
                 if self.velocity.x() != 0.0 {
                     if self.crouching {
                         body.set_image(&appearance.crouch_walk_image);
@@ -6,6 +6,8 @@ use super::*;
 pub mod types;
 
 pub trait WorldGenerator {
+    fn generator_name(&self) -> &'static str;
+
     fn world_seed(&self) -> u64;
 
     fn generate_chunk(&self, chunk: &mut block::Chunk, chunk_map: &block::ChunkMap, physics: &mut phys::Physics) -> Vec<Box<dyn entity::Entity>>;
@@ -17,3 +19,11 @@ pub trait WorldGenerator {
         hasher.finish()
     }
 }
+
+pub fn create_generator(name: &str, world_seed: u64) -> Option<Box<dyn WorldGenerator>> {
+    match name {
+        "overworld" => Some(Box::new(types::OverworldGenerator::new(world_seed))),
+        "test" => Some(Box::new(types::TestWorldGenerator::new(world_seed))),
+        _ => None,
+    }
+}
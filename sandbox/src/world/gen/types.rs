@@ -3,6 +3,9 @@ use crate::tools::noise::{scramble, smooth_step, PerlinNoise1D, PerlinNoise2D};
 use crate::world::block::BlockCoord;
 use super::*;
 
+/// Terrain below this height is sandy.
+const SAND_MAX_HEIGHT: i64 = -20;
+
 #[derive(Debug)]
 pub struct OverworldGenerator {
     world_seed: u64,
@@ -47,6 +50,10 @@ impl OverworldGenerator {
 }
 
 impl WorldGenerator for OverworldGenerator {
+    fn generator_name(&self) -> &'static str {
+        "overworld"
+    }
+
     fn world_seed(&self) -> u64 {
         self.world_seed
     }
@@ -66,17 +73,35 @@ impl WorldGenerator for OverworldGenerator {
                 let block_y = i64::from(BlockCoord::new(chunk.location().y(), y));
                 let terrain_height = height_map[x];
 
+                // Low-lying terrain is covered in sand instead of dirt. Sand falls, so it always
+                // sits on a layer of sandstone, and caves never reach that close to the surface
+                let is_sandy = terrain_height < SAND_MAX_HEIGHT;
+
                 let block_type;
+                let mut wall_type = &block::wall::AIR;
                 if block_y > terrain_height {
                     block_type = &block::types::AIR;
                 }
+                else if is_sandy && terrain_height - block_y < 3 {
+                    block_type = &block::types::SAND;
+                    if block_y < terrain_height {
+                        wall_type = &block::wall::SANDSTONE_WALL;
+                    }
+                }
+                else if is_sandy && terrain_height - block_y <= 4 {
+                    block_type = &block::types::SANDSTONE;
+                    wall_type = &block::wall::SANDSTONE_WALL;
+                }
                 else if block_y == terrain_height {
                     block_type = &block::types::GRASSY_DIRT;
                 }
                 else if terrain_height - block_y <= 4 {
                     block_type = &block::types::DIRT;
+                    wall_type = &block::wall::DIRT_WALL;
                 }
                 else {
+                    // Caves are lined with walls, so digging into the ground never exposes the sky
+                    wall_type = &block::wall::STONE_WALL;
                     let small_offset = Vector([x as f32, y as f32]).div(CHUNK_SIZE as f32);
                     let small_caves_value = small_caves_cell.compute_value(small_offset, smooth_step);
                     let big_offset = big_offset_base + small_offset.mul(0.5);
@@ -89,6 +114,7 @@ impl WorldGenerator for OverworldGenerator {
 
                     if value < -0.1 {
                         block_type = &block::types::SLATE;
+                        wall_type = &block::wall::SLATE_WALL;
                     }
                     else if value < 0.2 {
                         block_type = &block::types::STONE;
@@ -100,6 +126,7 @@ impl WorldGenerator for OverworldGenerator {
 
                 let block = Block::new(block_type, Default::default());
                 chunk.set_block_at(x, y, block, chunk_map, physics);
+                chunk.set_wall_at(x, y, wall_type, chunk_map);
             }
         }
 
@@ -119,6 +146,10 @@ impl TestWorldGenerator {
 }
 
 impl WorldGenerator for TestWorldGenerator {
+    fn generator_name(&self) -> &'static str {
+        "test"
+    }
+
     fn world_seed(&self) -> u64 {
         self.seed
     }
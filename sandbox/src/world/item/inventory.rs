@@ -0,0 +1,190 @@
+use std::ops::Range;
+use json::JsonValue;
+use super::*;
+
+/// The ways an inventory slot can be clicked while an item stack may be held by the cursor.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SlotClick {
+    /// Pick up the whole stack, or put down, merge or swap the held stack.
+    Primary,
+    /// Pick up half of the stack, or put down a single item from the held stack.
+    Secondary,
+}
+
+/// A fixed number of item slots. Items of the same type are merged into stacks of up to
+/// [`ItemType::max_count`] items, and empty slots always hold air.
+#[derive(Clone, Debug)]
+pub struct Inventory {
+    slots: Box<[Item]>,
+}
+
+impl Inventory {
+    pub fn new(slot_count: usize) -> Self {
+        Self {
+            slots: vec![Item::default(); slot_count].into_boxed_slice(),
+        }
+    }
+
+    pub fn slot_count(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn slots(&self) -> &[Item] {
+        &self.slots
+    }
+
+    pub fn item(&self, index: usize) -> &Item {
+        &self.slots[index]
+    }
+
+    /// Replace the item in a slot, returning the item that was there before.
+    pub fn set_item(&mut self, index: usize, item: Item) -> Item {
+        let item = if item.is_empty() { Item::default() } else { item };
+        std::mem::replace(&mut self.slots[index], item)
+    }
+
+    pub fn take_item(&mut self, index: usize) -> Item {
+        std::mem::take(&mut self.slots[index])
+    }
+
+    /// Count how many items of a type there are across every slot.
+    pub fn count_of(&self, item_type: &'static ItemType) -> u32 {
+        self.slots
+            .iter()
+            .filter(|item| item.item_type() == item_type)
+            .map(Item::count)
+            .sum()
+    }
+
+    /// Merge as much of an item as possible into the stack in a slot, returning whatever didn't
+    /// fit. Nothing is merged if the slot holds a different type of item.
+    pub fn insert_into(&mut self, index: usize, item: Item) -> Item {
+        let slot = &mut self.slots[index];
+        if item.is_empty() {
+            return Item::default();
+        }
+        if slot.is_empty() {
+            let count = item.count().min(item.item_type().max_count());
+            *slot = item.with_count(count);
+            return item.with_count(item.count() - count);
+        }
+        if slot.item_type() != item.item_type() {
+            return item;
+        }
+        let space = item.item_type().max_count().saturating_sub(slot.count());
+        let count = item.count().min(space);
+        *slot = slot.with_count(slot.count() + count);
+        item.with_count(item.count() - count)
+    }
+
+    /// Insert an item anywhere it fits, returning whatever didn't fit. Existing stacks of the same
+    /// type are topped up first, then the remainder goes into the first empty slots.
+    pub fn insert(&mut self, item: Item) -> Item {
+        self.insert_in(0..self.slots.len(), item)
+    }
+
+    /// Like [`Self::insert`], but only considers slots in the given range.
+    pub fn insert_in(&mut self, range: Range<usize>, mut item: Item) -> Item {
+        for index in range.clone() {
+            if item.is_empty() {
+                break;
+            }
+            if self.slots[index].item_type() == item.item_type() {
+                item = self.insert_into(index, item);
+            }
+        }
+        for index in range {
+            if item.is_empty() {
+                break;
+            }
+            if self.slots[index].is_empty() {
+                item = self.insert_into(index, item);
+            }
+        }
+        item
+    }
+
+    /// Move the stack in a slot into the given range of slots, leaving behind whatever didn't fit.
+    pub fn transfer(&mut self, index: usize, range: Range<usize>) {
+        let item = self.take_item(index);
+        let leftover = self.insert_in(range, item);
+        self.set_item(index, leftover);
+    }
+
+    /// Apply a click on a slot, exchanging items between the slot and the stack held by the cursor.
+    pub fn click_slot(&mut self, index: usize, cursor_item: &mut Item, click: SlotClick) {
+        let slot = &self.slots[index];
+        let can_merge = slot.is_empty() || slot.item_type() == cursor_item.item_type();
+        if cursor_item.is_empty() {
+            *cursor_item = match click {
+                SlotClick::Primary => self.take_item(index),
+                SlotClick::Secondary => self.split(index),
+            };
+        }
+        else if !can_merge {
+            *cursor_item = self.set_item(index, std::mem::take(cursor_item));
+        }
+        else {
+            match click {
+                SlotClick::Primary => {
+                    *cursor_item = self.insert_into(index, std::mem::take(cursor_item));
+                }
+                SlotClick::Secondary => {
+                    if self.insert_into(index, cursor_item.with_count(1)).is_empty() {
+                        *cursor_item = cursor_item.decrement_count();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Remove up to `count` items from a slot, returning the items removed.
+    pub fn remove_from(&mut self, index: usize, count: u32) -> Item {
+        let slot = &mut self.slots[index];
+        let count = count.min(slot.count());
+        let removed = slot.with_count(count);
+        *slot = slot.with_count(slot.count() - count);
+        removed
+    }
+
+    /// Remove up to `count` items of a type from anywhere in the inventory, starting from the last
+    /// slot. Returns how many were removed.
+    pub fn remove(&mut self, item_type: &'static ItemType, count: u32) -> u32 {
+        let mut removed = 0;
+        for index in (0..self.slots.len()).rev() {
+            if removed >= count {
+                break;
+            }
+            if self.slots[index].item_type() == item_type {
+                removed += self.remove_from(index, count - removed).count();
+            }
+        }
+        removed
+    }
+
+    /// Take half of the stack in a slot, rounding up, and leave the rest behind.
+    pub fn split(&mut self, index: usize) -> Item {
+        let half = self.slots[index].count().div_ceil(2);
+        self.remove_from(index, half)
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::Array(self.slots
+            .iter()
+            .map(|item| if item.is_empty() { JsonValue::Null } else { item.to_json() })
+            .collect())
+    }
+
+    /// Read the slots of an inventory saved with [`Self::to_json`]. If the saved inventory has
+    /// more slots than this one, the extra items are discarded.
+    pub fn read_json(&mut self, data: &JsonValue) -> Result<(), String> {
+        if !data.is_array() {
+            return Err(format!("invalid inventory: {data}"));
+        }
+        for (index, item_data) in data.members().enumerate().take(self.slots.len()) {
+            let item = if item_data.is_null() { Item::default() } else { Item::from_json(item_data)? };
+            self.set_item(index, item);
+        }
+        Ok(())
+    }
+}
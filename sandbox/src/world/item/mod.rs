@@ -1,13 +1,30 @@
-use crate::world::block::{Block, BlockSide, BlockType};
+use json::JsonValue;
+use crate::world::block::{Block, BlockSide, BlockType, WallType};
 
 pub mod types;
+pub mod recipe;
+mod inventory;
 
 pub use types::ITEM_TYPES;
+pub use recipe::{Recipe, RecipeBook};
+pub use inventory::*;
+
+/// The kinds of tools. Blocks which prefer a certain kind of tool are mined faster with it.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ToolClass {
+    Pickaxe,
+    Axe,
+    Shovel,
+    Sword,
+}
 
 pub struct ItemType {
     name: &'static str,
     max_count: u32,
     block_type: Option<&'static BlockType>,
+    wall_type: Option<&'static WallType>,
+    tool_class: Option<ToolClass>,
+    tool_tier: u8,
     right_click: fn(&Block, &Item, BlockSide) -> (Option<Block>, Option<Item>),
 }
 
@@ -24,11 +41,42 @@ impl ItemType {
         self.block_type
     }
 
+    pub fn wall_type(&self) -> Option<&'static WallType> {
+        self.wall_type
+    }
+
+    pub fn tool_class(&self) -> Option<ToolClass> {
+        self.tool_class
+    }
+
+    /// How strong this item is as a tool, where 0 means it isn't a tool at all.
+    pub fn tool_tier(&self) -> u8 {
+        self.tool_tier
+    }
+
+    /// How many times faster than by hand this item mines blocks which prefer the given tool class.
+    pub fn mining_speed(&self, preferred_tool: Option<ToolClass>) -> f32 {
+        if preferred_tool.is_some() && self.tool_class == preferred_tool {
+            1.0 + self.tool_tier as f32
+        }
+        else {
+            1.0
+        }
+    }
+
     pub fn is_air(&self) -> bool {
         self == &types::AIR
     }
 }
 
+pub fn get_item_type(name: &str) -> Option<&'static ItemType> {
+    // TODO: HashMap would probably be better
+    ITEM_TYPES
+        .iter()
+        .copied()
+        .find(|item_type| item_type.name() == name)
+}
+
 impl PartialEq for ItemType {
     fn eq(&self, other: &Self) -> bool {
         // Comparing pointers is sufficient; only the static ItemType objects should be used.
@@ -77,6 +125,20 @@ impl Item {
         self.count
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.item_type.is_air() || self.count == 0
+    }
+
+    /// Create a stack of the same item type with a different count. A count of zero gives air.
+    pub fn with_count(&self, count: u32) -> Self {
+        if count == 0 {
+            Self::default()
+        }
+        else {
+            Self::new(self.item_type, count)
+        }
+    }
+
     pub fn decrement_count(&self) -> Self {
         let mut item = self.clone();
         item.count = item.count.saturating_sub(1);
@@ -91,6 +153,22 @@ impl Item {
     pub fn handle_right_click(&self, target_block: &Block, side: BlockSide) -> (Option<Block>, Option<Self>) {
         (self.item_type.right_click)(target_block, self, side)
     }
+
+    pub fn to_json(&self) -> JsonValue {
+        json::object! {
+            type: self.item_type.name(),
+            count: self.count,
+        }
+    }
+
+    pub fn from_json(data: &JsonValue) -> Result<Self, String> {
+        let item_type = data["type"].as_str()
+            .and_then(get_item_type)
+            .ok_or_else(|| format!("invalid item type: {}", data["type"]))?;
+        let count = data["count"].as_u32()
+            .ok_or_else(|| format!("invalid item count: {}", data["count"]))?;
+        Ok(Self::new(item_type, count))
+    }
 }
 
 impl Default for Item {
@@ -0,0 +1,265 @@
+use json::JsonValue;
+use crate::script::utils::parse_item_type;
+use crate::tools::asset::AssetPool;
+use super::*;
+
+/// An item type which must be present in a crafting grid slot, along with how many of it are
+/// used up from that slot each time the recipe is crafted.
+#[derive(Clone, Debug)]
+pub struct Ingredient {
+    item_type: &'static ItemType,
+    count: u32,
+}
+
+impl Ingredient {
+    pub fn new(item_type: &'static ItemType, count: u32) -> Self {
+        Self {
+            item_type,
+            count: count.max(1),
+        }
+    }
+
+    pub fn item_type(&self) -> &'static ItemType {
+        self.item_type
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn matches(&self, item: &Item) -> bool {
+        item.item_type() == self.item_type && item.count() >= self.count
+    }
+
+    /// Parse either an item type name, or an object with `item` and an optional `count`.
+    fn from_json(data: &JsonValue, assets: &AssetPool) -> Result<Self, String> {
+        if let Some(name) = data.as_str() {
+            return Ok(Self::new(parse_item_type(name, assets)?, 1));
+        }
+        let name = data["item"].as_str()
+            .ok_or_else(|| format!("invalid ingredient: {data}"))?;
+        let count = match &data["count"] {
+            JsonValue::Null => 1,
+            count => count.as_u32().ok_or_else(|| format!("invalid ingredient count: {count}"))?,
+        };
+        Ok(Self::new(parse_item_type(name, assets)?, count))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum RecipeShape {
+    /// Ingredients must be laid out in the grid exactly as in the pattern, although the pattern
+    /// can be anywhere in the grid. Empty pattern cells must be empty in the grid.
+    Shaped {
+        width: usize,
+        height: usize,
+        pattern: Vec<Option<Ingredient>>,
+    },
+    /// Each ingredient must be in its own slot, in any arrangement, with no other items present.
+    Shapeless {
+        ingredients: Vec<Ingredient>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct Recipe {
+    name: String,
+    shape: RecipeShape,
+    result: Item,
+}
+
+impl Recipe {
+    pub fn new(name: String, shape: RecipeShape, result: Item) -> Self {
+        Self {
+            name,
+            shape,
+            result,
+        }
+    }
+
+    /// Parse a recipe from a recipe file. Shaped recipes have a `pattern` of rows and a `key`
+    /// mapping each symbol to an ingredient, while shapeless recipes have a list of
+    /// `ingredients`. Ingredients and the `result` are either an item type name or an object with
+    /// `item` and `count`. Unknown item names are reported the same way as in commands.
+    pub fn from_json(name: String, data: &JsonValue, assets: &AssetPool) -> Result<Self, String> {
+        let shape = match data["type"].as_str() {
+            Some("shaped") => {
+                let rows: Vec<&str> = data["pattern"].members()
+                    .map(|row| row.as_str().ok_or_else(|| format!("invalid pattern row: {row}")))
+                    .collect::<Result<_, _>>()?;
+                // Trim empty rows and columns so the pattern can be lined up with the grid contents
+                let symbol_at = |x: usize, y: usize| rows[y].chars().nth(x).unwrap_or(' ');
+                let full_width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+                let columns: Vec<usize> = (0..full_width)
+                    .filter(|&x| (0..rows.len()).any(|y| symbol_at(x, y) != ' '))
+                    .collect();
+                let lines: Vec<usize> = (0..rows.len())
+                    .filter(|&y| (0..full_width).any(|x| symbol_at(x, y) != ' '))
+                    .collect();
+                let (Some(&min_x), Some(&max_x)) = (columns.first(), columns.last()) else {
+                    return Err(format!("empty pattern: {}", data["pattern"]));
+                };
+                let (min_y, max_y) = (lines[0], lines[lines.len() - 1]);
+                let width = max_x - min_x + 1;
+                let height = max_y - min_y + 1;
+
+                let mut pattern = Vec::with_capacity(width * height);
+                for y in min_y..=max_y {
+                    for x in min_x..=max_x {
+                        pattern.push(match symbol_at(x, y) {
+                            ' ' => None,
+                            symbol => {
+                                let key_data = &data["key"][symbol.to_string().as_str()];
+                                if key_data.is_null() {
+                                    return Err(format!("pattern symbol '{symbol}' is missing from the key"));
+                                }
+                                Some(Ingredient::from_json(key_data, assets)?)
+                            }
+                        });
+                    }
+                }
+                RecipeShape::Shaped {
+                    width,
+                    height,
+                    pattern,
+                }
+            }
+            Some("shapeless") => {
+                let ingredients: Vec<Ingredient> = data["ingredients"].members()
+                    .map(|ingredient| Ingredient::from_json(ingredient, assets))
+                    .collect::<Result<_, _>>()?;
+                if ingredients.is_empty() {
+                    return Err(format!("no ingredients: {}", data["ingredients"]));
+                }
+                RecipeShape::Shapeless {
+                    ingredients,
+                }
+            }
+            _ => return Err(format!("invalid recipe type: {}", data["type"])),
+        };
+
+        let result = Ingredient::from_json(&data["result"], assets)?;
+
+        Ok(Self::new(name, shape, Item::new(result.item_type(), result.count())))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn shape(&self) -> &RecipeShape {
+        &self.shape
+    }
+
+    pub fn result(&self) -> &Item {
+        &self.result
+    }
+
+    /// Check whether the items in a crafting grid can be used to craft this recipe. If they can,
+    /// returns the slot indices and counts of the items which would be used up.
+    pub fn match_grid(&self, grid: &[Item], grid_width: usize) -> Option<Vec<(usize, u32)>> {
+        match &self.shape {
+            RecipeShape::Shaped { width, height, pattern } => {
+                // Line up the pattern with the smallest area containing every item in the grid
+                let occupied: Vec<usize> = (0..grid.len()).filter(|&index| !grid[index].is_empty()).collect();
+                let min_x = occupied.iter().map(|index| index % grid_width).min()?;
+                let min_y = occupied.iter().map(|index| index / grid_width).min()?;
+                let max_x = occupied.iter().map(|index| index % grid_width).max()?;
+                let max_y = occupied.iter().map(|index| index / grid_width).max()?;
+                // Trimmed patterns have ingredients on every edge, so the areas must be the same size
+                if max_x - min_x + 1 != *width || max_y - min_y + 1 != *height {
+                    return None;
+                }
+
+                let mut used = Vec::new();
+                for y in 0..*height {
+                    for x in 0..*width {
+                        let index = (min_y + y) * grid_width + (min_x + x);
+                        match &pattern[y * width + x] {
+                            Some(ingredient) if ingredient.matches(&grid[index]) => {
+                                used.push((index, ingredient.count()));
+                            }
+                            None if grid[index].is_empty() => {}
+                            _ => return None,
+                        }
+                    }
+                }
+                Some(used)
+            }
+            RecipeShape::Shapeless { ingredients } => {
+                let mut unused: Vec<usize> = (0..grid.len()).filter(|&index| !grid[index].is_empty()).collect();
+                if unused.len() != ingredients.len() {
+                    return None;
+                }
+                // Giving the largest stacks to the most demanding ingredients first never fails
+                // when some other assignment would succeed
+                let mut sorted_ingredients: Vec<&Ingredient> = ingredients.iter().collect();
+                sorted_ingredients.sort_by_key(|ingredient| std::cmp::Reverse(ingredient.count()));
+                let mut used = Vec::new();
+                for ingredient in sorted_ingredients {
+                    let position = unused.iter()
+                        .enumerate()
+                        .filter(|&(_, &index)| grid[index].item_type() == ingredient.item_type())
+                        .max_by_key(|&(_, &index)| grid[index].count())
+                        .map(|(position, _)| position)?;
+                    let index = unused.swap_remove(position);
+                    if !ingredient.matches(&grid[index]) {
+                        return None;
+                    }
+                    used.push((index, ingredient.count()));
+                }
+                Some(used)
+            }
+        }
+    }
+
+    /// Use up the ingredients for one craft from a crafting grid, returning the result. Returns
+    /// `None` and leaves the grid alone if the grid doesn't match this recipe.
+    pub fn craft(&self, grid: &mut Inventory, grid_width: usize) -> Option<Item> {
+        let used = self.match_grid(grid.slots(), grid_width)?;
+        for (index, count) in used {
+            grid.remove_from(index, count);
+        }
+        Some(self.result.clone())
+    }
+}
+
+/// Every known crafting recipe, loaded from the recipe files in the assets.
+#[derive(Default, Debug)]
+pub struct RecipeBook {
+    recipes: Vec<Recipe>,
+}
+
+impl RecipeBook {
+    pub fn new() -> Self {
+        Self {
+            recipes: Vec::new(),
+        }
+    }
+
+    pub fn recipes(&self) -> &[Recipe] {
+        &self.recipes
+    }
+
+    pub fn add(&mut self, recipe: Recipe) {
+        self.recipes.push(recipe);
+    }
+
+    pub fn clear(&mut self) {
+        self.recipes.clear();
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Recipe> {
+        self.recipes.iter().find(|recipe| recipe.name() == name)
+    }
+
+    /// Find the first recipe which can be crafted from the items in a crafting grid.
+    pub fn find_match(&self, grid: &[Item], grid_width: usize) -> Option<&Recipe> {
+        self.recipes.iter().find(|recipe| recipe.match_grid(grid, grid_width).is_some())
+    }
+
+    /// Find every recipe which produces the given item type.
+    pub fn recipes_for(&self, item_type: &'static ItemType) -> impl Iterator<Item = &Recipe> {
+        self.recipes.iter().filter(move |recipe| recipe.result().item_type() == item_type)
+    }
+}
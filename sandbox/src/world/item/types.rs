@@ -20,6 +20,9 @@ const DEFAULTS: ItemType = ItemType {
     name: "invalid",
     max_count: 100,
     block_type: None,
+    wall_type: None,
+    tool_class: None,
+    tool_tier: 0,
     right_click: right_click_no_action,
 };
 
@@ -47,6 +50,7 @@ pub const ITEM_TYPES: &[&ItemType] = &[
     &COBALT_SHOVEL,
     &COBALT_SWORD,
     &COBBLES,
+    &COBBLES_WALL,
     &COPPER,
     &COPPER_AXE,
     &COPPER_BLOCK,
@@ -63,6 +67,7 @@ pub const ITEM_TYPES: &[&ItemType] = &[
     &DIAMOND_SHOVEL,
     &DIAMOND_SWORD,
     &DIRT,
+    &DIRT_WALL,
     &EMERALD,
     &EMERALD_BLOCK,
     &FLAMARITE,
@@ -94,7 +99,10 @@ pub const ITEM_TYPES: &[&ItemType] = &[
     &MAGMIUM_PICKAXE,
     &MAGMIUM_SHOVEL,
     &MAGMIUM_SWORD,
+    &OAK_PLATFORM,
+    &OAK_SLOPE,
     &OAK_TRUNK,
+    &OAK_WALL,
     &OAK_WOOD,
     &OBSIDIAN,
     &OBSIDIAN_BLOCK,
@@ -108,7 +116,9 @@ pub const ITEM_TYPES: &[&ItemType] = &[
     &QUARTZ_ORE,
     &SAND,
     &SANDSTONE,
+    &SANDSTONE_WALL,
     &SLATE,
+    &SLATE_WALL,
     &STEEL,
     &STEEL_AXE,
     &STEEL_BLOCK,
@@ -117,6 +127,7 @@ pub const ITEM_TYPES: &[&ItemType] = &[
     &STEEL_SWORD,
     &STICK,
     &STONE,
+    &STONE_WALL,
     &VERSATILIUM,
     &VERSATILIUM_BLOCK,
     &VOLTAGITE,
@@ -140,6 +151,8 @@ pub static ALUMINUM: ItemType = ItemType {
 pub static ALUMINUM_AXE: ItemType = ItemType {
     name: "aluminum_axe",
     max_count: 1,
+    tool_class: Some(ToolClass::Axe),
+    tool_tier: 3,
     ..DEFAULTS
 };
 pub static ALUMINUM_BLOCK: ItemType = ItemType {
@@ -151,16 +164,22 @@ pub static ALUMINUM_BLOCK: ItemType = ItemType {
 pub static ALUMINUM_PICKAXE: ItemType = ItemType {
     name: "aluminum_pickaxe",
     max_count: 1,
+    tool_class: Some(ToolClass::Pickaxe),
+    tool_tier: 3,
     ..DEFAULTS
 };
 pub static ALUMINUM_SHOVEL: ItemType = ItemType {
     name: "aluminum_shovel",
     max_count: 1,
+    tool_class: Some(ToolClass::Shovel),
+    tool_tier: 3,
     ..DEFAULTS
 };
 pub static ALUMINUM_SWORD: ItemType = ItemType {
     name: "aluminum_sword",
     max_count: 1,
+    tool_class: Some(ToolClass::Sword),
+    tool_tier: 3,
     ..DEFAULTS
 };
 pub static AMETHYST: ItemType = ItemType {
@@ -218,6 +237,8 @@ pub static COBALT: ItemType = ItemType {
 pub static COBALT_AXE: ItemType = ItemType {
     name: "cobalt_axe",
     max_count: 1,
+    tool_class: Some(ToolClass::Axe),
+    tool_tier: 4,
     ..DEFAULTS
 };
 pub static COBALT_BLOCK: ItemType = ItemType {
@@ -229,16 +250,22 @@ pub static COBALT_BLOCK: ItemType = ItemType {
 pub static COBALT_PICKAXE: ItemType = ItemType {
     name: "cobalt_pickaxe",
     max_count: 1,
+    tool_class: Some(ToolClass::Pickaxe),
+    tool_tier: 4,
     ..DEFAULTS
 };
 pub static COBALT_SHOVEL: ItemType = ItemType {
     name: "cobalt_shovel",
     max_count: 1,
+    tool_class: Some(ToolClass::Shovel),
+    tool_tier: 4,
     ..DEFAULTS
 };
 pub static COBALT_SWORD: ItemType = ItemType {
     name: "cobalt_sword",
     max_count: 1,
+    tool_class: Some(ToolClass::Sword),
+    tool_tier: 4,
     ..DEFAULTS
 };
 pub static COBBLES: ItemType = ItemType {
@@ -247,6 +274,11 @@ pub static COBBLES: ItemType = ItemType {
     right_click: right_click_place_block,
     ..DEFAULTS
 };
+pub static COBBLES_WALL: ItemType = ItemType {
+    name: "cobbles_wall",
+    wall_type: Some(&block::wall::COBBLES_WALL),
+    ..DEFAULTS
+};
 pub static COPPER: ItemType = ItemType {
     name: "copper",
     ..DEFAULTS
@@ -254,6 +286,8 @@ pub static COPPER: ItemType = ItemType {
 pub static COPPER_AXE: ItemType = ItemType {
     name: "copper_axe",
     max_count: 1,
+    tool_class: Some(ToolClass::Axe),
+    tool_tier: 2,
     ..DEFAULTS
 };
 pub static COPPER_BLOCK: ItemType = ItemType {
@@ -265,16 +299,22 @@ pub static COPPER_BLOCK: ItemType = ItemType {
 pub static COPPER_PICKAXE: ItemType = ItemType {
     name: "copper_pickaxe",
     max_count: 1,
+    tool_class: Some(ToolClass::Pickaxe),
+    tool_tier: 2,
     ..DEFAULTS
 };
 pub static COPPER_SHOVEL: ItemType = ItemType {
     name: "copper_shovel",
     max_count: 1,
+    tool_class: Some(ToolClass::Shovel),
+    tool_tier: 2,
     ..DEFAULTS
 };
 pub static COPPER_SWORD: ItemType = ItemType {
     name: "copper_sword",
     max_count: 1,
+    tool_class: Some(ToolClass::Sword),
+    tool_tier: 2,
     ..DEFAULTS
 };
 pub static COPPER_WIRE: ItemType = ItemType {
@@ -300,6 +340,8 @@ pub static DIAMOND: ItemType = ItemType {
 pub static DIAMOND_AXE: ItemType = ItemType {
     name: "diamond_axe",
     max_count: 1,
+    tool_class: Some(ToolClass::Axe),
+    tool_tier: 5,
     ..DEFAULTS
 };
 pub static DIAMOND_BLOCK: ItemType = ItemType {
@@ -311,16 +353,22 @@ pub static DIAMOND_BLOCK: ItemType = ItemType {
 pub static DIAMOND_PICKAXE: ItemType = ItemType {
     name: "diamond_pickaxe",
     max_count: 1,
+    tool_class: Some(ToolClass::Pickaxe),
+    tool_tier: 5,
     ..DEFAULTS
 };
 pub static DIAMOND_SHOVEL: ItemType = ItemType {
     name: "diamond_shovel",
     max_count: 1,
+    tool_class: Some(ToolClass::Shovel),
+    tool_tier: 5,
     ..DEFAULTS
 };
 pub static DIAMOND_SWORD: ItemType = ItemType {
     name: "diamond_sword",
     max_count: 1,
+    tool_class: Some(ToolClass::Sword),
+    tool_tier: 5,
     ..DEFAULTS
 };
 pub static DIRT: ItemType = ItemType {
@@ -329,6 +377,11 @@ pub static DIRT: ItemType = ItemType {
     right_click: right_click_place_block,
     ..DEFAULTS
 };
+pub static DIRT_WALL: ItemType = ItemType {
+    name: "dirt_wall",
+    wall_type: Some(&block::wall::DIRT_WALL),
+    ..DEFAULTS
+};
 pub static EMERALD: ItemType = ItemType {
     name: "emerald",
     ..DEFAULTS
@@ -376,6 +429,8 @@ pub static GOLD: ItemType = ItemType {
 pub static GOLD_AXE: ItemType = ItemType {
     name: "gold_axe",
     max_count: 1,
+    tool_class: Some(ToolClass::Axe),
+    tool_tier: 2,
     ..DEFAULTS
 };
 pub static GOLD_BLOCK: ItemType = ItemType {
@@ -387,16 +442,22 @@ pub static GOLD_BLOCK: ItemType = ItemType {
 pub static GOLD_PICKAXE: ItemType = ItemType {
     name: "gold_pickaxe",
     max_count: 1,
+    tool_class: Some(ToolClass::Pickaxe),
+    tool_tier: 2,
     ..DEFAULTS
 };
 pub static GOLD_SHOVEL: ItemType = ItemType {
     name: "gold_shovel",
     max_count: 1,
+    tool_class: Some(ToolClass::Shovel),
+    tool_tier: 2,
     ..DEFAULTS
 };
 pub static GOLD_SWORD: ItemType = ItemType {
     name: "gold_sword",
     max_count: 1,
+    tool_class: Some(ToolClass::Sword),
+    tool_tier: 2,
     ..DEFAULTS
 };
 pub static GOLD_WIRE: ItemType = ItemType {
@@ -418,6 +479,8 @@ pub static IRON: ItemType = ItemType {
 pub static IRON_AXE: ItemType = ItemType {
     name: "iron_axe",
     max_count: 1,
+    tool_class: Some(ToolClass::Axe),
+    tool_tier: 3,
     ..DEFAULTS
 };
 pub static IRON_BLOCK: ItemType = ItemType {
@@ -429,16 +492,22 @@ pub static IRON_BLOCK: ItemType = ItemType {
 pub static IRON_PICKAXE: ItemType = ItemType {
     name: "iron_pickaxe",
     max_count: 1,
+    tool_class: Some(ToolClass::Pickaxe),
+    tool_tier: 3,
     ..DEFAULTS
 };
 pub static IRON_SHOVEL: ItemType = ItemType {
     name: "iron_shovel",
     max_count: 1,
+    tool_class: Some(ToolClass::Shovel),
+    tool_tier: 3,
     ..DEFAULTS
 };
 pub static IRON_SWORD: ItemType = ItemType {
     name: "iron_sword",
     max_count: 1,
+    tool_class: Some(ToolClass::Sword),
+    tool_tier: 3,
     ..DEFAULTS
 };
 pub static LANTERN: ItemType = ItemType {
@@ -464,6 +533,8 @@ pub static MAGMIUM: ItemType = ItemType {
 pub static MAGMIUM_AXE: ItemType = ItemType {
     name: "magmium_axe",
     max_count: 1,
+    tool_class: Some(ToolClass::Axe),
+    tool_tier: 6,
     ..DEFAULTS
 };
 pub static MAGMIUM_BLOCK: ItemType = ItemType {
@@ -475,16 +546,34 @@ pub static MAGMIUM_BLOCK: ItemType = ItemType {
 pub static MAGMIUM_PICKAXE: ItemType = ItemType {
     name: "magmium_pickaxe",
     max_count: 1,
+    tool_class: Some(ToolClass::Pickaxe),
+    tool_tier: 6,
     ..DEFAULTS
 };
 pub static MAGMIUM_SHOVEL: ItemType = ItemType {
     name: "magmium_shovel",
     max_count: 1,
+    tool_class: Some(ToolClass::Shovel),
+    tool_tier: 6,
     ..DEFAULTS
 };
 pub static MAGMIUM_SWORD: ItemType = ItemType {
     name: "magmium_sword",
     max_count: 1,
+    tool_class: Some(ToolClass::Sword),
+    tool_tier: 6,
+    ..DEFAULTS
+};
+pub static OAK_PLATFORM: ItemType = ItemType {
+    name: "oak_platform",
+    block_type: Some(&block::types::OAK_PLATFORM),
+    right_click: right_click_place_block,
+    ..DEFAULTS
+};
+pub static OAK_SLOPE: ItemType = ItemType {
+    name: "oak_slope",
+    block_type: Some(&block::types::OAK_SLOPE),
+    right_click: right_click_place_block,
     ..DEFAULTS
 };
 pub static OAK_TRUNK: ItemType = ItemType {
@@ -493,6 +582,11 @@ pub static OAK_TRUNK: ItemType = ItemType {
     right_click: right_click_place_block,
     ..DEFAULTS
 };
+pub static OAK_WALL: ItemType = ItemType {
+    name: "oak_wall",
+    wall_type: Some(&block::wall::OAK_WALL),
+    ..DEFAULTS
+};
 pub static OAK_WOOD: ItemType = ItemType {
     name: "oak_wood",
     block_type: Some(&block::types::OAK_WOOD),
@@ -567,12 +661,22 @@ pub static SANDSTONE: ItemType = ItemType {
     right_click: right_click_place_block,
     ..DEFAULTS
 };
+pub static SANDSTONE_WALL: ItemType = ItemType {
+    name: "sandstone_wall",
+    wall_type: Some(&block::wall::SANDSTONE_WALL),
+    ..DEFAULTS
+};
 pub static SLATE: ItemType = ItemType {
     name: "slate",
     block_type: Some(&block::types::SLATE),
     right_click: right_click_place_block,
     ..DEFAULTS
 };
+pub static SLATE_WALL: ItemType = ItemType {
+    name: "slate_wall",
+    wall_type: Some(&block::wall::SLATE_WALL),
+    ..DEFAULTS
+};
 pub static STEEL: ItemType = ItemType {
     name: "steel",
     ..DEFAULTS
@@ -580,6 +684,8 @@ pub static STEEL: ItemType = ItemType {
 pub static STEEL_AXE: ItemType = ItemType {
     name: "steel_axe",
     max_count: 1,
+    tool_class: Some(ToolClass::Axe),
+    tool_tier: 4,
     ..DEFAULTS
 };
 pub static STEEL_BLOCK: ItemType = ItemType {
@@ -591,16 +697,22 @@ pub static STEEL_BLOCK: ItemType = ItemType {
 pub static STEEL_PICKAXE: ItemType = ItemType {
     name: "steel_pickaxe",
     max_count: 1,
+    tool_class: Some(ToolClass::Pickaxe),
+    tool_tier: 4,
     ..DEFAULTS
 };
 pub static STEEL_SHOVEL: ItemType = ItemType {
     name: "steel_shovel",
     max_count: 1,
+    tool_class: Some(ToolClass::Shovel),
+    tool_tier: 4,
     ..DEFAULTS
 };
 pub static STEEL_SWORD: ItemType = ItemType {
     name: "steel_sword",
     max_count: 1,
+    tool_class: Some(ToolClass::Sword),
+    tool_tier: 4,
     ..DEFAULTS
 };
 pub static STICK: ItemType = ItemType {
@@ -613,6 +725,11 @@ pub static STONE: ItemType = ItemType {
     right_click: right_click_place_block,
     ..DEFAULTS
 };
+pub static STONE_WALL: ItemType = ItemType {
+    name: "stone_wall",
+    wall_type: Some(&block::wall::STONE_WALL),
+    ..DEFAULTS
+};
 pub static VERSATILIUM: ItemType = ItemType {
     name: "versatilium",
     ..DEFAULTS
@@ -642,20 +759,28 @@ pub static VOLTAGITE_BLOCK: ItemType = ItemType {
 pub static WOODEN_AXE: ItemType = ItemType {
     name: "wooden_axe",
     max_count: 1,
+    tool_class: Some(ToolClass::Axe),
+    tool_tier: 1,
     ..DEFAULTS
 };
 pub static WOODEN_PICKAXE: ItemType = ItemType {
     name: "wooden_pickaxe",
     max_count: 1,
+    tool_class: Some(ToolClass::Pickaxe),
+    tool_tier: 1,
     ..DEFAULTS
 };
 pub static WOODEN_SHOVEL: ItemType = ItemType {
     name: "wooden_shovel",
     max_count: 1,
+    tool_class: Some(ToolClass::Shovel),
+    tool_tier: 1,
     ..DEFAULTS
 };
 pub static WOODEN_SWORD: ItemType = ItemType {
     name: "wooden_sword",
     max_count: 1,
+    tool_class: Some(ToolClass::Sword),
+    tool_tier: 1,
     ..DEFAULTS
 };
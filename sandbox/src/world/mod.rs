@@ -1,19 +1,28 @@
 use std::cell::{Ref, RefMut};
 use std::collections::HashMap;
+use json::JsonValue;
 use innovus::gfx::color::RGBColor;
-use innovus::tools::phys::Physics;
+use innovus::tools::phys::{ColliderHandle, CollisionSide, Physics, QueryFilter};
 use crate::audio::AudioEngine;
 use crate::tools::*;
 use crate::tools::asset::AssetPool;
 use crate::tools::input::InputState;
-use block::{light_value, Block, BlockSide, Chunk, ChunkLocation, ChunkMap, CHUNK_SIZE};
+use crate::tools::noise::SimpleRandom;
+use block::{light_value, Block, BlockCoord, BlockSide, BlockSlot, BlockType, Chunk, ChunkLocation, ChunkMap, CHUNK_SIZE};
+use block::crack::CrackOverlay;
 use block::preview::BlockPreview;
+use block::wall::WallType;
 use camera::Camera;
 use entity::Entity;
 use entity::render::EntityRenderer;
+use entity::types::falling_block::FallingBlock;
+use entity::types::item::ItemEntity;
 use entity::types::player::{Player, PlayerMode};
 use gen::WorldGenerator;
-use particle::{choose_random, random_unit_vector, ParticleInfo, ParticleManager};
+use item::{Item, ItemType};
+use save::WorldSave;
+use time::WorldTime;
+use particle::{choose_random, pseudo_random, random_unit_vector, ParticleInfo, ParticleManager};
 
 pub mod block;
 pub mod camera;
@@ -21,11 +30,58 @@ pub mod entity;
 pub mod gen;
 pub mod item;
 pub mod particle;
+pub mod save;
+pub mod time;
 
 pub const SECONDS_PER_TICK: f32 = 0.05;
+pub const SECONDS_PER_PHYSICS_STEP: f32 = 1.0 / 120.0;
+/// The most physics steps which will be simulated in a single frame. If the game falls further
+/// behind than this (e.g. after a lag spike), the remaining time is skipped.
+pub const MAX_PHYSICS_STEPS_PER_FRAME: u32 = 8;
+pub const LEVEL_FORMAT_VERSION: u32 = 1;
+
+// Physics collision layers
+pub const TERRAIN_LAYER: u32 = 1 << 0;
+pub const ENTITY_LAYER: u32 = 1 << 1;
+
+/// The maximum distance from the player's eyes at which blocks can be interacted with.
+pub const PLAYER_REACH: f32 = 6.0;
+/// The minimum downward speed at which an entity hitting the ground counts as a landing.
+pub const LANDING_SPEED: f32 = 12.0;
+
+/// The thing a physics collider in the world belongs to.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ColliderOwner {
+    Block(Vector<i64, 2>),
+    Entity(Uuid),
+}
+
+/// What the player is mining in a block slot: either the block itself, or the wall behind it.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum MiningTarget {
+    Block(&'static BlockType),
+    Wall(&'static WallType),
+}
+
+impl MiningTarget {
+    fn mining_time(&self, tool: &ItemType) -> f32 {
+        match self {
+            Self::Block(block_type) => block_type.mining_time(tool),
+            Self::Wall(wall_type) => wall_type.mining_time(tool),
+        }
+    }
+}
+
+/// The block or wall the player is currently mining, and for how long they have been mining it.
+struct MiningState {
+    block_position: Vector<i64, 2>,
+    target: MiningTarget,
+    seconds: f32,
+}
 
 pub struct World<'world> {
     seconds_since_last_tick: f32,
+    seconds_since_last_physics_step: f32,
     camera: Camera,
     physics: Physics,
     chunks: ChunkMap,
@@ -34,23 +90,28 @@ pub struct World<'world> {
     entity_renderer: EntityRenderer,
     particles: ParticleManager,
     block_preview: BlockPreview,
-    sky_color: Vector<f32, 3>,
+    mining: Option<MiningState>,
+    crack_overlay: CrackOverlay,
+    time: WorldTime,
     sky_light: f32,
 }
 
 impl<'world> World<'world> {
-    pub fn new(generator: Option<Box<dyn WorldGenerator>>, camera: Camera, assets: &mut AssetPool) -> Self {
+    pub fn new(generator: Option<Box<dyn WorldGenerator>>, save: Option<WorldSave>, camera: Camera, assets: &mut AssetPool) -> Self {
         let mut world = Self {
             seconds_since_last_tick: SECONDS_PER_TICK,
+            seconds_since_last_physics_step: 0.0,
             camera,
             physics: Physics::new(),
-            chunks: ChunkMap::new(generator),
+            chunks: ChunkMap::new(generator, save),
             entities: HashMap::new(),
             entity_renderer: EntityRenderer::new(),
             player: Player::new(generate_uuid(), Vector([-0.5, 0.0]), None, PlayerMode::Normal),
             particles: ParticleManager::new(),
             block_preview: BlockPreview::new(Vector::zero(), &item::types::AIR, 0.4),
-            sky_color: Vector([0.6, 0.8, 1.0]),
+            mining: None,
+            crack_overlay: CrackOverlay::new(),
+            time: WorldTime::default(),
             sky_light: 1.0,
         };
         world.player.attach_collision(&mut world.physics);
@@ -60,7 +121,15 @@ impl<'world> World<'world> {
     }
 
     pub fn sky_color(&self) -> RGBColor {
-        RGBColor(self.sky_color.mul(self.sky_light))
+        RGBColor(self.time.sky_color().mul(self.sky_light))
+    }
+
+    pub fn time(&self) -> &WorldTime {
+        &self.time
+    }
+
+    pub fn time_mut(&mut self) -> &mut WorldTime {
+        &mut self.time
     }
 
     pub fn camera(&self) -> &Camera {
@@ -103,6 +172,73 @@ impl<'world> World<'world> {
         self.chunks.unload(location, &mut self.physics);
     }
 
+    /// Read the world generator specified by level data, if any.
+    pub fn read_level_generator(level: &JsonValue) -> Result<Option<Box<dyn WorldGenerator>>, String> {
+        let generator_data = &level["generator"];
+        if generator_data.is_null() {
+            return Ok(None);
+        }
+        let name = generator_data["name"].as_str()
+            .ok_or_else(|| format!("invalid world generator name: {}", generator_data["name"]))?;
+        let seed = generator_data["seed"].as_u64()
+            .ok_or_else(|| format!("invalid world seed: {}", generator_data["seed"]))?;
+        gen::create_generator(name, seed)
+            .map(Some)
+            .ok_or_else(|| format!("unknown world generator '{name}'"))
+    }
+
+    pub fn restore_level(&mut self, level: &JsonValue, assets: &mut AssetPool) -> Result<(), String> {
+        let version = level["version"].as_u32()
+            .ok_or_else(|| format!("invalid level format version: {}", level["version"]))?;
+        if version != LEVEL_FORMAT_VERSION {
+            return Err(format!("unsupported level format version {version}"));
+        }
+
+        // Older levels don't record the tick, in which case scheduled ticks just run early
+        let tick = level["tick"].as_u64().unwrap_or(0);
+        self.chunks.set_current_tick(tick);
+        // Older levels don't record the time of day either, so they start in the morning
+        self.time = match &level["time"] {
+            JsonValue::Null => WorldTime::default(),
+            time => WorldTime::from_json(time)?,
+        };
+
+        let player = Player::from_json(&level["player"])?;
+        self.player.detach_collision(&mut self.physics);
+        self.player.detach_appearance(&mut self.entity_renderer);
+        self.player = player;
+        self.player.attach_collision(&mut self.physics);
+        self.player.attach_appearance(assets, &mut self.entity_renderer);
+        self.camera.set_position(self.player.position());
+        Ok(())
+    }
+
+    pub fn level_data(&self) -> JsonValue {
+        let generator_data = match self.chunks.generator() {
+            Some(generator) => json::object! {
+                name: generator.generator_name(),
+                seed: generator.world_seed(),
+            },
+            None => JsonValue::Null,
+        };
+        json::object! {
+            version: LEVEL_FORMAT_VERSION,
+            generator: generator_data,
+            tick: self.chunks.current_tick(),
+            time: self.time.to_json(),
+            player: self.player.to_json(),
+        }
+    }
+
+    pub fn save(&mut self) {
+        self.chunks.save_all();
+        if let Some(save) = self.chunks.save() {
+            if let Err(err) = save.write_level(&self.level_data()) {
+                eprintln!("Failed to save world: {err}");
+            }
+        }
+    }
+
     pub fn player_use_item(&mut self, chunk_location: ChunkLocation, block_x: usize, block_y: usize, side: BlockSide, assets: &AssetPool, audio: &AudioEngine) {
         if let Some(mut chunk) = self.chunks.get_mut(chunk_location) {
             let (changed_block, changed_item) = chunk
@@ -118,35 +254,195 @@ impl<'world> World<'world> {
         }
     }
 
-    pub fn user_destroy_block(&mut self, chunk_location: ChunkLocation, block_x: usize, block_y: usize, assets: &mut AssetPool, audio: &AudioEngine) {
-        if let Some(mut chunk) = self.chunks.get_mut(chunk_location) {
-            let block_type = chunk.block_at(block_x, block_y).block_type();
-            if block_type != &block::types::AIR {
-                let air_block = Block::new(&block::types::AIR, Default::default());
-                chunk.set_block_at(block_x, block_y, air_block, &self.chunks, &mut self.physics);
-                // Create particles coming from the center of the destroyed block
-                if let Some(palette) = block_type.palette_key().and_then(|key| assets.get_color_palette(key).ok()) {
-                    let position = Vector([
-                        chunk_location.x() as f32 * CHUNK_SIZE as f32 + block_x as f32 + 0.5,
-                        chunk_location.y() as f32 * CHUNK_SIZE as f32 + block_y as f32 + 0.5,
-                    ]);
-                    for _ in 0..16 {
-                        let velocity = random_unit_vector().mul(3.0) + random_unit_vector().mul(1.0);
-                        let Some(&color) = choose_random(palette.colors()) else {
-                            continue;
-                        };
-                        self.particles.create_particle(ParticleInfo {
-                            position,
-                            velocity,
-                            color,
-                            size: 2.0,
-                            ..Default::default()
-                        });
-                    }
-                    audio.play_sound(assets.resolve_path("sounds/block/wood_big_0.ogg")).unwrap();
+    /// Keep mining the block at the given position, breaking it once the player has been mining it
+    /// for long enough with their held item. Moving on to a different block starts over.
+    pub fn player_mine_block(&mut self, block_position: Vector<i64, 2>, dt: f32, assets: &mut AssetPool, audio: &AudioEngine) {
+        // Air and liquids are replaced by placing blocks rather than mined
+        let block_type = self.chunks
+            .with_block_at(block_position, |block| (!block.is_replaceable()).then(|| block.block_type()))
+            .flatten();
+        match block_type {
+            Some(block_type) => self.player_mine(block_position, MiningTarget::Block(block_type), dt, assets, audio),
+            None => self.stop_mining(),
+        }
+    }
+
+    pub fn player_mine_wall(&mut self, block_position: Vector<i64, 2>, dt: f32, assets: &mut AssetPool, audio: &AudioEngine) {
+        let wall_type = self.chunks
+            .with_block_slot_at(block_position, BlockSlot::wall)
+            .filter(|wall_type| !wall_type.is_air());
+        match wall_type {
+            Some(wall_type) => self.player_mine(block_position, MiningTarget::Wall(wall_type), dt, assets, audio),
+            None => self.stop_mining(),
+        }
+    }
+
+    fn player_mine(&mut self, block_position: Vector<i64, 2>, target: MiningTarget, dt: f32, assets: &mut AssetPool, audio: &AudioEngine) {
+        let mining = match &mut self.mining {
+            Some(mining) if mining.block_position == block_position && mining.target == target => mining,
+            mining => mining.insert(MiningState {
+                block_position,
+                target,
+                seconds: 0.0,
+            }),
+        };
+        mining.seconds += dt;
+
+        let tool = self.player.held_item().item_type();
+        if mining.seconds >= target.mining_time(tool) {
+            self.mining = None;
+            match target {
+                MiningTarget::Block(block_type) => {
+                    self.user_destroy_block(block_position, block_type.can_harvest_with(tool), assets, audio);
+                }
+                MiningTarget::Wall(_) => self.user_destroy_wall(block_position, assets, audio),
+            }
+        }
+    }
+
+    pub fn stop_mining(&mut self) {
+        self.mining = None;
+    }
+
+    /// The block being mined and how close it is to breaking, from 0 to 1.
+    pub fn mining_progress(&self) -> Option<(Vector<i64, 2>, f32)> {
+        self.mining.as_ref().map(|mining| {
+            let mining_time = mining.target.mining_time(self.player.held_item().item_type());
+            let progress = if mining_time > 0.0 { mining.seconds / mining_time } else { 1.0 };
+            (mining.block_position, progress.min(1.0))
+        })
+    }
+
+    /// Break the block at the given position. The player only gets its item if `harvest` is set.
+    pub fn user_destroy_block(&mut self, block_position: Vector<i64, 2>, harvest: bool, assets: &mut AssetPool, audio: &AudioEngine) {
+        let Some(block_type) = self.chunks.with_block_at(block_position, Block::block_type) else {
+            return;
+        };
+        if block_type == &block::types::AIR {
+            return;
+        }
+        let air_block = Block::new(&block::types::AIR, Default::default());
+        self.chunks.set_block_at(block_position, air_block, &mut self.physics);
+        self.create_break_particles(block_position, block_type.palette_key(), assets, audio);
+        if harvest {
+            let mut random = SimpleRandom::with_seed(pseudo_random());
+            for item in block_type.roll_drops(&mut random) {
+                self.drop_item(block_position, item, assets);
+            }
+        }
+    }
+
+    pub fn player_place_wall(&mut self, block_position: Vector<i64, 2>, assets: &AssetPool, audio: &AudioEngine) {
+        let held_item = self.player.held_item().clone();
+        let Some(wall_type) = held_item.item_type().wall_type() else {
+            return;
+        };
+        let is_empty = self.chunks.with_block_slot_at(block_position, |slot| slot.wall().is_air());
+        if held_item.count() > 0 && is_empty == Some(true) {
+            self.chunks.set_wall_at(block_position, wall_type);
+            self.player.set_held_item(held_item.decrement_count());
+            audio.play_sound(assets.resolve_path("sounds/block/wood_big_1.ogg")).unwrap();
+        }
+    }
+
+    pub fn user_destroy_wall(&mut self, block_position: Vector<i64, 2>, assets: &mut AssetPool, audio: &AudioEngine) {
+        let Some(wall_type) = self.chunks.with_block_slot_at(block_position, BlockSlot::wall) else {
+            return;
+        };
+        if !wall_type.is_air() {
+            self.chunks.set_wall_at(block_position, &block::wall::AIR);
+            self.create_break_particles(block_position, wall_type.palette_key(), assets, audio);
+            if let Some(item_type) = wall_type.item_type() {
+                self.drop_item(block_position, Item::new(item_type, 1), assets);
+            }
+        }
+    }
+
+    /// Drop an item from the center of a block, popping it out in a random direction.
+    pub fn drop_item(&mut self, block_position: Vector<i64, 2>, item: Item, assets: &mut AssetPool) {
+        let position = block_position.map(|x| x as f32 + 0.5) - Vector([0.0, ItemEntity::SIZE / 2.0]);
+        let velocity = Vector([random_unit_vector().x() * 2.0, 4.0]);
+        self.add_entity(Box::new(ItemEntity::new(generate_uuid(), item, position, velocity)), assets);
+    }
+
+    /// Merge dropped items with the other stacks of the same item their sensors detect, and give
+    /// the player any dropped items they are touching. Items which don't fit in the inventory stay
+    /// on the ground.
+    fn update_dropped_items(&mut self) {
+        let dropped_colliders: HashMap<ColliderHandle, Uuid> = self.entities
+            .values()
+            .filter(|entity| !entity.is_removed() && entity.dropped_item().is_some())
+            .filter_map(|entity| Some((entity.collider()?.clone(), entity.uuid())))
+            .collect();
+        let dropped_item = |entities: &HashMap<Uuid, Box<dyn Entity + 'world>>, uuid: &Uuid| {
+            entities.get(uuid).and_then(|entity| entity.dropped_item().cloned())
+        };
+
+        let mut merges = Vec::new();
+        for &uuid in dropped_colliders.values() {
+            let Some(sensor) = self.entities.get(&uuid).and_then(|entity| entity.sensor()) else {
+                continue;
+            };
+            for other in self.physics.sensor_overlaps(sensor) {
+                // Both stacks detect each other, so only merge each pair once
+                if let Some(&other_uuid) = dropped_colliders.get(other).filter(|&&other_uuid| uuid < other_uuid) {
+                    merges.push((uuid, other_uuid));
                 }
             }
         }
+        merges.sort();
+        for (uuid, other_uuid) in merges {
+            let (Some(item), Some(other_item)) = (dropped_item(&self.entities, &uuid), dropped_item(&self.entities, &other_uuid)) else {
+                continue;
+            };
+            if item.is_empty() || other_item.is_empty() || item.item_type() != other_item.item_type() {
+                continue;
+            }
+            // Move as much of the other stack into this one as will fit
+            let moved = other_item.count().min(item.item_type().max_count().saturating_sub(item.count()));
+            if moved > 0 {
+                self.entities.get_mut(&uuid).unwrap().set_dropped_item(item.with_count(item.count() + moved));
+                self.entities.get_mut(&other_uuid).unwrap().set_dropped_item(other_item.with_count(other_item.count() - moved));
+            }
+        }
+
+        if self.player.mode() == PlayerMode::Spectating {
+            return;
+        }
+        let Some(player_bounds) = self.player.collider().and_then(|handle| self.physics.get_collider(handle)).map(|collider| collider.rectangle) else {
+            return;
+        };
+        let filter = QueryFilter::new().with_mask(ENTITY_LAYER);
+        for handle in self.physics.overlap(&player_bounds, &filter) {
+            let Some(entity) = dropped_colliders.get(&handle).and_then(|uuid| self.entities.get_mut(uuid)) else {
+                continue;
+            };
+            if let Some(item) = entity.dropped_item().filter(|item| !item.is_empty()).cloned() {
+                let leftover = self.player.inventory_mut().insert(item);
+                entity.set_dropped_item(leftover);
+            }
+        }
+    }
+
+    /// Create particles coming from the center of a destroyed block or wall.
+    fn create_break_particles(&mut self, block_position: Vector<i64, 2>, palette_key: Option<&str>, assets: &mut AssetPool, audio: &AudioEngine) {
+        if let Some(palette) = palette_key.and_then(|key| assets.get_color_palette(key).ok()) {
+            let position = block_position.map(|x| x as f32 + 0.5);
+            for _ in 0..16 {
+                let velocity = random_unit_vector().mul(3.0) + random_unit_vector().mul(1.0);
+                let Some(&color) = choose_random(palette.colors()) else {
+                    continue;
+                };
+                self.particles.create_particle(ParticleInfo {
+                    position,
+                    velocity,
+                    color,
+                    size: 2.0,
+                    ..Default::default()
+                });
+            }
+            audio.play_sound(assets.resolve_path("sounds/block/wood_big_0.ogg")).unwrap();
+        }
     }
 
     pub fn player(&mut self) -> &Player {
@@ -190,6 +486,109 @@ impl<'world> World<'world> {
         }
     }
 
+    pub fn collider_owner(&self, handle: &ColliderHandle) -> Option<ColliderOwner> {
+        if self.player.collider() == Some(handle) {
+            return Some(ColliderOwner::Entity(self.player.uuid()));
+        }
+        for entity in self.entities.values() {
+            if entity.collider() == Some(handle) {
+                return Some(ColliderOwner::Entity(entity.uuid()));
+            }
+        }
+
+        let collider = self.physics.get_collider(handle)?;
+        if !collider.fixed {
+            return None;
+        }
+        // Block colliders always lie within the bounds of their block
+        let center = collider.rectangle.center();
+        let block_x = center.x().floor() as i64;
+        let block_y = center.y().floor() as i64;
+        let coord_x = BlockCoord::from(block_x);
+        let coord_y = BlockCoord::from(block_y);
+        let chunk = self.chunks.get(Vector([coord_x.chunk, coord_y.chunk]))?;
+        chunk
+            .block_colliders_at(coord_x.offset, coord_y.offset)?
+            .contains(handle)
+            .then_some(ColliderOwner::Block(Vector([block_x, block_y])))
+    }
+
+    /// Whether the player can interact with the block at the given position, meaning the block
+    /// is within reach and there is no other block in the way.
+    pub fn player_can_reach(&self, block_position: Vector<i64, 2>) -> bool {
+        if self.player.mode() == PlayerMode::Spectating {
+            return true;
+        }
+
+        let eye_position = self.player.eye_position();
+        let target = block_position.map(|x| x as f32 + 0.5);
+        if (target - eye_position).magnitude() > PLAYER_REACH {
+            return false;
+        }
+
+        let filter = QueryFilter::new().with_mask(TERRAIN_LAYER);
+        match self.physics.raycast(eye_position, target, &filter) {
+            Some(hit) => self.collider_owner(&hit.collider) == Some(ColliderOwner::Block(block_position)),
+            None => true,
+        }
+    }
+
+    fn handle_contact_events(&mut self, assets: &mut AssetPool, audio: &AudioEngine) {
+        let events: Vec<_> = self.physics.drain_contact_events().collect();
+        // An entity landing across multiple blocks produces a contact for each one
+        let mut landed_colliders = Vec::new();
+        for event in events {
+            // Consider each contact from the perspective of both colliders involved
+            let perspectives = [
+                (&event.collider_1, &event.collider_2, event.side, event.relative_velocity),
+                (&event.collider_2, &event.collider_1, event.side.opposite(), event.relative_velocity.mul(-1.0)),
+            ];
+            for (this, other, side, relative_velocity) in perspectives {
+                let landed = matches!(side, CollisionSide::Bottom | CollisionSide::BottomLeft | CollisionSide::BottomRight)
+                    && -relative_velocity.y() >= LANDING_SPEED;
+                if !landed || landed_colliders.contains(this) {
+                    continue;
+                }
+                let (Some(ColliderOwner::Entity(_)), Some(ColliderOwner::Block(block_position))) = (self.collider_owner(this), self.collider_owner(other)) else {
+                    continue;
+                };
+                let Some(rectangle) = self.physics.get_collider(this).map(|collider| collider.rectangle) else {
+                    continue;
+                };
+                landed_colliders.push(this.clone());
+                let block_type = {
+                    let coord_x = BlockCoord::from(block_position.x());
+                    let coord_y = BlockCoord::from(block_position.y());
+                    let Some(chunk) = self.chunks.get(Vector([coord_x.chunk, coord_y.chunk])) else {
+                        continue;
+                    };
+                    chunk.block_at(coord_x.offset, coord_y.offset).block_type()
+                };
+
+                // Kick up some particles from the block that was landed on
+                if let Some(palette) = block_type.palette_key().and_then(|key| assets.get_color_palette(key).ok()) {
+                    let position = Vector([rectangle.center().x(), rectangle.min.y()]);
+                    let particle_count = (-relative_velocity.y() / 2.0) as usize;
+                    for _ in 0..particle_count {
+                        let velocity = Vector([random_unit_vector().x() * 4.0, 2.0]) + random_unit_vector();
+                        let Some(&color) = choose_random(palette.colors()) else {
+                            continue;
+                        };
+                        self.particles.create_particle(ParticleInfo {
+                            position,
+                            velocity,
+                            color,
+                            size: 1.5,
+                            lifetime: 0.5,
+                            ..Default::default()
+                        });
+                    }
+                    audio.play_sound(assets.resolve_path("sounds/block/wood_big_2.ogg")).unwrap();
+                }
+            }
+        }
+    }
+
     pub fn set_block_preview_position(&mut self, position: Vector<f32, 2>) {
         self.block_preview.set_position(position);
     }
@@ -204,19 +603,32 @@ impl<'world> World<'world> {
         self.player.attach_appearance(assets, &mut self.entity_renderer);
     }
 
-    pub fn update(&mut self, inputs: &InputState, dt: f32) {
+    pub fn update(&mut self, inputs: &InputState, dt: f32, assets: &mut AssetPool, audio: &AudioEngine) {
         self.seconds_since_last_tick += dt;
         if self.seconds_since_last_tick >= SECONDS_PER_TICK {
             // Advance one tick
             self.seconds_since_last_tick -= SECONDS_PER_TICK;
             // Perform tick actions
-            self.tick();
+            self.tick(assets);
         }
 
+        self.seconds_since_last_physics_step += dt;
+        let mut physics_steps = 0;
+        while self.seconds_since_last_physics_step >= SECONDS_PER_PHYSICS_STEP {
+            if physics_steps >= MAX_PHYSICS_STEPS_PER_FRAME {
+                self.seconds_since_last_physics_step %= SECONDS_PER_PHYSICS_STEP;
+                break;
+            }
+            self.seconds_since_last_physics_step -= SECONDS_PER_PHYSICS_STEP;
+            self.step_physics(inputs);
+            physics_steps += 1;
+        }
+        let alpha = self.seconds_since_last_physics_step / SECONDS_PER_PHYSICS_STEP;
+
         for entity in self.entities.values_mut() {
             entity.update(
                 dt,
-                inputs,
+                alpha,
                 &mut self.physics,
                 &mut self.entity_renderer,
                 &mut self.chunks,
@@ -225,16 +637,34 @@ impl<'world> World<'world> {
         }
         self.player.update(
             dt,
-            inputs,
+            alpha,
             &mut self.physics,
             &mut self.entity_renderer,
             &mut self.chunks,
             &mut self.particles,
         );
+        let drops: Vec<(Vector<i64, 2>, Item)> = self.entities
+            .values_mut()
+            .flat_map(|entity| entity.take_drops())
+            .collect();
+        for (block_position, item) in drops {
+            self.drop_item(block_position, item, assets);
+        }
+        self.update_dropped_items();
+        let removed_entities: Vec<Uuid> = self.entities
+            .values()
+            .filter(|entity| entity.is_removed())
+            .map(|entity| entity.uuid())
+            .collect();
+        for uuid in removed_entities {
+            self.destroy_entity(uuid);
+        }
 
-        self.camera.set_target(self.player.position());
+        self.camera.set_target(self.player.interpolated_position());
         self.camera.update(dt);
-        self.physics.step_simulation(dt);
+        self.handle_contact_events(assets, audio);
+        // Dropped items read their sensors' current overlaps, so nothing listens for sensor events yet
+        self.physics.drain_sensor_events();
         self.particles.update(dt);
 
         let target_sky_light = {
@@ -255,10 +685,55 @@ impl<'world> World<'world> {
         self.sky_light += (target_sky_light - self.sky_light) * dt.min(1.0);
     }
 
-    fn tick(&mut self) {
+    fn step_physics(&mut self, inputs: &InputState) {
+        for entity in self.entities.values_mut() {
+            entity.fixed_update(SECONDS_PER_PHYSICS_STEP, inputs, &mut self.physics, &mut self.chunks);
+        }
+        self.player.fixed_update(SECONDS_PER_PHYSICS_STEP, inputs, &mut self.physics, &mut self.chunks);
+
+        self.physics.step_simulation(SECONDS_PER_PHYSICS_STEP);
+    }
+
+    fn tick(&mut self, assets: &mut AssetPool) {
+        self.time.advance();
         self.entity_renderer.tick();
         self.block_preview.set_item_type(self.player.held_item().item_type());
         self.chunks.tick(self.player.position(), &mut self.physics);
+        self.process_block_updates(assets);
+
+        for emission in self.chunks.pipe_grid().take_emissions() {
+            // Spray the fluid out from the edge of the spout
+            let direction = emission.direction.map(|x| x as f32);
+            let position = emission.target.map(|x| x as f32 + 0.5) - direction.mul(0.5);
+            for _ in 0..emission.amount.div_ceil(2) {
+                self.particles.create_particle(ParticleInfo {
+                    position,
+                    velocity: direction.mul(4.0) + random_unit_vector().mul(0.75),
+                    color: emission.fluid.color(),
+                    size: 1.5,
+                    lifetime: 0.6,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    fn process_block_updates(&mut self, assets: &mut AssetPool) {
+        for position in self.chunks.take_block_updates() {
+            let Some(block) = self.chunks.with_block_at(position, Block::clone) else {
+                continue;
+            };
+            if block.block_type().affected_by_gravity() {
+                let below = position + Vector([0, -1]);
+                if self.chunks.with_block_at(below, Block::is_replaceable) == Some(true) {
+                    // Removing the block queues an update for the block above, so columns fall one at a time
+                    self.chunks.set_block_at(position, Block::new(&block::types::AIR, BlockSide::None), &mut self.physics);
+                    self.add_entity(Box::new(FallingBlock::new(generate_uuid(), block, position)), assets);
+                    continue;
+                }
+            }
+            self.chunks.update_block_at(position, &mut self.physics);
+        }
     }
 
     pub fn render(&mut self, assets: &AssetPool) {
@@ -266,6 +741,7 @@ impl<'world> World<'world> {
         assets.block_shaders().set_uniform("tex_atlas", assets.block_texture());
         assets.block_shaders().set_uniform("camera_view", self.camera.view());
         assets.block_shaders().set_uniform("camera_proj", self.camera.projection());
+        assets.block_shaders().set_uniform("sky_light_multiplier", &self.time.sky_light_multiplier());
         for mut chunk in self.chunks.iter_mut() {
             chunk.render(assets, &self.chunks);
         }
@@ -274,7 +750,15 @@ impl<'world> World<'world> {
         assets.default_shaders().set_uniform("camera_view", self.camera.view());
         assets.default_shaders().set_uniform("camera_proj", self.camera.projection());
         self.particles.render();
+        if let Some((block_position, progress)) = self.mining_progress() {
+            self.crack_overlay.render(block_position, progress, assets, &self.chunks);
+        }
         self.block_preview.render(assets, &self.chunks);
+        assets.block_texture().bind();
+        assets.block_shaders().bind();
+        for entity in self.entities.values_mut() {
+            entity.render_blocks(assets, &self.chunks);
+        }
 
         assets.entity_texture().bind();
         assets.default_shaders().set_uniform("tex_atlas", assets.entity_texture());
@@ -0,0 +1,128 @@
+pub struct DataWriter {
+    bytes: Vec<u8>,
+}
+
+impl DataWriter {
+    pub fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn write_i8(&mut self, value: i8) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_i32(&mut self, value: i32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_i64(&mut self, value: i64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_str(&mut self, value: &str) {
+        self.write_u32(value.len() as u32);
+        self.write_bytes(value.as_bytes());
+    }
+}
+
+impl Default for DataWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct DataReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> DataReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            position: 0,
+        }
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    pub fn read_bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+        let end = self.position + count;
+        if end > self.bytes.len() {
+            return Err(format!("unexpected end of data at byte {}", self.position));
+        }
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.read_bytes(N)?.try_into().unwrap())
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    pub fn read_i8(&mut self) -> Result<i8, String> {
+        Ok(i8::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, String> {
+        Ok(i32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_i64(&mut self) -> Result<i64, String> {
+        Ok(i64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_str(&mut self) -> Result<&'a str, String> {
+        let length = self.read_u32()? as usize;
+        let bytes = self.read_bytes(length)?;
+        std::str::from_utf8(bytes).map_err(|err| format!("invalid string data: {err}"))
+    }
+}
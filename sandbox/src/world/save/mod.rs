@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use json::JsonValue;
+use crate::world::block::{Chunk, ChunkLocation};
+
+const LEVEL_FILE_NAME: &str = "level.json";
+
+mod data;
+mod region;
+
+pub use data::*;
+pub use region::*;
+
+/// On-disk storage for a world, rooted at a world directory.
+/// Chunks are grouped into region files under `regions/`.
+pub struct WorldSave {
+    directory: PathBuf,
+    regions: BTreeMap<RegionLocation, RegionFile>,
+}
+
+impl WorldSave {
+    pub fn open(directory: impl AsRef<Path>) -> Result<Self, String> {
+        let directory = directory.as_ref().to_path_buf();
+        let regions_path = directory.join("regions");
+        std::fs::create_dir_all(&regions_path)
+            .map_err(|err| format!("failed to create world directory '{}': {err}", regions_path.display()))?;
+        Ok(Self {
+            directory,
+            regions: BTreeMap::new(),
+        })
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn read_level(&self) -> Result<Option<JsonValue>, String> {
+        let path = self.directory.join(LEVEL_FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let level_raw = std::fs::read_to_string(&path)
+            .map_err(|err| format!("failed to read level file '{}': {err}", path.display()))?;
+        json::parse(&level_raw)
+            .map(Some)
+            .map_err(|err| format!("failed to parse level file '{}': {err}", path.display()))
+    }
+
+    pub fn write_level(&self, level: &JsonValue) -> Result<(), String> {
+        let path = self.directory.join(LEVEL_FILE_NAME);
+        std::fs::write(&path, level.pretty(4))
+            .map_err(|err| format!("failed to write level file '{}': {err}", path.display()))
+    }
+
+    fn region_path(&self, location: RegionLocation) -> PathBuf {
+        self.directory
+            .join("regions")
+            .join(format!("r.{}.{}.dat", location.x(), location.y()))
+    }
+
+    fn get_region(&mut self, location: RegionLocation) -> Result<&mut RegionFile, String> {
+        if !self.regions.contains_key(&location) {
+            let region = RegionFile::open(self.region_path(location))?;
+            self.regions.insert(location, region);
+        }
+        Ok(self.regions.get_mut(&location).unwrap())
+    }
+
+    pub fn load_chunk(&mut self, location: ChunkLocation) -> Result<Option<Chunk>, String> {
+        let (region_location, index) = region_location_of(location);
+        let region = self.get_region(region_location)?;
+        let Some(data) = region.chunk_data(index) else {
+            return Ok(None);
+        };
+        let mut reader = DataReader::new(data);
+        Chunk::read_data(location, &mut reader)
+            .map(Some)
+            .map_err(|err| format!("invalid data for chunk {location:?}: {err}"))
+    }
+
+    pub fn save_chunk(&mut self, chunk: &Chunk) -> Result<(), String> {
+        let (region_location, index) = region_location_of(chunk.location());
+        let mut writer = DataWriter::new();
+        chunk.write_data(&mut writer);
+        self.get_region(region_location)?.set_chunk_data(index, writer.into_bytes().into_boxed_slice());
+        Ok(())
+    }
+
+    /// Write all modified regions to disk and release the cached ones.
+    pub fn flush(&mut self) -> Result<(), String> {
+        for region in self.regions.values_mut() {
+            if region.is_dirty() {
+                region.write()?;
+            }
+        }
+        self.regions.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use std::path::{Path, PathBuf};
+use innovus::tools::Vector;
+use crate::world::block::ChunkLocation;
+use super::*;
+
+/// Width and height of a region, measured in chunks.
+pub const REGION_SIZE: usize = 16;
+
+const REGION_MAGIC: &[u8; 4] = b"INVR";
+const REGION_FORMAT_VERSION: u32 = 1;
+
+pub type RegionLocation = Vector<i64, 2>;
+
+pub fn region_location_of(chunk_location: ChunkLocation) -> (RegionLocation, usize) {
+    let region_location = Vector([
+        chunk_location.x().div_euclid(REGION_SIZE as i64),
+        chunk_location.y().div_euclid(REGION_SIZE as i64),
+    ]);
+    let chunk_x = chunk_location.x().rem_euclid(REGION_SIZE as i64) as usize;
+    let chunk_y = chunk_location.y().rem_euclid(REGION_SIZE as i64) as usize;
+    (region_location, chunk_y * REGION_SIZE + chunk_x)
+}
+
+/// A group of `REGION_SIZE * REGION_SIZE` serialized chunks, stored together in a single file.
+pub struct RegionFile {
+    path: PathBuf,
+    chunks: Vec<Option<Box<[u8]>>>,
+    dirty: bool,
+}
+
+impl RegionFile {
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        let mut region = Self {
+            path,
+            chunks: vec![None; REGION_SIZE * REGION_SIZE],
+            dirty: false,
+        };
+        if region.path.exists() {
+            let bytes = std::fs::read(&region.path)
+                .map_err(|err| format!("failed to read region file '{}': {err}", region.path.display()))?;
+            region.parse(&bytes)
+                .map_err(|err| format!("invalid region file '{}': {err}", region.path.display()))?;
+        }
+        Ok(region)
+    }
+
+    fn parse(&mut self, bytes: &[u8]) -> Result<(), String> {
+        let mut reader = DataReader::new(bytes);
+        if reader.read_bytes(REGION_MAGIC.len())? != REGION_MAGIC {
+            return Err("missing region header".into());
+        }
+        let version = reader.read_u32()?;
+        if version != REGION_FORMAT_VERSION {
+            return Err(format!("unsupported region format version {version}"));
+        }
+        let chunk_count = reader.read_u32()?;
+        for _ in 0..chunk_count {
+            let index = reader.read_u16()? as usize;
+            let length = reader.read_u32()? as usize;
+            let data = reader.read_bytes(length)?;
+            let slot = self.chunks
+                .get_mut(index)
+                .ok_or_else(|| format!("chunk index {index} is out of range"))?;
+            *slot = Some(data.into());
+        }
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn chunk_data(&self, index: usize) -> Option<&[u8]> {
+        self.chunks[index].as_deref()
+    }
+
+    pub fn set_chunk_data(&mut self, index: usize, data: Box<[u8]>) {
+        self.chunks[index] = Some(data);
+        self.dirty = true;
+    }
+
+    pub fn write(&mut self) -> Result<(), String> {
+        let mut writer = DataWriter::new();
+        writer.write_bytes(REGION_MAGIC);
+        writer.write_u32(REGION_FORMAT_VERSION);
+        writer.write_u32(self.chunks.iter().flatten().count() as u32);
+        for (index, data) in self.chunks.iter().enumerate() {
+            if let Some(data) = data {
+                writer.write_u16(index as u16);
+                writer.write_u32(data.len() as u32);
+                writer.write_bytes(data);
+            }
+        }
+
+        // Write to a temporary file first so a crash mid-write can't corrupt the existing region
+        let temp_path = self.path.with_extension("tmp");
+        std::fs::write(&temp_path, writer.bytes())
+            .map_err(|err| format!("failed to write region file '{}': {err}", temp_path.display()))?;
+        std::fs::rename(&temp_path, &self.path)
+            .map_err(|err| format!("failed to write region file '{}': {err}", self.path.display()))?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_file_round_trip() {
+        let directory = std::env::temp_dir().join(format!("innovus-region-test-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("r.0.0.dat");
+
+        let mut region = RegionFile::open(path.clone()).unwrap();
+        region.set_chunk_data(0, vec![1, 2, 3].into_boxed_slice());
+        region.set_chunk_data(REGION_SIZE * REGION_SIZE - 1, vec![4; 300].into_boxed_slice());
+        assert!(region.is_dirty());
+        region.write().unwrap();
+        assert!(!region.is_dirty());
+
+        let loaded = RegionFile::open(path).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(loaded.chunk_data(0), Some(&[1, 2, 3][..]));
+        assert_eq!(loaded.chunk_data(1), None);
+        assert_eq!(loaded.chunk_data(REGION_SIZE * REGION_SIZE - 1), Some(&[4; 300][..]));
+    }
+
+    #[test]
+    fn region_location_of_negative_chunks() {
+        assert_eq!(region_location_of(Vector([-1, -1])), (Vector([-1, -1]), REGION_SIZE * REGION_SIZE - 1));
+        assert_eq!(region_location_of(Vector([16, 3])), (Vector([1, 0]), 3 * REGION_SIZE));
+    }
+}
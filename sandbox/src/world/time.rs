@@ -0,0 +1,176 @@
+use json::JsonValue;
+use innovus::tools::Vector;
+
+/// The number of ticks in a full day by default, which lasts 20 minutes.
+pub const DEFAULT_DAY_LENGTH: u64 = 24000;
+
+/// Sky color and sky light multiplier keyframes throughout the day. The time of day runs from 0
+/// at dawn to 1 at the next dawn, with noon at 0.25 and midnight at 0.75.
+const SKY_KEYFRAMES: &[(f32, Vector<f32, 3>, f32)] = &[
+    (0.0, Vector([0.95, 0.6, 0.45]), 0.6),
+    (0.08, Vector([0.6, 0.8, 1.0]), 1.0),
+    (0.42, Vector([0.6, 0.8, 1.0]), 1.0),
+    (0.5, Vector([0.9, 0.5, 0.35]), 0.6),
+    (0.58, Vector([0.05, 0.07, 0.15]), 0.2),
+    (0.92, Vector([0.05, 0.07, 0.15]), 0.2),
+    (1.0, Vector([0.95, 0.6, 0.45]), 0.6),
+];
+
+/// Named times of day which can be used in place of a tick count, as fractions of a day.
+pub const NAMED_TIMES: &[(&str, f32)] = &[
+    ("dawn", 0.0),
+    ("day", 0.08),
+    ("noon", 0.25),
+    ("dusk", 0.5),
+    ("night", 0.58),
+    ("midnight", 0.75),
+];
+
+#[derive(Clone, Debug)]
+pub struct WorldTime {
+    ticks: u64,
+    day_length: u64,
+}
+
+impl WorldTime {
+    pub fn new(ticks: u64, day_length: u64) -> Self {
+        Self {
+            ticks,
+            day_length: day_length.max(1),
+        }
+    }
+
+    pub fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let ticks = value["ticks"].as_u64()
+            .ok_or_else(|| format!("invalid world time: {}", value["ticks"]))?;
+        let day_length = value["day_length"].as_u64()
+            .ok_or_else(|| format!("invalid day length: {}", value["day_length"]))?;
+        Ok(Self::new(ticks, day_length))
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        json::object! {
+            ticks: self.ticks,
+            day_length: self.day_length,
+        }
+    }
+
+    /// The number of ticks since dawn on the first day.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn set_ticks(&mut self, ticks: u64) {
+        self.ticks = ticks;
+    }
+
+    /// Move forward by one tick. The tick count wraps around rather than overflowing, since it
+    /// can be set to anything with commands.
+    pub fn advance(&mut self) {
+        self.ticks = self.ticks.wrapping_add(1);
+    }
+
+    pub fn day_length(&self) -> u64 {
+        self.day_length
+    }
+
+    /// Change the length of a day, keeping the current day and time of day. Returns `false`
+    /// without changing anything if the tick count would overflow with the new length.
+    pub fn set_day_length(&mut self, day_length: u64) -> bool {
+        let day_length = day_length.max(1);
+        let time_of_day = self.time_of_day();
+        let ticks = self.day()
+            .checked_mul(day_length)
+            .and_then(|ticks| ticks.checked_add((time_of_day * day_length as f32) as u64));
+        let Some(ticks) = ticks else {
+            return false;
+        };
+        self.ticks = ticks;
+        self.day_length = day_length;
+        true
+    }
+
+    pub fn day(&self) -> u64 {
+        self.ticks / self.day_length
+    }
+
+    /// How far through the current day it is, from 0 at dawn up to (but not including) 1.
+    pub fn time_of_day(&self) -> f32 {
+        (self.ticks % self.day_length) as f32 / self.day_length as f32
+    }
+
+    /// Skip forward to the next time the given time of day comes around.
+    pub fn skip_to(&mut self, time_of_day: f32) {
+        let target = (time_of_day.rem_euclid(1.0) * self.day_length as f32) as u64;
+        let current = self.ticks % self.day_length;
+        let skipped = if target >= current { target - current } else { target + (self.day_length - current) };
+        self.ticks = self.ticks.wrapping_add(skipped);
+    }
+
+    fn sky_keyframe(&self) -> (Vector<f32, 3>, f32) {
+        let time_of_day = self.time_of_day();
+        let next_index = SKY_KEYFRAMES
+            .iter()
+            .position(|&(keyframe_time, ..)| keyframe_time > time_of_day)
+            .unwrap_or(SKY_KEYFRAMES.len() - 1);
+        let (start_time, start_color, start_light) = SKY_KEYFRAMES[next_index - 1];
+        let (end_time, end_color, end_light) = SKY_KEYFRAMES[next_index];
+        let t = (time_of_day - start_time) / (end_time - start_time);
+        (start_color.lerp(end_color, t), start_light + (end_light - start_light) * t)
+    }
+
+    pub fn sky_color(&self) -> Vector<f32, 3> {
+        self.sky_keyframe().0
+    }
+
+    /// How bright sky light is at this time of day, from 0 to 1.
+    pub fn sky_light_multiplier(&self) -> f32 {
+        self.sky_keyframe().1
+    }
+}
+
+impl Default for WorldTime {
+    fn default() -> Self {
+        // Start new worlds in the morning
+        Self::new((NAMED_TIMES[1].1 * DEFAULT_DAY_LENGTH as f32) as u64, DEFAULT_DAY_LENGTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_length_keeps_day_and_time_of_day() {
+        let mut time = WorldTime::new(3 * 1000 + 250, 1000);
+        assert!(time.set_day_length(2000));
+        assert_eq!(time.day(), 3);
+        assert_eq!(time.ticks(), 3 * 2000 + 500);
+    }
+
+    #[test]
+    fn ticks_wrap_around_at_the_maximum() {
+        let mut time = WorldTime::new(u64::MAX, DEFAULT_DAY_LENGTH);
+        time.advance();
+        assert_eq!(time.ticks(), 0);
+
+        let mut time = WorldTime::new(u64::MAX, DEFAULT_DAY_LENGTH);
+        let time_of_day = time.time_of_day();
+        time.skip_to(0.0);
+        assert!(time.ticks() < DEFAULT_DAY_LENGTH);
+        assert!(time_of_day > 0.0);
+
+        let mut time = WorldTime::new(u64::MAX - 1, u64::MAX);
+        time.skip_to(0.0);
+        assert_eq!(time.ticks(), u64::MAX);
+    }
+
+    #[test]
+    fn day_length_rejects_overflowing_ticks() {
+        let mut time = WorldTime::new(u64::MAX / 2, 1);
+        assert!(!time.set_day_length(3));
+        assert_eq!(time.ticks(), u64::MAX / 2);
+        assert_eq!(time.day_length(), 1);
+        assert!(time.set_day_length(2));
+    }
+}